@@ -0,0 +1,358 @@
+//! Working out what two Pokemon breed into, for both the menus and the
+//! command line.
+//!
+
+use serde::{Deserialize, Serialize};
+use strum::{EnumIter, EnumString, IntoEnumIterator, IntoStaticStr};
+
+use crate::locale::Locale;
+use crate::sqlite::{
+    Database, Goal, OwnedPokemon, Project, get_egg_moves, get_groups, get_pokemon_id,
+};
+
+/// Ditto breeds with anything, so the other parent decides the egg.
+pub const DITTO_ID: i64 = 132;
+/// The highest an individual value can be.
+pub const MAX_IV: u8 = 31;
+
+#[derive(
+    Serialize,
+    Deserialize,
+    EnumIter,
+    EnumString,
+    IntoStaticStr,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Hash,
+    Debug,
+)]
+pub enum Gender {
+    #[default]
+    Male,
+    Female,
+    Genderless,
+}
+
+#[derive(
+    Serialize,
+    Deserialize,
+    EnumIter,
+    EnumString,
+    IntoStaticStr,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Hash,
+    Debug,
+)]
+pub enum Nature {
+    #[default]
+    Hardy,
+    Lonely,
+    Brave,
+    Adamant,
+    Naughty,
+    Bold,
+    Docile,
+    Relaxed,
+    Impish,
+    Lax,
+    Timid,
+    Hasty,
+    Serious,
+    Jolly,
+    Naive,
+    Modest,
+    Mild,
+    Quiet,
+    Bashful,
+    Rash,
+    Calm,
+    Gentle,
+    Sassy,
+    Careful,
+    Quirky,
+}
+
+/// The stats individual values are for, in the order they are written.
+#[derive(
+    Serialize, Deserialize, EnumIter, IntoStaticStr, Clone, Copy, PartialEq, Eq, Hash, Debug,
+)]
+pub enum Stat {
+    Hp,
+    Attack,
+    Defense,
+    SpecialAttack,
+    SpecialDefense,
+    Speed,
+}
+
+impl Stat {
+    /// The [`Locale`] key of its short name.
+    pub fn key(self) -> &'static str {
+        match self {
+            Stat::Hp => "projects.stat.hp",
+            Stat::Attack => "projects.stat.attack",
+            Stat::Defense => "projects.stat.defense",
+            Stat::SpecialAttack => "projects.stat.special_attack",
+            Stat::SpecialDefense => "projects.stat.special_defense",
+            Stat::Speed => "projects.stat.speed",
+        }
+    }
+}
+
+/// Individual values, from 0 to [`MAX_IV`], by [`Stat`].
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash, Debug)]
+pub struct Ivs(pub [u8; 6]);
+
+impl Ivs {
+    pub fn get(&self, stat: Stat) -> u8 {
+        self.0[stat as usize]
+    }
+
+    pub fn set(&mut self, stat: Stat, iv: u8) {
+        self.0[stat as usize] = iv;
+    }
+
+    /// How many stats are at [`MAX_IV`].
+    pub fn perfect(&self) -> usize {
+        self.0.iter().filter(|iv| **iv == MAX_IV).count()
+    }
+
+    /// Reads IVs written like `31/31/31/0/31/31`.
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut ivs = Ivs::default();
+        let mut parts = text.split('/').map(str::trim);
+
+        for stat in Stat::iter() {
+            let iv = parts.next()?.parse::<u8>().ok()?;
+            if iv > MAX_IV {
+                return None;
+            }
+            ivs.set(stat, iv);
+        }
+
+        parts.next().is_none().then_some(ivs)
+    }
+}
+
+impl std::fmt::Display for Ivs {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let ivs = self.0.map(|iv| iv.to_string());
+        write!(f, "{}", ivs.join("/"))
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Parent {
+    /// The name as it was given.
+    pub name: String,
+    pub pokemon_id: i64,
+    pub egg_groups: Vec<String>,
+    /// The individual from the boxes, if it is one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owned: Option<OwnedPokemon>,
+}
+
+/// What hatches from the egg.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Offspring {
+    /// The name of the parent whose species hatches, as it was given.
+    pub species: String,
+    pub egg_moves: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct BreedReport {
+    /// `None` if there is no Pokemon by that name.
+    pub mother: Option<Parent>,
+    pub other: Option<Parent>,
+    /// `None` if the parents don't share an egg group.
+    pub offspring: Option<Offspring>,
+}
+
+impl BreedReport {
+    /// The egg groups of a parent, and what matters about it for breeding if
+    /// it is from the boxes, as shown under its name.
+    pub fn parent_text(parent: Option<&Parent>, locale: &Locale) -> String {
+        match parent {
+            None => locale.get("common.not_found").into(),
+            Some(parent) => {
+                let groups = locale.format(
+                    "breed.egg_groups",
+                    &[&list_text(&parent.egg_groups, locale)],
+                );
+                match &parent.owned {
+                    Some(owned) => format!("{groups}\n{}", owned.summary()),
+                    None => groups,
+                }
+            }
+        }
+    }
+
+    /// What hatches, as shown under the result.
+    pub fn offspring_text(&self, locale: &Locale) -> String {
+        match &self.offspring {
+            None => locale.get("breed.bad_match").into(),
+            Some(offspring) => locale.format(
+                "breed.egg_moves",
+                &[&offspring.species, &list_text(&offspring.egg_moves, locale)],
+            ),
+        }
+    }
+}
+
+fn list_text(items: &[String], locale: &Locale) -> String {
+    if items.is_empty() {
+        locale.get("common.none").into()
+    } else {
+        items.join(", ")
+    }
+}
+
+/// Breeds two Pokemon, named in any language, naming the egg moves in `language`.
+pub fn breed(db: &Database, mother: &str, other: &str, language: &str) -> BreedReport {
+    let parent = |name: &str| {
+        get_pokemon_id(db, name).map(|pokemon_id| Parent {
+            name: name.into(),
+            pokemon_id,
+            egg_groups: get_groups(db, name),
+            owned: None,
+        })
+    };
+    let mother_parent = parent(mother);
+    let other_parent = parent(other);
+
+    let groups = |parent: &Option<Parent>| {
+        parent
+            .as_ref()
+            .map_or(&[][..], |parent| parent.egg_groups.as_slice())
+    };
+    let any_overlap = groups(&mother_parent)
+        .iter()
+        .any(|g| groups(&other_parent).iter().any(|y| y == g));
+
+    let offspring = any_overlap.then(|| {
+        let species = if mother_parent.as_ref().map(|p| p.pokemon_id) == Some(DITTO_ID) {
+            other
+        } else {
+            mother
+        };
+
+        Offspring {
+            species: species.into(),
+            egg_moves: get_egg_moves(db, species, language),
+        }
+    });
+
+    BreedReport {
+        mother: mother_parent,
+        other: other_parent,
+        offspring,
+    }
+}
+
+/// Two Pokemon from the boxes to breed next for a project.
+#[derive(Serialize, Clone, Debug)]
+pub struct Pairing {
+    pub mother: OwnedPokemon,
+    pub other: OwnedPokemon,
+    /// The project's goals the parents can pass on between them.
+    pub passed_on: Vec<Goal>,
+}
+
+/// Whether `owned` can pass `goal` on to an egg, holding the right item.
+/// Only the mother passes on her ball, and the parent of the egg's species
+/// its ability.
+fn can_pass_on(owned: &OwnedPokemon, goal: &Goal, is_mother: bool, is_species: bool) -> bool {
+    match goal {
+        Goal::Ball(_) if !is_mother => false,
+        Goal::Ability(_) if !is_species => false,
+        _ => goal.met_by(
+            owned.nature,
+            owned.ability.as_deref(),
+            &owned.ball,
+            &owned.ivs,
+            &owned.moves,
+        ),
+    }
+}
+
+/// The pair out of `candidates` that breeds the project's species and can
+/// pass on the most of its goals, or `None` if no pair breeds it.
+pub fn propose_pairing(
+    db: &Database,
+    project: &Project,
+    candidates: &[OwnedPokemon],
+    language: &str,
+) -> Option<Pairing> {
+    let goals = project.goals();
+    let mut best: Option<(Pairing, u32)> = None;
+
+    for mother in candidates {
+        for other in candidates {
+            if mother.owned_id == other.owned_id {
+                continue;
+            }
+
+            let ditto = mother.pokemon_id == DITTO_ID || other.pokemon_id == DITTO_ID;
+            if !ditto && (mother.gender != Gender::Female || other.gender != Gender::Male) {
+                continue;
+            }
+
+            let report = breed(db, &mother.species, &other.species, language);
+            let hatches = report
+                .offspring
+                .and_then(|offspring| get_pokemon_id(db, &offspring.species));
+            if hatches != Some(project.pokemon_id) {
+                continue;
+            }
+
+            let passed_on = goals
+                .iter()
+                .filter(|goal| {
+                    [(mother, true), (other, false)]
+                        .iter()
+                        .any(|(parent, is_mother)| {
+                            can_pass_on(
+                                parent,
+                                goal,
+                                *is_mother,
+                                parent.pokemon_id == project.pokemon_id,
+                            )
+                        })
+                })
+                .cloned()
+                .collect::<Vec<_>>();
+            // Ties go to the pair with the better IVs overall.
+            let iv_total = mother
+                .ivs
+                .0
+                .iter()
+                .chain(&other.ivs.0)
+                .map(|iv| *iv as u32)
+                .sum();
+
+            let better = best.as_ref().is_none_or(|(pairing, best_total)| {
+                (passed_on.len(), iv_total) > (pairing.passed_on.len(), *best_total)
+            });
+            if better {
+                best = Some((
+                    Pairing {
+                        mother: mother.clone(),
+                        other: other.clone(),
+                        passed_on,
+                    },
+                    iv_total,
+                ));
+            }
+        }
+    }
+
+    best.map(|(pairing, _)| pairing)
+}
@@ -0,0 +1,360 @@
+//! The command line, for querying the database from scripts without a window.
+//!
+//! Without a command the GUI is started instead.
+
+use std::path::PathBuf;
+
+use bevy::app::AppExit;
+use serde_json::json;
+
+use crate::breeding::{BreedReport, breed};
+use crate::locale::{Language, Locale};
+use crate::planner::Item;
+use crate::settings::Settings;
+use crate::simulator::{MIN_GENERATION, SimConfig, simulate};
+use crate::sqlite::{Database, exists, find_owned, get_all_marks, get_pokemon_compatible};
+
+/// The widest a bar of a histogram is printed.
+const BAR_WIDTH: u32 = 40;
+
+pub const USAGE: &str = "\
+Usage: tcss445project [COMMAND] [OPTIONS]
+
+Starts the GUI when no command is given.
+
+Commands:
+  breed <mother> <other>  What two Pokemon breed into
+  search <pokemon>        Every Pokemon that can breed with this one
+  simulate <mother> <other>
+                          Hatch eggs at random from two Pokemon in the boxes
+  export                  Print the starred and tagged species as JSON
+  help                    Show this message
+
+Options:
+  --json                  Print JSON instead of text
+  --language <code>       Language of names and text: en, fr or de
+  --database <path>       Database file to use instead of the one in the settings
+
+Simulation options:
+  --eggs <count>          How many eggs to hatch, 1000 by default
+  --seed <number>         Seed for the eggs, the same seed hatches the same eggs
+  --generation <number>   Breeding rules to follow, the settings' by default
+  --mother-item <item>    Item the mother holds, like \"Destiny Knot\"
+  --other-item <item>     Item the other parent holds
+  --masuda                The parents are from games in different languages
+  --shiny-charm           The Shiny Charm is in the bag";
+
+#[derive(Debug)]
+pub enum Command {
+    Breed {
+        mother: String,
+        other: String,
+    },
+    Search {
+        pokemon: String,
+    },
+    Simulate {
+        mother: String,
+        other: String,
+        config: SimConfig,
+        /// `None` to follow the generation in the settings.
+        generation: Option<u8>,
+    },
+    Export,
+    Help,
+}
+
+#[derive(Debug)]
+pub struct Cli {
+    pub command: Command,
+    pub json: bool,
+    pub language: Option<Language>,
+    pub database: Option<PathBuf>,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum CliError {
+    #[error("unknown command: {0}")]
+    UnknownCommand(String),
+    #[error("unknown option: {0}")]
+    UnknownOption(String),
+    #[error("{0} needs a value")]
+    MissingValue(&'static str),
+    #[error("unknown language: {0}")]
+    UnknownLanguage(String),
+    #[error("unknown item: {0}")]
+    UnknownItem(String),
+    #[error("{option} needs a number, not {value}")]
+    NotANumber { option: &'static str, value: String },
+    #[error("{command} takes {expected} arguments, but was given {given}")]
+    WrongArguments {
+        command: &'static str,
+        expected: usize,
+        given: usize,
+    },
+}
+
+impl Cli {
+    /// Reads the arguments after the program name, or `None` if there are
+    /// none and the GUI should start.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Option<Self>, CliError> {
+        let mut args = args.into_iter();
+        let Some(command) = args.next() else {
+            return Ok(None);
+        };
+
+        let mut json = false;
+        let mut language = None;
+        let mut database = None;
+        let mut config = SimConfig::default();
+        let mut generation = None;
+        let mut positional = Vec::new();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--json" => json = true,
+                "--language" => {
+                    let code = args.next().ok_or(CliError::MissingValue("--language"))?;
+                    language =
+                        Some(Language::from_code(&code).ok_or(CliError::UnknownLanguage(code))?);
+                }
+                "--database" => {
+                    database = Some(
+                        args.next()
+                            .ok_or(CliError::MissingValue("--database"))?
+                            .into(),
+                    );
+                }
+                "--eggs" => config.eggs = number("--eggs", args.next())?,
+                "--seed" => config.seed = number("--seed", args.next())?,
+                "--generation" => generation = Some(number("--generation", args.next())?),
+                "--mother-item" => config.mother_item = Some(item("--mother-item", args.next())?),
+                "--other-item" => config.other_item = Some(item("--other-item", args.next())?),
+                "--masuda" => config.masuda = true,
+                "--shiny-charm" => config.shiny_charm = true,
+                option if option.starts_with("--") => {
+                    return Err(CliError::UnknownOption(arg));
+                }
+                _ => positional.push(arg),
+            }
+        }
+
+        let arguments = |command, expected| {
+            if positional.len() == expected {
+                Ok(())
+            } else {
+                Err(CliError::WrongArguments {
+                    command,
+                    expected,
+                    given: positional.len(),
+                })
+            }
+        };
+
+        let command = match command.as_str() {
+            "breed" => {
+                arguments("breed", 2)?;
+                let [mother, other] = <[String; 2]>::try_from(positional).unwrap();
+                Command::Breed { mother, other }
+            }
+            "search" => {
+                arguments("search", 1)?;
+                Command::Search {
+                    pokemon: positional.remove(0),
+                }
+            }
+            "simulate" => {
+                arguments("simulate", 2)?;
+                let [mother, other] = <[String; 2]>::try_from(positional).unwrap();
+                Command::Simulate {
+                    mother,
+                    other,
+                    config,
+                    generation,
+                }
+            }
+            "export" => {
+                arguments("export", 0)?;
+                Command::Export
+            }
+            "help" | "--help" | "-h" => Command::Help,
+            _ => return Err(CliError::UnknownCommand(command)),
+        };
+
+        Ok(Some(Self {
+            command,
+            json,
+            language,
+            database,
+        }))
+    }
+
+    /// Runs the command, printing its results.
+    pub fn run(self) -> AppExit {
+        let settings = Settings::load(&Settings::path());
+        let locale = Locale::new(self.language.unwrap_or(settings.language));
+        let path = self.database.or(settings.database_path);
+
+        let db = match Database::open(path.as_deref()) {
+            Ok(db) => db,
+            Err(e) => {
+                eprintln!("Failed to open database: {e}");
+                return AppExit::error();
+            }
+        };
+
+        let found = match &self.command {
+            Command::Breed { mother, other } => {
+                let report = breed(&db, mother, other, locale.code());
+
+                if self.json {
+                    println!("{}", serde_json::to_string_pretty(&report).unwrap());
+                } else {
+                    for (name, parent) in [(mother, &report.mother), (other, &report.other)] {
+                        println!(
+                            "{name}: {}",
+                            BreedReport::parent_text(parent.as_ref(), &locale)
+                        );
+                    }
+                    println!("{}", report.offspring_text(&locale));
+                }
+
+                report.mother.is_some() && report.other.is_some()
+            }
+            Command::Search { pokemon } => {
+                let found = exists(&db, pokemon);
+                let compatible = get_pokemon_compatible(&db, pokemon, locale.code());
+
+                if self.json {
+                    let results = json!({
+                        "pokemon": pokemon,
+                        "found": found,
+                        "compatible": compatible,
+                    });
+                    println!("{}", serde_json::to_string_pretty(&results).unwrap());
+                } else if !found {
+                    println!("{}", locale.get("common.not_found"));
+                } else {
+                    println!(
+                        "{}",
+                        locale.format("search.breedable", &[&compatible.len()])
+                    );
+                    for entry in &compatible {
+                        let types = match &entry.secondary_type {
+                            Some(secondary) => format!("{}/{secondary}", entry.primary_type),
+                            None => entry.primary_type.clone(),
+                        };
+                        println!("#{} {} ({types})", entry.pokemon_id, entry.name);
+                    }
+                }
+
+                found
+            }
+            Command::Simulate {
+                mother,
+                other,
+                config,
+                generation,
+            } => {
+                // The default can be from before IVs, the GUI starts from
+                // the first generation with them too.
+                let config = SimConfig {
+                    generation: generation
+                        .unwrap_or(settings.default_generation.max(MIN_GENERATION)),
+                    ..config.clone()
+                };
+
+                let parents = [mother, other].map(|name| {
+                    let owned = find_owned(&db, name);
+                    if owned.is_none() {
+                        eprintln!("{}", locale.format("projects.not_owned", &[name]));
+                    }
+                    owned
+                });
+                let [Some(mother), Some(other)] = parents else {
+                    return AppExit::error();
+                };
+
+                match simulate(&db, &mother, &other, &config) {
+                    Err(e) => {
+                        eprintln!("{e}");
+                        false
+                    }
+                    Ok(simulation) if self.json => {
+                        println!("{}", serde_json::to_string_pretty(&simulation).unwrap());
+                        true
+                    }
+                    Ok(simulation) => {
+                        println!(
+                            "{}",
+                            locale.format(
+                                "sim.summary",
+                                &[&config.eggs, &simulation.species, &config.generation]
+                            )
+                        );
+                        for histogram in simulation.histograms(&locale) {
+                            println!("\n{}", histogram.title);
+                            print_bars(&histogram.bars, config.eggs);
+                        }
+                        true
+                    }
+                }
+            }
+            Command::Export => {
+                let mut marks = get_all_marks(&db).into_values().collect::<Vec<_>>();
+                marks.sort_by_key(|marks| marks.pokemon_id);
+                println!("{}", serde_json::to_string_pretty(&marks).unwrap());
+                true
+            }
+            Command::Help => {
+                println!("{USAGE}");
+                true
+            }
+        };
+
+        if found {
+            AppExit::Success
+        } else {
+            AppExit::error()
+        }
+    }
+}
+
+/// Reads the value of a numeric option.
+fn number<T: std::str::FromStr>(
+    option: &'static str,
+    value: Option<String>,
+) -> Result<T, CliError> {
+    let value = value.ok_or(CliError::MissingValue(option))?;
+    value
+        .parse()
+        .map_err(|_| CliError::NotANumber { option, value })
+}
+
+/// Reads the value of an item option.
+fn item(option: &'static str, value: Option<String>) -> Result<Item, CliError> {
+    let value = value.ok_or(CliError::MissingValue(option))?;
+    Item::from_name(&value).ok_or(CliError::UnknownItem(value))
+}
+
+/// Prints a histogram's bars, scaled to the most common, with how many of
+/// the `total` eggs each is.
+fn print_bars(bars: &[(String, u32)], total: u32) {
+    let width = bars.iter().map(|(label, _)| label.chars().count()).max();
+    let most = bars
+        .iter()
+        .map(|(_, count)| *count)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+
+    for (label, count) in bars {
+        println!(
+            "{label:<width$}  {:<bar$}  {count} ({:.1}%)",
+            "#".repeat((*count as u64 * BAR_WIDTH as u64 / most as u64) as usize),
+            *count as f32 * 100.0 / total.max(1) as f32,
+            width = width.unwrap_or(0),
+            bar = BAR_WIDTH as usize,
+        );
+    }
+}
@@ -0,0 +1,214 @@
+//! Comparing a few species side by side, to pick a breeding partner from
+//! them: their types, egg groups, base stats, abilities and egg moves, and
+//! which of them can breed with each other.
+
+use serde::Serialize;
+use strum::IntoEnumIterator;
+
+use crate::breeding::Stat;
+use crate::locale::Locale;
+use crate::sqlite::{
+    Database, get_abilities, get_base_stats, get_egg_moves, get_groups, get_pokemon_entry,
+};
+
+pub const MIN_SPECIES: usize = 2;
+pub const MAX_SPECIES: usize = 4;
+
+#[derive(thiserror::Error, Debug)]
+pub enum CompareError {
+    #[error("compare {MIN_SPECIES} to {MAX_SPECIES} Pokemon, not {0}")]
+    Count(usize),
+    #[error("unknown Pokemon: {0}")]
+    Unknown(String),
+}
+
+/// What is compared of one species.
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct SpeciesSheet {
+    pub pokemon_id: i64,
+    pub name: String,
+    pub types: Vec<String>,
+    pub egg_groups: Vec<String>,
+    /// By [`Stat`], `None` if they aren't known.
+    pub base_stats: Option<[u8; 6]>,
+    pub abilities: Vec<String>,
+    pub egg_moves: Vec<String>,
+}
+
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct Comparison {
+    pub species: Vec<SpeciesSheet>,
+}
+
+/// What a line of the comparison holds, for showing it.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RowKind {
+    /// The types of each species, joined like `Grass/Poison`.
+    Types,
+    Text,
+    Stat,
+}
+
+/// A line of the comparison, with a cell for each species.
+#[derive(Clone, PartialEq, Debug)]
+pub struct CompareRow {
+    pub title: String,
+    pub kind: RowKind,
+    pub cells: Vec<String>,
+    /// How much each cell stands out from the others, from 0 to 1, or
+    /// `None` if all the cells are the same.
+    pub heat: Option<Vec<f32>>,
+}
+
+impl Comparison {
+    /// Whether two of the species share an egg group.
+    pub fn compatible(&self, a: usize, b: usize) -> bool {
+        let groups = |i: usize| &self.species[i].egg_groups;
+        groups(a).iter().any(|group| groups(b).contains(group))
+    }
+
+    pub fn rows(&self, locale: &Locale) -> Vec<CompareRow> {
+        let none = || locale.get("common.none").to_string();
+        let list = |items: &[String]| {
+            if items.is_empty() {
+                none()
+            } else {
+                items.join(", ")
+            }
+        };
+
+        let mut rows = vec![
+            CompareRow {
+                kind: RowKind::Types,
+                ..text_row(
+                    locale.get("compare.types"),
+                    self.species.iter().map(|sheet| sheet.types.join("/")),
+                )
+            },
+            text_row(
+                locale.get("compare.egg_groups"),
+                self.species.iter().map(|sheet| list(&sheet.egg_groups)),
+            ),
+        ];
+
+        for stat in Stat::iter() {
+            let values = self
+                .species
+                .iter()
+                .map(|sheet| sheet.base_stats.map(|stats| stats[stat as usize]))
+                .collect::<Vec<_>>();
+            rows.push(stat_row(locale.get(stat.key()), &values, none()));
+        }
+
+        let total = self
+            .species
+            .iter()
+            .map(|sheet| {
+                sheet
+                    .base_stats
+                    .map(|stats| stats.iter().map(|stat| *stat as u16).sum::<u16>())
+            })
+            .collect::<Vec<_>>();
+        rows.push(stat_row(locale.get("compare.total"), &total, none()));
+
+        rows.push(text_row(
+            locale.get("compare.abilities"),
+            self.species.iter().map(|sheet| list(&sheet.abilities)),
+        ));
+        rows.push(text_row(
+            locale.get("compare.egg_moves"),
+            self.species.iter().map(|sheet| list(&sheet.egg_moves)),
+        ));
+        rows.push(text_row(
+            locale.get("compare.breeds_with"),
+            (0..self.species.len()).map(|a| {
+                let partners = (0..self.species.len())
+                    .filter(|b| *b != a && self.compatible(a, *b))
+                    .map(|b| self.species[b].name.clone())
+                    .collect::<Vec<_>>();
+                list(&partners)
+            }),
+        ));
+
+        rows
+    }
+}
+
+/// A row whose cells differ or not, with every cell standing out if they do.
+fn text_row(title: &str, cells: impl Iterator<Item = String>) -> CompareRow {
+    let cells = cells.collect::<Vec<_>>();
+    let heat = cells
+        .iter()
+        .any(|cell| *cell != cells[0])
+        .then(|| vec![1.0; cells.len()]);
+
+    CompareRow {
+        title: title.into(),
+        kind: RowKind::Text,
+        cells,
+        heat,
+    }
+}
+
+/// A row of numbers, where the higher ones stand out more.
+fn stat_row<T: Into<f32> + Copy + PartialEq + ToString>(
+    title: &str,
+    values: &[Option<T>],
+    unknown: String,
+) -> CompareRow {
+    let known = values
+        .iter()
+        .flatten()
+        .map(|value| Into::<f32>::into(*value));
+    let least = known.clone().fold(f32::INFINITY, f32::min);
+    let most = known.fold(f32::NEG_INFINITY, f32::max);
+
+    let heat = values.iter().any(|value| *value != values[0]).then(|| {
+        values
+            .iter()
+            .map(|value| match value {
+                Some(value) if most > least => (Into::<f32>::into(*value) - least) / (most - least),
+                _ => 0.0,
+            })
+            .collect()
+    });
+
+    CompareRow {
+        title: title.into(),
+        kind: RowKind::Stat,
+        cells: values
+            .iter()
+            .map(|value| value.map_or(unknown.clone(), |value| value.to_string()))
+            .collect(),
+        heat,
+    }
+}
+
+/// Looks up the species named, in any language, naming them and their egg
+/// moves in `language`.
+pub fn compare(db: &Database, names: &[&str], language: &str) -> Result<Comparison, CompareError> {
+    if !(MIN_SPECIES..=MAX_SPECIES).contains(&names.len()) {
+        return Err(CompareError::Count(names.len()));
+    }
+
+    let species = names
+        .iter()
+        .map(|name| {
+            let entry = get_pokemon_entry(db, name, language)
+                .ok_or_else(|| CompareError::Unknown(name.to_string()))?;
+            Ok(SpeciesSheet {
+                pokemon_id: entry.pokemon_id,
+                types: std::iter::once(entry.primary_type)
+                    .chain(entry.secondary_type)
+                    .collect(),
+                name: entry.name,
+                egg_groups: get_groups(db, name),
+                base_stats: get_base_stats(db, name),
+                abilities: get_abilities(db, name),
+                egg_moves: get_egg_moves(db, name, language),
+            })
+        })
+        .collect::<Result<Vec<_>, CompareError>>()?;
+
+    Ok(Comparison { species })
+}
@@ -0,0 +1,162 @@
+//! Translations of the UI, picked by the language in the settings.
+//!
+//! Each language has a string table in `assets/locale`, mapping keys like
+//! `"breed.mother"` to text. Pokemon and move names are translated in the
+//! database instead, through the `pokemon_name` and `move_name` tables.
+
+use std::collections::HashMap;
+use std::fmt::Display;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use strum::{EnumIter, IntoEnumIterator};
+
+use crate::settings::{ApplySettings, Settings};
+
+#[derive(Serialize, Deserialize, EnumIter, Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub enum Language {
+    #[default]
+    English,
+    French,
+    German,
+}
+
+impl Language {
+    /// The code names are stored under in the database.
+    pub fn code(self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::French => "fr",
+            Language::German => "de",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        Language::iter().find(|language| language.code().eq_ignore_ascii_case(code))
+    }
+
+    /// The name of the language, in itself.
+    pub fn name(self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::French => "Français",
+            Language::German => "Deutsch",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        Language::iter()
+            .cycle()
+            .skip_while(|language| *language != self)
+            .nth(1)
+            .unwrap()
+    }
+
+    fn table(self) -> &'static str {
+        match self {
+            Language::English => include_str!("../assets/locale/en.ron"),
+            Language::French => include_str!("../assets/locale/fr.ron"),
+            Language::German => include_str!("../assets/locale/de.ron"),
+        }
+    }
+
+    fn strings(self) -> HashMap<String, String> {
+        ron::from_str(self.table())
+            .inspect_err(|e| error!("Failed to parse {} strings: {e}", self.name()))
+            .unwrap_or_default()
+    }
+}
+
+/// The UI text of the current language.
+#[derive(Resource)]
+pub struct Locale {
+    pub language: Language,
+    strings: HashMap<String, String>,
+    /// English, for anything missing a translation.
+    fallback: HashMap<String, String>,
+}
+
+impl Locale {
+    pub fn new(language: Language) -> Self {
+        Self {
+            language,
+            strings: language.strings(),
+            fallback: Language::English.strings(),
+        }
+    }
+
+    /// The text for `key`, or the key itself if no language has it.
+    pub fn get<'a>(&'a self, key: &'a str) -> &'a str {
+        self.strings
+            .get(key)
+            .or_else(|| self.fallback.get(key))
+            .map_or(key, String::as_str)
+    }
+
+    /// The text for `key`, with `{0}`, `{1}`, ... replaced by `args`.
+    pub fn format(&self, key: &str, args: &[&dyn Display]) -> String {
+        args.iter()
+            .enumerate()
+            .fold(self.get(key).to_string(), |text, (i, arg)| {
+                text.replace(&format!("{{{i}}}"), &arg.to_string())
+            })
+    }
+
+    /// The database code of the current language.
+    pub fn code(&self) -> &'static str {
+        self.language.code()
+    }
+}
+
+/// Text that is the translation of this key, kept up to date with the language.
+#[derive(Component, Clone, Copy, Debug)]
+#[require(Text)]
+pub struct Localized(pub &'static str);
+
+pub struct LocalePlugin;
+
+impl Plugin for LocalePlugin {
+    fn build(&self, app: &mut App) {
+        let language = app
+            .world()
+            .get_resource::<Settings>()
+            .map(|settings| settings.language)
+            .unwrap_or_default();
+
+        app.insert_resource(Locale::new(language))
+            .add_observer(localized_on_add)
+            .add_systems(
+                Update,
+                (
+                    select_language
+                        .in_set(ApplySettings)
+                        .run_if(resource_changed::<Settings>),
+                    retranslate
+                        .after(ApplySettings)
+                        .run_if(resource_changed::<Locale>),
+                ),
+            );
+    }
+}
+
+fn localized_on_add(
+    trigger: Trigger<OnAdd, Localized>,
+    mut texts: Query<(&mut Text, &Localized)>,
+    locale: Res<Locale>,
+) {
+    if let Ok((mut text, Localized(key))) = texts.get_mut(trigger.target()) {
+        text.0 = locale.get(key).into();
+    }
+}
+
+fn select_language(settings: Res<Settings>, mut locale: ResMut<Locale>) {
+    if locale.language != settings.language {
+        *locale = Locale::new(settings.language);
+    }
+}
+
+fn retranslate(locale: Res<Locale>, mut texts: Query<(&mut Text, &Localized)>) {
+    for (mut text, Localized(key)) in &mut texts {
+        text.0 = locale.get(key).into();
+    }
+}
@@ -1,12 +1,25 @@
+mod breeding;
+mod cli;
+mod compare;
+mod locale;
 mod menu;
+mod planner;
+mod settings;
+mod simulator;
 mod sqlite;
 
-use menu::MenuPlugin;
+use cli::{Cli, USAGE};
+use locale::LocalePlugin;
+use menu::{MenuPlugin, register_sprite_source};
+use settings::SettingsPlugin;
 use sqlite::DatabasePlugin;
 
 pub mod prelude {
     pub use bevy::prelude::*;
 
+    pub use crate::breeding::*;
+    pub use crate::locale::*;
+    pub use crate::settings::*;
     pub use crate::sqlite::*;
 
     #[cfg(feature = "debug")]
@@ -29,9 +42,22 @@ macro_rules! embed_asset {
         );
     }};
 }
-fn main() {
+fn main() -> AppExit {
+    // A command runs headless, without Bevy, and no command starts the GUI.
+    match Cli::parse(std::env::args().skip(1)) {
+        Ok(Some(cli)) => return cli.run(),
+        Ok(None) => {}
+        Err(e) => {
+            eprintln!("{e}\n\n{USAGE}");
+            return AppExit::error();
+        }
+    }
+
     let mut app = App::new();
 
+    // Asset sources have to be registered before the asset plugin is added.
+    register_sprite_source(&mut app);
+
     app.add_plugins(
         DefaultPlugins
             .set(ImagePlugin::default_nearest())
@@ -45,8 +71,11 @@ fn main() {
             }),
     ); // fallback to nearest sampling
 
-    // Local Plugins
-    app.add_plugins(DatabasePlugin).add_plugins(MenuPlugin);
+    // Local Plugins, settings first since the others read them.
+    app.add_plugins(SettingsPlugin)
+        .add_plugins(LocalePlugin)
+        .add_plugins(DatabasePlugin)
+        .add_plugins(MenuPlugin);
 
-    app.run();
+    app.run()
 }
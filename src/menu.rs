@@ -1,18 +1,73 @@
+mod accessibility;
+mod boxes;
+mod compare;
+#[cfg(feature = "debug")]
+mod console;
+mod editor;
+mod egg_groups;
+mod gamepad;
+mod history;
+mod loading;
+mod moves;
+mod navigation;
+mod plan;
+mod pokedex;
+mod projects;
+mod session;
+mod settings;
+mod simulate;
+mod sprites;
+mod theme;
+mod types;
+mod widgets;
+
+pub use sprites::register_sprite_source;
+
+use std::collections::HashMap;
+
 use crate::embed_asset;
 use crate::prelude::*;
 
-use bevy::ecs::schedule::ScheduleConfigs;
-use bevy::ecs::system::ScheduleSystem;
+use accessibility::{InputLabel, LiveRegion, ScreenReaderPlugin};
+use boxes::BoxesPlugin;
+use compare::{CompareInput, ComparePlugin};
+#[cfg(feature = "debug")]
+use console::ConsolePlugin;
+use editor::EditorPlugin;
+use egg_groups::EggGroupsPlugin;
+use gamepad::GamepadNavigationPlugin;
+use history::{HistoryPlugin, SearchRecord, recent_text_input, record_history};
+use loading::LoadingPlugin;
+use moves::MovesPlugin;
+use navigation::{BackButton, KeyboardNavigationPlugin, SubmitButton};
+use plan::PlanPlugin;
+use pokedex::{
+    FilterText, MarkFiltered, PokedexPlugin, favorites_filter_on_click, filter_text, marked_name,
+    tag_filter_on_click,
+};
+use projects::ProjectsPlugin;
+use session::{
+    Session, SessionFileInput, SessionPlugin, SessionStatus, load_session_on_click,
+    save_session_on_click,
+};
+use settings::SettingsScreenPlugin;
+use simulate::SimulatePlugin;
+use sprites::{PokemonSpritePlugin, pokemon_sprite};
+use theme::{Theme, ThemePlugin, ThemedBackground, ThemedText, Themes};
+use types::{TypeBadgePlugin, TypeIcons, type_badge, type_badges};
+use widgets::{Ui, small_text_style, spawn_screen, text_style};
+
+use bevy::input_focus::{InputFocus, InputFocusVisible};
 use bevy::state::state::FreelyMutableState;
-use bevy_ui_text_input::{TextInputContents, TextInputMode, TextInputNode};
+use bevy_ui_text_input::{
+    TextInputAction, TextInputContents, TextInputEdit, TextInputMode, TextInputNode, TextInputQueue,
+};
+use serde::{Deserialize, Serialize};
 
 const DEFAULT_FONT_PATH: &str = "embedded://assets/fonts/Ithaca/Ithaca-LVB75.ttf";
 const TITLE_PATH: &str = "embedded://assets/title.png";
-const TEXT_COLOR: Color = Color::srgb_u8(0xFF, 0xFF, 0xFF);
-const TEXT_INPUT_COLOR: Color = Color::srgb_u8(0x33, 0x55, 0x33);
-const BUTTON_COLOR: Color = Color::srgb_u8(0x33, 0x55, 0x77);
-const HOVERED_BUTTON_COLOR: Color = Color::srgb_u8(0x77, 0x55, 0x33);
-const PRESSED_BUTTON_COLOR: Color = Color::srgb_u8(0x00, 0x00, 0x00);
+/// How many compatible Pokemon are shown on one page of search results.
+const SEARCH_PAGE_SIZE: usize = 9;
 
 pub struct MenuPlugin;
 
@@ -22,34 +77,96 @@ impl Plugin for MenuPlugin {
         embed_asset!(app, "assets/title.png");
 
         app.init_state::<MenuState>();
-        app.add_plugins(bevy_ui_text_input::TextInputPlugin);
+        app.add_plugins(bevy_ui_text_input::TextInputPlugin)
+            .add_plugins(LoadingPlugin)
+            .add_plugins(MovesPlugin)
+            .add_plugins(EggGroupsPlugin)
+            .add_plugins(BoxesPlugin)
+            .add_plugins(ProjectsPlugin)
+            .add_plugins(PlanPlugin)
+            .add_plugins(SimulatePlugin)
+            .add_plugins(SessionPlugin)
+            .add_plugins(HistoryPlugin)
+            .add_plugins(PokedexPlugin)
+            .add_plugins(ComparePlugin)
+            .add_plugins(EditorPlugin)
+            .add_plugins(SettingsScreenPlugin)
+            .add_plugins(ThemePlugin)
+            .add_plugins(TypeBadgePlugin)
+            .add_plugins(PokemonSpritePlugin)
+            .add_plugins(KeyboardNavigationPlugin)
+            .add_plugins(GamepadNavigationPlugin)
+            .add_plugins(ScreenReaderPlugin);
 
         #[cfg(feature = "debug")]
-        app.add_systems(Update, log_transitions::<MenuState>);
+        app.add_systems(Update, log_transitions::<MenuState>)
+            .add_plugins(ConsolePlugin);
 
-        app.add_systems(Startup, (load_font, camera_setup))
+        app.init_resource::<SearchResults>()
+            .add_systems(Startup, (load_assets, camera_setup))
+            .add_systems(OnEnter(MenuState::Main), main_enter)
+            .add_systems(
+                OnEnter(MenuState::Breed),
+                (breed_enter, restore_inputs, breed_redraw).chain(),
+            )
             .add_systems(
                 Update,
-                change_state(MenuState::Main).run_if(in_state(MenuState::Loading)),
+                breed_redraw
+                    .run_if(in_state(MenuState::Breed).and(resource_exists_and_changed::<Session>)),
             )
-            .add_systems(OnEnter(MenuState::Main), main_enter)
-            .add_systems(OnEnter(MenuState::Breed), breed_enter)
-            .add_systems(OnEnter(MenuState::Search), search_enter)
-            .add_systems(Update, button_highlight);
+            .add_systems(
+                OnEnter(MenuState::Search),
+                (search_enter, restore_inputs, search_results_redraw).chain(),
+            )
+            .add_systems(
+                Update,
+                search_results_redraw.run_if(
+                    in_state(MenuState::Search).and(resource_exists_and_changed::<SearchResults>),
+                ),
+            )
+            .add_systems(Update, button_highlight)
+            .add_systems(
+                Update,
+                apply_font_size
+                    .run_if(resource_exists::<GameFont>.and(resource_changed::<Settings>)),
+            );
     }
 }
 
 #[derive(Resource)]
-pub struct GameFont(pub Handle<Font>);
+pub struct TitleImage(pub Handle<Image>);
 
-pub fn change_state<State: FreelyMutableState + Clone>(
-    state: State,
-) -> ScheduleConfigs<ScheduleSystem> {
-    (move |mut next_state: ResMut<NextState<State>>| next_state.set(state.clone())).into_configs()
+#[derive(Resource)]
+pub struct GameFont {
+    pub font: Handle<Font>,
+    /// The size of normal text, from the settings.
+    pub size: f32,
 }
 
-fn load_font(mut commands: Commands, asset_server: ResMut<AssetServer>) {
-    commands.insert_resource(GameFont(asset_server.load(DEFAULT_FONT_PATH)));
+fn load_assets(mut commands: Commands, asset_server: ResMut<AssetServer>, settings: Res<Settings>) {
+    commands.insert_resource(GameFont {
+        font: asset_server.load(DEFAULT_FONT_PATH),
+        size: settings.font_size,
+    });
+    commands.insert_resource(TitleImage(asset_server.load(TITLE_PATH)));
+}
+
+/// Resizes all text on screen when the font size setting changes, keeping
+/// small text small.
+fn apply_font_size(
+    settings: Res<Settings>,
+    mut font: ResMut<GameFont>,
+    mut texts: Query<&mut TextFont>,
+) {
+    if font.size == settings.font_size {
+        return;
+    }
+
+    let scale = settings.font_size / font.size;
+    for mut text in &mut texts {
+        text.font_size *= scale;
+    }
+    font.size = settings.font_size;
 }
 
 fn camera_setup(mut commands: Commands) {
@@ -71,6 +188,19 @@ pub enum MenuState {
     Main,
     Breed,
     Search,
+    Pokedex,
+    Compare,
+    History,
+    Moves,
+    EggGroups,
+    Boxes,
+    Projects,
+    Plan,
+    Simulate,
+    Settings,
+    Editor,
+    #[cfg(feature = "debug")]
+    Console,
 }
 
 /// Tag component used to mark which setting is currently selected
@@ -83,368 +213,232 @@ struct MotherPokemonInput;
 #[derive(Component)]
 struct MotherPokemonInfo;
 
+#[derive(Component)]
+struct MotherPokemonSprite;
+
+#[derive(Component)]
+struct SearchPokemonInput;
+
 #[derive(Component)]
 struct OtherPokemonInput;
 
 #[derive(Component)]
 struct OtherPokemonInfo;
 
+#[derive(Component)]
+struct OtherPokemonSprite;
+
 #[derive(Component)]
 struct ResultLabel;
 
-fn main_enter(mut commands: Commands, font: Res<GameFont>, asset_server: ResMut<AssetServer>) {
-    let button_node = Node {
-        width: Val::Px(300.0),
-        height: Val::Px(65.0),
-        margin: UiRect::all(Val::Px(15.0)),
-        justify_content: JustifyContent::Center,
-        align_items: AlignItems::Center,
-        ..default()
-    };
+/// The grid the search results are spawned into.
+#[derive(Component)]
+struct SearchResultsGrid;
 
-    let button_text_style = (
-        TextFont {
-            font: font.0.clone(),
-            font_size: 33.0,
-            ..default()
-        },
-        TextColor(TEXT_COLOR),
-        TextLayout::new_with_justify(JustifyText::Center),
-    );
+/// Shows which page of search results is visible.
+#[derive(Component)]
+struct SearchPageLabel;
 
-    commands
-        .spawn((
-            Node {
-                width: Val::Percent(100.0),
-                height: Val::Percent(100.0),
-                align_items: AlignItems::Center,
-                justify_content: JustifyContent::Center,
-                ..default()
-            },
-            StateScoped(MenuState::Main),
-        ))
-        .with_children(|builder| {
-            builder
-                .spawn(Node {
-                    flex_direction: FlexDirection::Column,
-                    align_items: AlignItems::Center,
-                    ..default()
-                })
-                .with_children(|builder| {
-                    builder.spawn((
-                        button_text_style.clone(),
-                        ImageNode {
-                            image: asset_server.load(TITLE_PATH),
-                            ..default()
-                        },
-                        Pickable::IGNORE,
-                    ));
-
-                    builder
-                        .spawn((
-                            Button,
-                            button_node.clone(),
-                            BackgroundColor(BUTTON_COLOR),
-                            children![(
-                                button_text_style.clone(),
-                                Text::new("Breed"),
-                                Pickable::IGNORE
-                            ),],
-                        ))
-                        .observe(change_state_on_click(
-                            PointerButton::Primary,
-                            MenuState::Breed,
-                        ));
-
-                    builder
-                        .spawn((
-                            Button,
-                            button_node.clone(),
-                            BackgroundColor(BUTTON_COLOR),
-                            children![(
-                                button_text_style.clone(),
-                                Text::new("Search"),
-                                Pickable::IGNORE
-                            ),],
-                        ))
-                        .observe(change_state_on_click(
-                            PointerButton::Primary,
-                            MenuState::Search,
-                        ));
-
-                    builder
-                        .spawn((
-                            Button,
-                            button_node.clone(),
-                            BackgroundColor(BUTTON_COLOR),
-                            children![(
-                                button_text_style.clone(),
-                                Text::new("Quit"),
-                                Pickable::IGNORE
-                            ),],
-                        ))
-                        .observe(quit_game_on_click);
-                });
+/// A single search result, holding the name of the compatible Pokemon.
+#[derive(Component)]
+struct SearchResultButton(String);
+
+/// The results of the last search, and which page of them is shown.
+#[derive(Resource, Default)]
+struct SearchResults {
+    pokemon: String,
+    compatible: Vec<PokemonEntry>,
+    page: usize,
+    /// How many were found, or why none were.
+    message: String,
+    /// Which of the results are shown, by how they are marked.
+    filter: MarkFilter,
+    marks: HashMap<i64, SpeciesMarks>,
+}
+
+impl SearchResults {
+    fn shown(&self) -> Vec<&PokemonEntry> {
+        self.compatible
+            .iter()
+            .filter(|entry| self.filter.allows(self.marks.get(&entry.pokemon_id)))
+            .collect()
+    }
+
+    fn page_count(&self) -> usize {
+        self.shown().len().div_ceil(SEARCH_PAGE_SIZE).max(1)
+    }
+}
+
+impl MarkFiltered for SearchResults {
+    fn filter(&self) -> &MarkFilter {
+        &self.filter
+    }
+
+    fn set_filter(&mut self, filter: MarkFilter) {
+        self.filter = filter;
+        self.page = 0;
+    }
+}
+
+/// The project the Plan screen works out a plan for.
+#[derive(Resource)]
+struct PlanTarget(Project);
+
+/// The Pokemon from the boxes sent to the Breed screen, which are bred
+/// instead of their species while their names are in the inputs.
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq, Debug)]
+struct BreedParents {
+    mother: Option<OwnedPokemon>,
+    other: Option<OwnedPokemon>,
+}
+
+impl BreedParents {
+    /// The species to breed for what was typed, and the Pokemon from the
+    /// boxes if it was the name of `owned`.
+    fn pick<'a>(
+        owned: Option<&'a OwnedPokemon>,
+        typed: &'a str,
+    ) -> (&'a str, Option<&'a OwnedPokemon>) {
+        match owned {
+            Some(owned) if owned.name() == typed => (&owned.species, Some(owned)),
+            _ => (typed, None),
+        }
+    }
+}
+
+fn main_enter(mut commands: Commands, font: Res<GameFont>, title: Res<TitleImage>) {
+    spawn_screen(&mut commands, &font, MenuState::Main, |ui| {
+        ui.image(title.0.clone());
+        ui.row(|ui| {
+            ui.state_button("main.breed", MenuState::Breed);
+            ui.state_button("main.search", MenuState::Search);
+        });
+        ui.row(|ui| {
+            ui.state_button("main.pokedex", MenuState::Pokedex);
+            ui.state_button("main.compare", MenuState::Compare);
+        });
+        ui.row(|ui| {
+            ui.state_button("main.moves", MenuState::Moves);
+            ui.state_button("main.egg_groups", MenuState::EggGroups);
+        });
+        ui.row(|ui| {
+            ui.state_button("main.boxes", MenuState::Boxes);
+            ui.state_button("main.projects", MenuState::Projects);
+        });
+        ui.row(|ui| {
+            ui.state_button("main.history", MenuState::History);
+            ui.state_button("main.editor", MenuState::Editor);
         });
+        ui.row(|ui| {
+            ui.state_button("main.settings", MenuState::Settings);
+            #[cfg(feature = "debug")]
+            ui.state_button("main.console", MenuState::Console);
+        });
+        ui.button("main.quit", quit_game_on_click);
+    });
 }
 
 fn breed_enter(mut commands: Commands, font: Res<GameFont>) {
-    // Common style for all buttons on the screen
-    let button_node = Node {
-        width: Val::Px(300.0),
-        height: Val::Px(65.0),
-        margin: UiRect::all(Val::Px(15.0)),
-        justify_content: JustifyContent::Center,
-        align_items: AlignItems::Center,
-        ..default()
-    };
+    spawn_screen(&mut commands, &font, MenuState::Breed, |ui| {
+        recent_text_input(ui, "breed.mother", HistoryField::Mother, MotherPokemonInput);
+        ui.row(|ui| {
+            ui.spawn((MotherPokemonSprite, Node::default()));
+            ui.result_panel(MotherPokemonInfo);
+        });
 
-    let button_text_style = (
-        TextFont {
-            font: font.0.clone(),
-            font_size: 33.0,
-            ..default()
-        },
-        TextColor(TEXT_COLOR),
-        TextLayout::new_with_justify(JustifyText::Center),
-    );
+        recent_text_input(ui, "breed.other", HistoryField::Other, OtherPokemonInput);
+        ui.row(|ui| {
+            ui.spawn((OtherPokemonSprite, Node::default()));
+            ui.result_panel(OtherPokemonInfo);
+        });
 
-    commands
-        .spawn((
-            Node {
-                width: Val::Percent(100.0),
-                height: Val::Percent(100.0),
-                align_items: AlignItems::Center,
-                justify_content: JustifyContent::Center,
-                ..default()
-            },
-            StateScoped(MenuState::Breed),
-        ))
-        .with_children(|builder| {
-            builder
-                .spawn(Node {
-                    flex_direction: FlexDirection::Column,
-                    align_items: AlignItems::Center,
-                    ..default()
-                })
-                .with_children(|builder| {
-                    builder.spawn((
-                        button_text_style.clone(),
-                        Text::new("Mother Pokemon"),
-                        Pickable::IGNORE,
-                    ));
-                    builder.spawn((
-                        Node {
-                            width: Val::Px(500.0),
-                            height: Val::Px(60.0),
-                            ..default()
-                        },
-                        TextInputContents::default(),
-                        BackgroundColor(TEXT_INPUT_COLOR),
-                        TextInputNode {
-                            clear_on_submit: false,
-                            mode: TextInputMode::SingleLine,
-                            focus_on_pointer_down: true,
-                            unfocus_on_submit: true,
-                            max_chars: Some(32),
-                            ..default()
-                        },
-                        MotherPokemonInput,
-                        button_text_style.clone(),
-                    ));
-                    builder.spawn((
-                        button_text_style.clone(),
-                        Text::new(""),
-                        Pickable::IGNORE,
-                        MotherPokemonInfo,
-                    ));
-
-                    builder.spawn((
-                        button_text_style.clone(),
-                        Text::new("Other Pokemon"),
-                        Pickable::IGNORE,
-                    ));
-                    builder.spawn((
-                        Node {
-                            width: Val::Px(500.0),
-                            height: Val::Px(60.0),
-                            ..default()
-                        },
-                        TextInputContents::default(),
-                        BackgroundColor(TEXT_INPUT_COLOR),
-                        TextInputNode {
-                            clear_on_submit: false,
-                            mode: TextInputMode::SingleLine,
-                            focus_on_pointer_down: true,
-                            unfocus_on_submit: true,
-                            max_chars: Some(32),
-                            ..default()
-                        },
-                        OtherPokemonInput,
-                        button_text_style.clone(),
-                    ));
-                    builder.spawn((
-                        button_text_style.clone(),
-                        Text::new(""),
-                        Pickable::IGNORE,
-                        OtherPokemonInfo,
-                    ));
-
-                    builder.spawn((
-                        button_text_style.clone(),
-                        Text::new("Result:"),
-                        Pickable::IGNORE,
-                    ));
-                    builder.spawn((
-                        button_text_style.clone(),
-                        Text::new(""),
-                        Pickable::IGNORE,
-                        ResultLabel,
-                    ));
-
-                    builder
-                        .spawn((
-                            Button,
-                            button_node.clone(),
-                            BackgroundColor(BUTTON_COLOR),
-                            children![(
-                                button_text_style.clone(),
-                                Text::new("Submit"),
-                                Pickable::IGNORE
-                            ),],
-                        ))
-                        .observe(breed_submit_button);
-
-                    builder
-                        .spawn((
-                            Button,
-                            button_node.clone(),
-                            BackgroundColor(BUTTON_COLOR),
-                            children![(button_text_style, Text::new("Back"), Pickable::IGNORE),],
-                        ))
-                        .observe(change_state_on_click(
-                            PointerButton::Primary,
-                            MenuState::Main,
-                        ));
-                });
+        ui.label("breed.result");
+        ui.result_panel(ResultLabel);
+
+        ui.row(|ui| {
+            ui.button("common.submit", breed_submit_button)
+                .insert(SubmitButton);
+            ui.state_button("breed.boxes", MenuState::Boxes);
+            ui.state_button("breed.simulate", MenuState::Simulate);
         });
-}
-fn search_enter(mut commands: Commands, font: Res<GameFont>) {
-    // Common style for all buttons on the screen
-    let button_node = Node {
-        width: Val::Px(300.0),
-        height: Val::Px(65.0),
-        margin: UiRect::all(Val::Px(15.0)),
-        justify_content: JustifyContent::Center,
-        align_items: AlignItems::Center,
-        ..default()
-    };
 
-    let button_text_style = (
-        TextFont {
-            font: font.0.clone(),
-            font_size: 33.0,
-            ..default()
-        },
-        TextColor(TEXT_COLOR),
-        TextLayout::new_with_justify(JustifyText::Center),
-    );
+        ui.long_text_input("breed.session_file", None, SessionFileInput);
+        ui.result_panel(SessionStatus);
+        ui.row(|ui| {
+            ui.button("breed.save_session", save_session_on_click);
+            ui.button("breed.load_session", load_session_on_click);
+            ui.back_button();
+        });
+    });
+}
 
-    commands
-        .spawn((
-            Node {
-                width: Val::Percent(100.0),
-                height: Val::Percent(100.0),
-                align_items: AlignItems::Center,
-                justify_content: JustifyContent::Center,
-                ..default()
-            },
-            StateScoped(MenuState::Search),
-        ))
-        .with_children(|builder| {
-            builder
-                .spawn(Node {
-                    flex_direction: FlexDirection::Column,
-                    align_items: AlignItems::Center,
-                    ..default()
-                })
-                .with_children(|builder| {
-                    builder.spawn((
-                        button_text_style.clone(),
-                        Text::new("Pokemon"),
-                        Pickable::IGNORE,
-                    ));
-                    builder.spawn((
-                        Node {
-                            width: Val::Px(500.0),
-                            height: Val::Px(60.0),
-                            ..default()
-                        },
-                        TextInputContents::default(),
-                        BackgroundColor(TEXT_INPUT_COLOR),
-                        TextInputNode {
-                            clear_on_submit: false,
-                            mode: TextInputMode::SingleLine,
-                            focus_on_pointer_down: true,
-                            unfocus_on_submit: true,
-                            max_chars: Some(32),
-                            ..default()
-                        },
-                        MotherPokemonInput,
-                        button_text_style.clone(),
-                    ));
-
-                    builder.spawn((
-                        button_text_style.clone(),
-                        Text::new("Result:"),
-                        Pickable::IGNORE,
-                    ));
-                    builder.spawn((
-                        button_text_style.clone(),
-                        Text::new(""),
-                        Pickable::IGNORE,
-                        ResultLabel,
-                    ));
-                    builder
-                        .spawn((
-                            Button,
-                            button_node.clone(),
-                            BackgroundColor(BUTTON_COLOR),
-                            children![(
-                                button_text_style.clone(),
-                                Text::new("Submit"),
-                                Pickable::IGNORE
-                            ),],
-                        ))
-                        .observe(search_submit_button);
-
-                    builder
-                        .spawn((
-                            Button,
-                            button_node.clone(),
-                            BackgroundColor(BUTTON_COLOR),
-                            children![(button_text_style, Text::new("Back"), Pickable::IGNORE),],
-                        ))
-                        .observe(change_state_on_click(
-                            PointerButton::Primary,
-                            MenuState::Main,
-                        ));
-                });
+fn search_enter(
+    mut commands: Commands,
+    font: Res<GameFont>,
+    mut results: ResMut<SearchResults>,
+    db: NonSend<Database>,
+) {
+    // They may have been starred or tagged since.
+    results.marks = get_all_marks(&db);
+
+    spawn_screen(&mut commands, &font, MenuState::Search, |ui| {
+        recent_text_input(
+            ui,
+            "search.pokemon",
+            HistoryField::Search,
+            SearchPokemonInput,
+        );
+        ui.result_panel(ResultLabel);
+        ui.grid(3, 300.0, SearchResultsGrid);
+        ui.pager(
+            SearchPageLabel,
+            search_page_on_click(-1),
+            search_page_on_click(1),
+        );
+        ui.row(|ui| {
+            ui.dynamic_button(
+                FilterText::Favorites,
+                favorites_filter_on_click::<SearchResults>,
+            );
+            ui.dynamic_button(FilterText::Tag, tag_filter_on_click::<SearchResults>);
+        });
+        ui.row(|ui| {
+            ui.button("common.submit", search_submit_button)
+                .insert(SubmitButton);
+            ui.back_button();
         });
+    });
 }
 
 fn button_highlight(
     mut interaction_query: Query<
-        (&Interaction, &mut BackgroundColor, Option<&SelectedOption>),
-        (Changed<Interaction>, With<Button>),
+        (
+            Entity,
+            Ref<Interaction>,
+            &mut BackgroundColor,
+            Option<&SelectedOption>,
+        ),
+        With<Button>,
     >,
+    focus: Res<InputFocus>,
+    focus_visible: Res<InputFocusVisible>,
+    theme: Res<Theme>,
 ) {
-    for (interaction, mut background_color, selected) in &mut interaction_query {
+    let focus_changed = focus.is_changed() || focus_visible.is_changed() || theme.is_changed();
+
+    for (entity, interaction, mut background_color, selected) in &mut interaction_query {
+        if !interaction.is_changed() && !focus_changed {
+            continue;
+        }
+
+        let focused = focus_visible.0 && focus.0 == Some(entity);
+
         *background_color = match (*interaction, selected) {
-            (Interaction::Pressed, _) | (Interaction::None, Some(_)) => PRESSED_BUTTON_COLOR.into(),
-            (Interaction::Hovered, Some(_)) => HOVERED_BUTTON_COLOR.into(),
-            (Interaction::Hovered, Option::None) => HOVERED_BUTTON_COLOR.into(),
-            (Interaction::None, Option::None) => BUTTON_COLOR.into(),
+            (Interaction::Pressed, _) | (Interaction::None, Some(_)) => theme.pressed_button.into(),
+            (Interaction::Hovered, Some(_)) => theme.hovered_button.into(),
+            (Interaction::Hovered, Option::None) => theme.hovered_button.into(),
+            (Interaction::None, Option::None) if focused => theme.hovered_button.into(),
+            (Interaction::None, Option::None) => theme.button.into(),
         }
     }
 }
@@ -462,8 +456,48 @@ fn quit_game_on_click(
 
 fn breed_submit_button(
     mut click: Trigger<Pointer<Click>>,
-    mother: Query<&TextInputContents, With<MotherPokemonInput>>,
-    other: Query<&TextInputContents, With<OtherPokemonInput>>,
+    mother: Single<&TextInputContents, With<MotherPokemonInput>>,
+    other: Single<&TextInputContents, With<OtherPokemonInput>>,
+    mut session: ResMut<Session>,
+    locale: Res<Locale>,
+    db: NonSend<Database>,
+) {
+    click.propagate(false);
+
+    if click.button == PointerButton::Primary {
+        session.mother = mother.get().into();
+        session.other = other.get().into();
+        run_breed(&mut session, &db, &locale);
+    }
+}
+
+/// Breeds the parents named in the session into its report, and adds them
+/// to the history.
+fn run_breed(session: &mut Session, db: &Database, locale: &Locale) {
+    let (mother, mother_owned) =
+        BreedParents::pick(session.parents.mother.as_ref(), &session.mother);
+    let (other, other_owned) = BreedParents::pick(session.parents.other.as_ref(), &session.other);
+
+    let mut report = breed(db, mother, other, locale.code());
+    for (parent, owned) in [
+        (&mut report.mother, mother_owned),
+        (&mut report.other, other_owned),
+    ] {
+        if let Some(parent) = parent {
+            parent.owned = owned.cloned();
+        }
+    }
+
+    let submission = Submission::Breed {
+        mother: session.mother.clone(),
+        other: session.other.clone(),
+    };
+    record_history(db, &submission, &report);
+    session.report = Some(report);
+}
+
+/// Shows the last report in the session, with sprites of the parents.
+fn breed_redraw(
     mut mother_info: Query<
         &mut Text,
         (
@@ -481,168 +515,261 @@ fn breed_submit_button(
         ),
     >,
     mut result: Query<&mut Text, With<ResultLabel>>,
+    mother_sprite: Single<Entity, With<MotherPokemonSprite>>,
+    other_sprite: Single<Entity, With<OtherPokemonSprite>>,
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    session: Res<Session>,
+    locale: Res<Locale>,
+) {
+    let Some(report) = &session.report else {
+        return;
+    };
+
+    for (slot, parent) in [
+        (*mother_sprite, &report.mother),
+        (*other_sprite, &report.other),
+    ] {
+        commands.entity(slot).despawn_related::<Children>();
+        if let Some(parent) = parent {
+            commands
+                .entity(slot)
+                .with_child(pokemon_sprite(parent.pokemon_id, &asset_server));
+        }
+    }
+
+    mother_info.single_mut().unwrap().0 = BreedReport::parent_text(report.mother.as_ref(), &locale);
+    other_info.single_mut().unwrap().0 = BreedReport::parent_text(report.other.as_ref(), &locale);
+    result.single_mut().unwrap().0 = report.offspring_text(&locale);
+}
+
+fn search_submit_button(
+    mut click: Trigger<Pointer<Click>>,
+    pokemon: Query<&TextInputContents, With<SearchPokemonInput>>,
+    mut results: ResMut<SearchResults>,
+    locale: Res<Locale>,
     db: NonSend<Database>,
 ) {
     click.propagate(false);
 
     if click.button == PointerButton::Primary {
-        let mother = mother.single().unwrap().get();
-        let other = other.single().unwrap().get();
+        let pokemon = pokemon.single().unwrap().get();
+        run_search(&mut results, pokemon, &db, &locale);
+    }
+}
 
-        let mother_groups = get_groups(&db, mother);
-        let other_groups = get_groups(&db, other);
+/// Searches for what breeds with `pokemon`, and adds it to the history.
+fn run_search(results: &mut SearchResults, pokemon: &str, db: &Database, locale: &Locale) {
+    let found = exists(db, pokemon);
+    let (compatible, message) = if !found {
+        (Vec::new(), locale.get("common.not_found").into())
+    } else {
+        let compatible = get_pokemon_compatible(db, pokemon, locale.code());
+        let message = match compatible.len() {
+            0 => locale.format("search.breedable", &[&locale.get("common.none")]),
+            count => locale.format("search.breedable", &[&count]),
+        };
+        (compatible, message)
+    };
 
-        let mut result = result.single_mut().unwrap();
+    let record = SearchRecord {
+        found,
+        compatible: compatible.clone(),
+    };
+    record_history(
+        db,
+        &Submission::Search {
+            pokemon: pokemon.into(),
+        },
+        &record,
+    );
 
-        let mut mother_info = mother_info.single_mut().unwrap();
+    *results = SearchResults {
+        pokemon: pokemon.into(),
+        compatible,
+        page: 0,
+        message,
+        filter: results.filter.clone(),
+        marks: get_all_marks(db),
+    };
+}
 
-        mother_info.0 = if !exists(&db, mother) {
-            "Not Found".into()
-        } else {
-            let str = if mother_groups.len() > 0 {
-                mother_groups.join(", ")
-            } else {
-                "None".into()
-            };
-            format!("Egg Groups: {}", str)
-        };
+fn search_page_on_click(step: isize) -> impl Fn(Trigger<Pointer<Click>>, ResMut<SearchResults>) {
+    move |mut click, mut results| {
+        click.propagate(false);
 
-        let mut other_info = other_info.single_mut().unwrap();
-
-        other_info.0 = if !exists(&db, other) {
-            "Not Found".into()
-        } else {
-            let str = if other_groups.len() > 0 {
-                other_groups.join(", ")
-            } else {
-                "None".into()
-            };
-            format!("Egg Groups: {}", str)
-        };
+        if click.button != PointerButton::Primary {
+            return;
+        }
 
-        let any_overlap = mother_groups
-            .iter()
-            .any(|g| other_groups.iter().any(|y| y == g));
+        let page = results
+            .page
+            .saturating_add_signed(step)
+            .min(results.page_count() - 1);
 
-        if !any_overlap {
-            result.0 = "Bad Match!".into();
-        } else {
-            let pokemon = if mother == "Ditto" { other } else { mother };
+        if page != results.page {
+            results.page = page;
+        }
+    }
+}
 
-            let egg_moves = get_egg_moves(&db, pokemon);
+fn search_results_redraw(
+    mut commands: Commands,
+    results: Res<SearchResults>,
+    grid: Single<Entity, With<SearchResultsGrid>>,
+    mut result: Single<
+        &mut Text,
+        (
+            With<ResultLabel>,
+            Without<SearchPageLabel>,
+            Without<FilterText>,
+        ),
+    >,
+    mut page_label: Single<&mut Text, (With<SearchPageLabel>, Without<FilterText>)>,
+    mut filter_texts: Query<(&mut Text, &FilterText)>,
+    font: Res<GameFont>,
+    icons: Res<TypeIcons>,
+    asset_server: Res<AssetServer>,
+    locale: Res<Locale>,
+) {
+    commands.entity(*grid).despawn_related::<Children>();
 
-            let egg_moves = if egg_moves.len() > 0 {
-                egg_moves.join(", ")
-            } else {
-                "None".into()
-            };
+    let shown = results.shown();
 
-            result.0 = format!("{pokemon}\nEgg Moves: {egg_moves}");
-        };
+    result.0 = results.message.clone();
+    page_label.0 = if shown.is_empty() {
+        "".into()
+    } else {
+        locale.format("common.page", &[&(results.page + 1), &results.page_count()])
+    };
+    for (mut text, filter) in &mut filter_texts {
+        text.0 = filter_text(&results.filter, *filter, &locale);
     }
+
+    let name_style = text_style(&font);
+
+    commands.entity(*grid).with_children(|builder| {
+        for pokemon in shown
+            .iter()
+            .skip(results.page * SEARCH_PAGE_SIZE)
+            .take(SEARCH_PAGE_SIZE)
+        {
+            builder
+                .spawn((
+                    Button,
+                    Node {
+                        width: Val::Px(300.0),
+                        height: Val::Px(80.0),
+                        column_gap: Val::Px(10.0),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    ThemedBackground::Button,
+                    SearchResultButton(pokemon.name.clone()),
+                    children![
+                        pokemon_sprite(pokemon.pokemon_id, &asset_server),
+                        (
+                            Node {
+                                flex_direction: FlexDirection::Column,
+                                align_items: AlignItems::Center,
+                                ..default()
+                            },
+                            Pickable::IGNORE,
+                            children![
+                                (
+                                    name_style.clone(),
+                                    Text::new(marked_name(
+                                        &pokemon.name,
+                                        results.marks.get(&pokemon.pokemon_id)
+                                    )),
+                                    Pickable::IGNORE
+                                ),
+                                type_badges(
+                                    &pokemon.primary_type,
+                                    pokemon.secondary_type.as_deref(),
+                                    &font,
+                                    &icons
+                                ),
+                            ],
+                        ),
+                    ],
+                ))
+                .observe(search_result_on_click);
+        }
+    });
 }
 
-fn search_submit_button(
+fn search_result_on_click(
     mut click: Trigger<Pointer<Click>>,
-    mother: Query<&TextInputContents, With<MotherPokemonInput>>,
-    mut result: Query<&mut Text, With<ResultLabel>>,
-    db: NonSend<Database>,
+    buttons: Query<&SearchResultButton>,
+    results: Res<SearchResults>,
+    mut session: ResMut<Session>,
+    mut next_state: ResMut<NextState<MenuState>>,
 ) {
     click.propagate(false);
 
-    if click.button == PointerButton::Primary {
-        let mother = mother.single().unwrap().get();
-
-        let mut result = result.single_mut().unwrap();
-
-        result.0 = if !exists(&db, mother) {
-            "Not Found".into()
-        } else {
-            let compatible = get_pokemon_compatible(&db, mother);
-
-            format!(
-                "Breedable: {}",
-                if compatible.len() > 0 {
-                    compatible.join(", ")
-                } else {
-                    "None".into()
-                }
-            )
-        };
+    if click.button != PointerButton::Primary {
+        return;
     }
+
+    let Ok(SearchResultButton(other)) = buttons.get(click.target()) else {
+        return;
+    };
+
+    session.mother = results.pokemon.clone();
+    session.other = other.clone();
+    next_state.set(MenuState::Breed);
 }
-fn exists(db: &Database, name: &str) -> bool {
-    let query = r#"
-            SELECT COUNT(*)
-                FROM pokemon
-                WHERE pokemon.name = :name
-                COLLATE NOCASE
-        "#;
-    let mut query = db.connection.prepare_cached(query).unwrap();
-
-    query.query_one((name,), |a| a.get::<_, u32>(0)).unwrap() > 0
+
+/// Fills the inputs of the screen just entered in from the session.
+fn restore_inputs(
+    mut inputs: Query<(
+        &mut TextInputQueue,
+        Has<MotherPokemonInput>,
+        Has<OtherPokemonInput>,
+        Has<SearchPokemonInput>,
+        Has<CompareInput>,
+    )>,
+    session: Res<Session>,
+) {
+    for (mut queue, mother, other, search, compare) in &mut inputs {
+        let text = match (mother, other, search, compare) {
+            (true, _, _, _) => &session.mother,
+            (_, true, _, _) => &session.other,
+            (_, _, true, _) => &session.search,
+            (_, _, _, true) => &session.compare,
+            _ => continue,
+        };
+        set_input_text(&mut queue, text);
+    }
 }
 
-fn get_groups(db: &Database, name: &str) -> Vec<String> {
-    let query = r#"
-            SELECT egg_group.name
-                FROM pokemon
-                    JOIN pokemon_egg_group ON pokemon.pokemon_id = pokemon_egg_group.pokemon_id
-                    JOIN egg_group ON pokemon_egg_group.egg_group_id = egg_group.egg_group_id
-                WHERE pokemon.name = :pokemon_name
-                COLLATE NOCASE
-        "#;
-    let mut query = db.connection.prepare_cached(query).unwrap();
-
-    query
-        .query_map((name,), |row| row.get::<_, String>(0))
-        .unwrap()
-        .collect::<Result<Vec<_>, _>>()
-        .unwrap()
+/// Replaces whatever is in a text input with `text`.
+fn set_input_text(queue: &mut TextInputQueue, text: &str) {
+    queue.add(TextInputAction::Edit(TextInputEdit::SelectAll));
+    queue.add(TextInputAction::Edit(TextInputEdit::Delete));
+    for c in text.chars() {
+        queue.add(TextInputAction::Edit(TextInputEdit::Insert(c, false)));
+    }
 }
 
-fn get_egg_moves(db: &Database, name: &str) -> Vec<String> {
-    let query = r#"
-        SELECT move.name
-            FROM pokemon
-                JOIN pokemon_move ON pokemon.pokemon_id = pokemon_move.pokemon_id
-                JOIN move ON pokemon_move.move_id = move.move_id
-            WHERE pokemon.name = :pokemon_name
-              AND pokemon_move.method = 'egg'
-            COLLATE NOCASE
-    "#;
-    let mut query = db.connection.prepare_cached(query).unwrap();
-
-    query
-        .query_map((name,), |row| row.get::<_, String>(0))
-        .unwrap()
-        .collect::<Result<Vec<_>, _>>()
-        .unwrap()
+/// The index `step` away from `index` among `count`, wrapping around, or
+/// `index` itself if there are none.
+fn step_index(index: usize, step: isize, count: usize) -> usize {
+    if count == 0 {
+        return index;
+    }
+    (index as isize + step).rem_euclid(count as isize) as usize
 }
 
-fn get_pokemon_compatible(db: &Database, name: &str) -> Vec<String> {
-    let query = r#"
-        SELECT pokemon.name
-            FROM pokemon
-                JOIN pokemon_egg_group ON pokemon.pokemon_id = pokemon_egg_group.pokemon_id
-                JOIN egg_group ON pokemon_egg_group.egg_group_id = egg_group.egg_group_id
-                JOIN (
-                    SELECT eg.egg_group_id AS id
-                        FROM pokemon as pk
-                            JOIN pokemon_egg_group peg ON pk.pokemon_id = peg.pokemon_id
-                            JOIN egg_group eg ON peg.egg_group_id = eg.egg_group_id
-                        WHERE pk.name = :pokemon_name
-                        COLLATE NOCASE
-                ) AS breedable ON egg_group.egg_group_id = breedable.id
-            GROUP BY pokemon.pokemon_id
-    "#;
-    let mut query = db.connection.prepare_cached(query).unwrap();
-
-    query
-        .query_map((name,), |row| row.get::<_, String>(0))
-        .unwrap()
-        .collect::<Result<Vec<_>, _>>()
-        .unwrap()
+/// The value `step` away from `value` in `all`, wrapping around, or `value`
+/// itself if `all` is empty.
+fn step_through<T: PartialEq + Copy>(all: Vec<T>, value: T, step: isize) -> T {
+    let index = all.iter().position(|v| *v == value).unwrap_or(0);
+    all.get(step_index(index, step, all.len()))
+        .copied()
+        .unwrap_or(value)
 }
 
 pub fn change_state_on_click<State: FreelyMutableState + Clone>(
@@ -0,0 +1,188 @@
+//! Screen reader support, through the accessibility tree Bevy hands to accesskit.
+//!
+//! Bevy only names buttons and images when they are spawned, so this keeps
+//! button names, text and input values up to date as they change.
+
+use super::*;
+
+use accesskit::{Live, Node as AccessNode, Role};
+use bevy::a11y::AccessibilityNode;
+
+pub struct ScreenReaderPlugin;
+
+impl Plugin for ScreenReaderPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, (text_changed, text_input_changed));
+    }
+}
+
+/// Text that is announced whenever it changes, like results.
+#[derive(Component, Default)]
+pub struct LiveRegion;
+
+/// The accessible name of a text input, as a key into the [`Locale`].
+#[derive(Component)]
+pub struct InputLabel(pub &'static str);
+
+fn text_changed(
+    mut commands: Commands,
+    texts: Query<(Entity, &Text, Has<LiveRegion>), Changed<Text>>,
+    mut nodes: Query<&mut AccessibilityNode>,
+    parents: Query<&ChildOf>,
+    children: Query<&Children>,
+    buttons: Query<(), With<Button>>,
+    all_texts: Query<&Text>,
+) {
+    for (entity, text, live) in &texts {
+        if let Some(button) = parents
+            .iter_ancestors(entity)
+            .find(|ancestor| buttons.contains(*ancestor))
+        {
+            let label = children
+                .iter_descendants(button)
+                .filter_map(|child| all_texts.get(child).ok())
+                .map(|text| text.0.as_str())
+                .filter(|text| !text.is_empty())
+                .collect::<Vec<_>>()
+                .join(" ");
+
+            update_node(&mut commands, &mut nodes, button, Role::Button, |node| {
+                node.set_label(label)
+            });
+            continue;
+        }
+
+        update_node(&mut commands, &mut nodes, entity, Role::Label, |node| {
+            node.set_value(text.0.as_str());
+            if live {
+                node.set_live(Live::Polite);
+            }
+        });
+    }
+}
+
+fn text_input_changed(
+    mut commands: Commands,
+    inputs: Query<(Entity, &InputLabel, Ref<TextInputContents>)>,
+    mut nodes: Query<&mut AccessibilityNode>,
+    locale: Res<Locale>,
+) {
+    for (entity, label, contents) in &inputs {
+        if !contents.is_changed() && !locale.is_changed() {
+            continue;
+        }
+
+        update_node(&mut commands, &mut nodes, entity, Role::TextInput, |node| {
+            node.set_label(locale.get(label.0));
+            node.set_value(contents.get());
+        });
+    }
+}
+
+/// Sets the role of an entity's accessibility node and edits it, adding the
+/// node if it doesn't have one yet.
+fn update_node(
+    commands: &mut Commands,
+    nodes: &mut Query<&mut AccessibilityNode>,
+    entity: Entity,
+    role: Role,
+    edit: impl FnOnce(&mut AccessNode),
+) {
+    if let Ok(mut node) = nodes.get_mut(entity) {
+        node.set_role(role);
+        edit(&mut node.0);
+    } else {
+        let mut node = AccessNode::new(role);
+        edit(&mut node);
+        commands
+            .entity(entity)
+            .try_insert(AccessibilityNode::from(node));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.insert_resource(Locale::new(Language::English))
+            .add_plugins(ScreenReaderPlugin);
+        app
+    }
+
+    fn node(app: &App, entity: Entity) -> &AccessNode {
+        &app.world().get::<AccessibilityNode>(entity).unwrap().0
+    }
+
+    fn set_text(app: &mut App, entity: Entity, text: &str) {
+        app.world_mut().get_mut::<Text>(entity).unwrap().0 = text.into();
+    }
+
+    #[test]
+    fn buttons_are_named_by_their_text() {
+        let mut app = app();
+        let button = app.world_mut().spawn(Button).id();
+        let text = app
+            .world_mut()
+            .spawn((Text::new("Breed"), ChildOf(button)))
+            .id();
+
+        app.update();
+        assert_eq!(node(&app, button).role(), Role::Button);
+        assert_eq!(node(&app, button).label(), Some("Breed"));
+        // The text is part of the button, not a label of its own.
+        assert!(app.world().get::<AccessibilityNode>(text).is_none());
+
+        set_text(&mut app, text, "Search");
+        app.update();
+        assert_eq!(node(&app, button).label(), Some("Search"));
+    }
+
+    #[test]
+    fn text_inputs_have_their_label_and_value() {
+        let mut app = app();
+        let input = app
+            .world_mut()
+            .spawn((
+                TextInputNode::default(),
+                TextInputContents::default(),
+                InputLabel("breed.mother"),
+            ))
+            .id();
+
+        app.update();
+        assert_eq!(node(&app, input).role(), Role::TextInput);
+        assert_eq!(node(&app, input).label(), Some("Mother Pokemon"));
+        assert_eq!(node(&app, input).value(), Some(""));
+
+        // The label follows the language.
+        *app.world_mut().resource_mut::<Locale>() = Locale::new(Language::French);
+        app.update();
+        assert_eq!(node(&app, input).label(), Some("Pokémon mère"));
+    }
+
+    #[test]
+    fn results_are_announced() {
+        let mut app = app();
+        let result = app
+            .world_mut()
+            .spawn((Text::new(""), LiveRegion, ResultLabel))
+            .id();
+        let label = app.world_mut().spawn(Text::new("Mother Pokemon")).id();
+
+        app.update();
+        assert_eq!(node(&app, result).role(), Role::Label);
+        assert_eq!(node(&app, result).value(), Some(""));
+        assert_eq!(node(&app, result).live(), Some(Live::Polite));
+        assert_eq!(node(&app, label).live(), None);
+
+        set_text(&mut app, result, "Bulbasaur can breed with Charmander");
+        app.update();
+        assert_eq!(
+            node(&app, result).value(),
+            Some("Bulbasaur can breed with Charmander")
+        );
+        assert_eq!(node(&app, label).value(), Some("Mother Pokemon"));
+    }
+}
@@ -0,0 +1,462 @@
+//! The boxes of Pokemon we own, laid out in grids of slots like in the games.
+//!
+//! Clicking a slot fills in the form with whatever is in it, or clears it
+//! for a new Pokemon. A saved Pokemon can be sent to the Breed screen as
+//! either parent.
+
+use super::*;
+
+use strum::IntoEnumIterator;
+
+const SLOT_SIZE: f32 = 64.0;
+const BOX_COLUMNS: usize = 6;
+/// Room for four move names, comma separated.
+const MOVES_MAX_CHARS: usize = 80;
+
+pub struct BoxesPlugin;
+
+impl Plugin for BoxesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(MenuState::Boxes), boxes_enter)
+            .add_systems(
+                Update,
+                boxes_redraw
+                    .run_if(in_state(MenuState::Boxes).and(resource_exists_and_changed::<Boxes>)),
+            );
+    }
+}
+
+/// The box being looked at, and the form for the selected slot.
+#[derive(Resource)]
+struct Boxes {
+    /// From 1 to [`BOX_COUNT`].
+    box_number: u32,
+    pokemon: Vec<OwnedPokemon>,
+    selected: u32,
+    gender: Gender,
+    nature: Nature,
+    /// An index into [`BALLS`].
+    ball: usize,
+    status: String,
+    /// Whether the text inputs need filling in again. Stepping through the
+    /// choices leaves them alone, so what has been typed isn't thrown away.
+    refill_form: bool,
+}
+
+impl Boxes {
+    fn new(box_number: u32, db: &Database) -> Self {
+        let mut boxes = Self {
+            box_number,
+            pokemon: get_box(db, box_number),
+            selected: 0,
+            gender: Gender::default(),
+            nature: Nature::default(),
+            ball: 0,
+            status: String::new(),
+            refill_form: true,
+        };
+        boxes.select(0);
+        boxes
+    }
+
+    fn in_slot(&self, slot: u32) -> Option<&OwnedPokemon> {
+        self.pokemon.iter().find(|owned| owned.slot == slot)
+    }
+
+    fn selected(&self) -> Option<&OwnedPokemon> {
+        self.in_slot(self.selected)
+    }
+
+    /// Selects a slot, filling in the form with what is in it.
+    fn select(&mut self, slot: u32) {
+        self.selected = slot;
+        let (gender, nature, ball) = match self.selected() {
+            Some(owned) => (
+                owned.gender,
+                owned.nature,
+                BALLS
+                    .iter()
+                    .position(|ball| *ball == owned.ball)
+                    .unwrap_or(0),
+            ),
+            None => (Gender::default(), Nature::default(), 0),
+        };
+        self.gender = gender;
+        self.nature = nature;
+        self.ball = ball;
+        self.refill_form = true;
+    }
+
+    /// Reloads the box after a change, keeping the same slot selected.
+    fn refresh(&mut self, db: &Database) {
+        self.pokemon = get_box(db, self.box_number);
+        self.select(self.selected);
+    }
+}
+
+/// A choice in the form that is stepped through with its button.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Choice {
+    Gender,
+    Nature,
+    Ball,
+}
+
+#[derive(Component)]
+struct BoxLabel;
+
+/// The grid the slots are spawned into.
+#[derive(Component)]
+struct BoxGrid;
+
+/// A slot in the box, holding its number.
+#[derive(Component)]
+struct SlotButton(u32);
+
+/// The text showing the current value of a choice.
+#[derive(Component)]
+struct ChoiceValue(Choice);
+
+/// A text input of the form.
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+enum FormInput {
+    Species,
+    Nickname,
+    Ability,
+    Ivs,
+    Moves,
+}
+
+#[derive(Component)]
+struct BoxesStatus;
+
+fn boxes_enter(mut commands: Commands, font: Res<GameFont>, db: NonSend<Database>) {
+    commands.insert_resource(Boxes::new(1, &db));
+
+    spawn_screen(&mut commands, &font, MenuState::Boxes, |ui| {
+        ui.row(|ui| {
+            ui.column(|ui| {
+                ui.pager(BoxLabel, box_on_click(-1), box_on_click(1));
+                ui.grid(BOX_COLUMNS, SLOT_SIZE, BoxGrid);
+            });
+            ui.column(|ui| {
+                ui.text_input("boxes.species", FormInput::Species);
+                ui.text_input("boxes.nickname", FormInput::Nickname);
+                ui.text_input("boxes.ability", FormInput::Ability);
+                ui.text_input("boxes.ivs", FormInput::Ivs);
+                ui.long_text_input("boxes.moves", Some(MOVES_MAX_CHARS), FormInput::Moves);
+            });
+            ui.column(|ui| {
+                for (name, choice) in [
+                    ("boxes.gender", Choice::Gender),
+                    ("boxes.nature", Choice::Nature),
+                    ("boxes.ball", Choice::Ball),
+                ] {
+                    ui.label(name);
+                    ui.dynamic_button(ChoiceValue(choice), step_choice_on_click(choice, 1));
+                }
+            });
+        });
+
+        ui.result_panel(BoxesStatus);
+        ui.row(|ui| {
+            ui.button("boxes.save", save_owned_on_click)
+                .insert(SubmitButton);
+            ui.button("boxes.release", release_owned_on_click);
+            ui.button("boxes.use_as_mother", use_as_parent_on_click(true));
+            ui.button("boxes.use_as_other", use_as_parent_on_click(false));
+        });
+        ui.back_button();
+    });
+}
+
+fn box_on_click(step: i32) -> impl Fn(Trigger<Pointer<Click>>, ResMut<Boxes>, NonSend<Database>) {
+    move |mut click, mut boxes, db| {
+        click.propagate(false);
+
+        if click.button != PointerButton::Primary {
+            return;
+        }
+
+        let box_number = boxes
+            .box_number
+            .saturating_add_signed(step)
+            .clamp(1, BOX_COUNT);
+
+        if box_number != boxes.box_number {
+            *boxes = Boxes::new(box_number, &db);
+        }
+    }
+}
+
+fn slot_on_click(
+    mut click: Trigger<Pointer<Click>>,
+    buttons: Query<&SlotButton>,
+    mut boxes: ResMut<Boxes>,
+) {
+    click.propagate(false);
+
+    if click.button != PointerButton::Primary {
+        return;
+    }
+
+    if let Ok(SlotButton(slot)) = buttons.get(click.target()) {
+        boxes.select(*slot);
+        boxes.status.clear();
+    }
+}
+
+fn step_choice_on_click(
+    choice: Choice,
+    step: isize,
+) -> impl Fn(Trigger<Pointer<Click>>, ResMut<Boxes>) {
+    move |mut click, mut boxes| {
+        click.propagate(false);
+
+        if click.button != PointerButton::Primary {
+            return;
+        }
+
+        match choice {
+            Choice::Gender => {
+                boxes.gender = step_through(Gender::iter().collect(), boxes.gender, step)
+            }
+            Choice::Nature => {
+                boxes.nature = step_through(Nature::iter().collect(), boxes.nature, step)
+            }
+            Choice::Ball => {
+                boxes.ball = step_index(boxes.ball, step, BALLS.len());
+            }
+        }
+    }
+}
+
+/// Reads the form into a Pokemon for the selected slot, or explains what is
+/// wrong with it.
+fn read_form(
+    boxes: &Boxes,
+    inputs: &Query<(&TextInputContents, &FormInput)>,
+    locale: &Locale,
+    db: &Database,
+) -> Result<OwnedPokemon, String> {
+    let (mut species, mut nickname, mut ability, mut ivs, mut moves) = ("", "", "", "", "");
+    for (contents, input) in inputs {
+        let text = contents.get().trim();
+        match input {
+            FormInput::Species => species = text,
+            FormInput::Nickname => nickname = text,
+            FormInput::Ability => ability = text,
+            FormInput::Ivs => ivs = text,
+            FormInput::Moves => moves = text,
+        }
+    }
+
+    if species.is_empty() {
+        return Err(locale.get("boxes.species_required").into());
+    }
+    let Some(pokemon_id) = get_pokemon_id(db, species) else {
+        return Err(locale.format("boxes.unknown_species", &[&species]));
+    };
+
+    let ivs = if ivs.is_empty() {
+        Ivs::default()
+    } else {
+        Ivs::parse(ivs).ok_or_else(|| locale.get("boxes.bad_ivs").to_string())?
+    };
+
+    let moves = moves
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(|name| {
+            find_move(db, name).ok_or_else(|| locale.format("boxes.unknown_move", &[&name]))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if moves.len() > MAX_MOVES {
+        return Err(locale.format("boxes.too_many_moves", &[&MAX_MOVES]));
+    }
+
+    let optional = |text: &str| (!text.is_empty()).then(|| text.to_string());
+
+    Ok(OwnedPokemon {
+        owned_id: boxes.selected().and_then(|owned| owned.owned_id),
+        pokemon_id,
+        species: species.into(),
+        nickname: optional(nickname),
+        gender: boxes.gender,
+        nature: boxes.nature,
+        ability: optional(ability),
+        ball: BALLS[boxes.ball].into(),
+        ivs,
+        moves,
+        box_number: boxes.box_number,
+        slot: boxes.selected,
+    })
+}
+
+fn save_owned_on_click(
+    mut click: Trigger<Pointer<Click>>,
+    inputs: Query<(&TextInputContents, &FormInput)>,
+    mut boxes: ResMut<Boxes>,
+    locale: Res<Locale>,
+    db: NonSend<Database>,
+) {
+    click.propagate(false);
+
+    if click.button != PointerButton::Primary {
+        return;
+    }
+
+    let owned = match read_form(&boxes, &inputs, &locale, &db) {
+        Ok(owned) => owned,
+        Err(status) => {
+            boxes.status = status;
+            return;
+        }
+    };
+
+    match save_owned(&db, &owned) {
+        Ok(_) => {
+            boxes.refresh(&db);
+            boxes.status = locale.format("boxes.saved", &[&owned.name()]);
+        }
+        Err(e) => boxes.status = locale.format("editor.error", &[&e]),
+    }
+}
+
+fn release_owned_on_click(
+    mut click: Trigger<Pointer<Click>>,
+    mut boxes: ResMut<Boxes>,
+    locale: Res<Locale>,
+    db: NonSend<Database>,
+) {
+    click.propagate(false);
+
+    if click.button != PointerButton::Primary {
+        return;
+    }
+
+    let Some(owned) = boxes.selected().cloned() else {
+        boxes.status = locale.get("boxes.empty_slot").into();
+        return;
+    };
+
+    match release_owned(&db, &owned) {
+        Ok(()) => {
+            boxes.refresh(&db);
+            boxes.status = locale.format("boxes.released", &[&owned.name()]);
+        }
+        Err(e) => boxes.status = locale.format("editor.error", &[&e]),
+    }
+}
+
+/// Sends the Pokemon in the selected slot to the Breed screen as the mother,
+/// or as the other parent.
+fn use_as_parent_on_click(
+    mother: bool,
+) -> impl Fn(
+    Trigger<Pointer<Click>>,
+    ResMut<Boxes>,
+    ResMut<Session>,
+    Res<Locale>,
+    ResMut<NextState<MenuState>>,
+) {
+    move |mut click, mut boxes, mut session, locale, mut next_state| {
+        click.propagate(false);
+
+        if click.button != PointerButton::Primary {
+            return;
+        }
+
+        let Some(owned) = boxes.selected().cloned() else {
+            boxes.status = locale.get("boxes.empty_slot").into();
+            return;
+        };
+
+        if mother {
+            session.mother = owned.name().into();
+            session.parents.mother = Some(owned);
+        } else {
+            session.other = owned.name().into();
+            session.parents.other = Some(owned);
+        }
+        next_state.set(MenuState::Breed);
+    }
+}
+
+/// Lays out the slots of the box, and fills in the form for the selected one.
+fn boxes_redraw(
+    mut commands: Commands,
+    mut boxes: ResMut<Boxes>,
+    grid: Single<Entity, With<BoxGrid>>,
+    mut box_label: Single<&mut Text, (With<BoxLabel>, Without<ChoiceValue>, Without<BoxesStatus>)>,
+    mut choices: Query<(&mut Text, &ChoiceValue), (Without<BoxLabel>, Without<BoxesStatus>)>,
+    mut status: Single<&mut Text, (With<BoxesStatus>, Without<BoxLabel>, Without<ChoiceValue>)>,
+    mut inputs: Query<(&mut TextInputQueue, &FormInput)>,
+    asset_server: Res<AssetServer>,
+    locale: Res<Locale>,
+) {
+    box_label.0 = locale.format("boxes.box", &[&boxes.box_number, &BOX_COUNT]);
+    status.0 = boxes.status.clone();
+
+    for (mut text, ChoiceValue(choice)) in &mut choices {
+        text.0 = match choice {
+            Choice::Gender => locale.get(gender_key(boxes.gender)).into(),
+            Choice::Nature => <&str>::from(boxes.nature).into(),
+            Choice::Ball => BALLS[boxes.ball].into(),
+        };
+    }
+
+    commands.entity(*grid).despawn_related::<Children>();
+    commands.entity(*grid).with_children(|builder| {
+        for slot in 0..BOX_SIZE {
+            let mut button = builder.spawn((
+                Button,
+                Node {
+                    width: Val::Px(SLOT_SIZE),
+                    height: Val::Px(SLOT_SIZE),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                ThemedBackground::Button,
+                SlotButton(slot),
+            ));
+            button.observe(slot_on_click);
+
+            if let Some(owned) = boxes.in_slot(slot) {
+                button.with_child(pokemon_sprite(owned.pokemon_id, &asset_server));
+            }
+            if boxes.selected == slot {
+                button.insert(SelectedOption);
+            }
+        }
+    });
+
+    if !boxes.refill_form {
+        return;
+    }
+    boxes.bypass_change_detection().refill_form = false;
+
+    let owned = boxes.selected();
+    for (mut queue, input) in &mut inputs {
+        let text = match (owned, input) {
+            (None, _) => String::new(),
+            (Some(owned), FormInput::Species) => owned.species.clone(),
+            (Some(owned), FormInput::Nickname) => owned.nickname.clone().unwrap_or_default(),
+            (Some(owned), FormInput::Ability) => owned.ability.clone().unwrap_or_default(),
+            (Some(owned), FormInput::Ivs) => owned.ivs.to_string(),
+            (Some(owned), FormInput::Moves) => owned.moves.join(", "),
+        };
+        set_input_text(&mut queue, &text);
+    }
+}
+
+/// The [`Locale`] key of a gender's name.
+pub(super) fn gender_key(gender: Gender) -> &'static str {
+    match gender {
+        Gender::Male => "boxes.gender.male",
+        Gender::Female => "boxes.gender.female",
+        Gender::Genderless => "boxes.gender.genderless",
+    }
+}
@@ -0,0 +1,177 @@
+//! The Compare screen, laying a few species out in columns with [`compare`]
+//! to pick a breeding partner, with the cells that differ highlighted.
+
+use super::*;
+
+use crate::compare::{CompareError, Comparison, MAX_SPECIES, MIN_SPECIES, RowKind, compare};
+
+const COLUMN_WIDTH: f32 = 220.0;
+/// How long the list of species typed can be.
+const SPECIES_MAX_CHARS: usize = 80;
+
+pub struct ComparePlugin;
+
+impl Plugin for ComparePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(MenuState::Compare),
+            (compare_enter, restore_inputs).chain(),
+        )
+        .add_systems(
+            Update,
+            compare_redraw
+                .run_if(in_state(MenuState::Compare).and(resource_exists_and_changed::<Comparer>)),
+        );
+    }
+}
+
+/// The species typed on the Compare screen, separated by commas.
+#[derive(Component)]
+pub(super) struct CompareInput;
+
+/// The last comparison, or why there isn't one.
+#[derive(Resource, Default)]
+struct Comparer {
+    comparison: Option<Comparison>,
+    message: String,
+}
+
+#[derive(Component)]
+struct CompareStatus;
+
+/// The grid the comparison is spawned into, a column per species after
+/// the titles of the rows.
+#[derive(Component)]
+struct CompareTable;
+
+fn compare_enter(mut commands: Commands, font: Res<GameFont>, mut session: ResMut<Session>) {
+    commands.insert_resource(Comparer::default());
+
+    // Start from the parents on the Breed screen.
+    if session.compare.is_empty() {
+        let parents = [&session.mother, &session.other]
+            .into_iter()
+            .filter(|name| !name.trim().is_empty())
+            .cloned()
+            .collect::<Vec<_>>();
+        session.bypass_change_detection().compare = parents.join(", ");
+    }
+
+    spawn_screen(&mut commands, &font, MenuState::Compare, |ui| {
+        ui.long_text_input("compare.species", Some(SPECIES_MAX_CHARS), CompareInput);
+        ui.result_panel(CompareStatus);
+        ui.grid(MIN_SPECIES + 1, COLUMN_WIDTH, CompareTable);
+        ui.row(|ui| {
+            ui.button("compare.run", compare_on_click)
+                .insert(SubmitButton);
+            ui.back_button();
+        });
+    });
+}
+
+fn compare_on_click(
+    mut click: Trigger<Pointer<Click>>,
+    input: Single<&TextInputContents, With<CompareInput>>,
+    mut comparer: ResMut<Comparer>,
+    locale: Res<Locale>,
+    db: NonSend<Database>,
+) {
+    click.propagate(false);
+
+    if click.button != PointerButton::Primary {
+        return;
+    }
+
+    let names = input
+        .get()
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .collect::<Vec<_>>();
+
+    *comparer = match compare(&db, &names, locale.code()) {
+        Ok(comparison) => Comparer {
+            comparison: Some(comparison),
+            message: String::new(),
+        },
+        Err(e) => Comparer {
+            comparison: None,
+            message: match e {
+                CompareError::Count(_) => {
+                    locale.format("compare.count", &[&MIN_SPECIES, &MAX_SPECIES])
+                }
+                CompareError::Unknown(name) => locale.format("compare.unknown", &[&name]),
+            },
+        },
+    };
+}
+
+fn compare_redraw(
+    mut commands: Commands,
+    comparer: Res<Comparer>,
+    table: Single<(Entity, &mut Node), With<CompareTable>>,
+    mut status: Single<&mut Text, With<CompareStatus>>,
+    font: Res<GameFont>,
+    icons: Res<TypeIcons>,
+    asset_server: Res<AssetServer>,
+    locale: Res<Locale>,
+) {
+    status.0 = comparer.message.clone();
+
+    let (table, mut node) = table.into_inner();
+    commands.entity(table).despawn_related::<Children>();
+    let Some(comparison) = &comparer.comparison else {
+        return;
+    };
+    node.grid_template_columns = RepeatedGridTrack::px(comparison.species.len() + 1, COLUMN_WIDTH);
+
+    let style = small_text_style(&font);
+    let cell_node = Node {
+        padding: UiRect::all(Val::Px(5.0)),
+        flex_direction: FlexDirection::Column,
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        ..default()
+    };
+    let cell = |text: String| {
+        (
+            cell_node.clone(),
+            Pickable::IGNORE,
+            children![(style.clone(), Text::new(text), Pickable::IGNORE)],
+        )
+    };
+
+    commands.entity(table).with_children(|builder| {
+        // The names head the columns, under an empty corner.
+        builder.spawn(cell(String::new()));
+        for sheet in &comparison.species {
+            builder
+                .spawn(cell(sheet.name.clone()))
+                .with_child(pokemon_sprite(sheet.pokemon_id, &asset_server));
+        }
+
+        for row in comparison.rows(&locale) {
+            builder.spawn(cell(row.title));
+            for (i, (text, sheet)) in row.cells.into_iter().zip(&comparison.species).enumerate() {
+                // The types are shown as badges rather than their names.
+                let mut spawned = if row.kind == RowKind::Types {
+                    builder.spawn((
+                        cell_node.clone(),
+                        Pickable::IGNORE,
+                        children![type_badges(
+                            &sheet.types[0],
+                            sheet.types.get(1).map(String::as_str),
+                            &font,
+                            &icons
+                        )],
+                    ))
+                } else {
+                    builder.spawn(cell(text))
+                };
+                if let Some(heat) = &row.heat {
+                    spawned.insert(ThemedBackground::Heat(heat[i]));
+                }
+            }
+        }
+    });
+}
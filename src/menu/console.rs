@@ -0,0 +1,381 @@
+//! A console for running SQL against the open database, only built with the
+//! `debug` feature. F12 opens it from any screen.
+//!
+//! Statements that would change the database are refused until writes are
+//! turned on, and writes are turned back off every time the console opens.
+//! Transactions and attached databases are always refused, since SQLite
+//! counts them as reads but they'd get in the way of recording changes.
+
+use super::*;
+
+/// Rows past this are counted but not shown, so a big `SELECT` doesn't
+/// spawn thousands of cells.
+const MAX_ROWS: usize = 100;
+const CONSOLE_WIDTH: f32 = 900.0;
+const CONSOLE_HEIGHT: f32 = 200.0;
+/// Statements that don't write to the database but aren't safe to run.
+const REFUSED_KEYWORDS: &[&str] = &["ATTACH", "DETACH"];
+
+pub struct ConsolePlugin;
+
+impl Plugin for ConsolePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(MenuState::Console), console_enter)
+            .add_systems(
+                Update,
+                (
+                    open_console.run_if(not(in_state(MenuState::Loading))),
+                    console_redraw.run_if(
+                        in_state(MenuState::Console).and(resource_exists_and_changed::<Console>),
+                    ),
+                ),
+            );
+    }
+}
+
+/// Whether writes are allowed, and what the last statement did.
+#[derive(Resource, Default)]
+struct Console {
+    allow_writes: bool,
+    output: Option<Result<SqlOutput, ConsoleError>>,
+}
+
+enum SqlOutput {
+    Rows {
+        columns: Vec<String>,
+        /// At most [`MAX_ROWS`] of them.
+        rows: Vec<Vec<String>>,
+        total: usize,
+    },
+    Changed(usize),
+}
+
+enum ConsoleError {
+    /// The statement writes, and writes are off.
+    ReadOnly,
+    /// The statement starts a transaction or attaches a database.
+    Refused,
+    Sqlite(rusqlite::Error),
+}
+
+impl From<rusqlite::Error> for ConsoleError {
+    fn from(e: rusqlite::Error) -> Self {
+        ConsoleError::Sqlite(e)
+    }
+}
+
+#[derive(Component)]
+struct ConsoleInput;
+
+/// The text of the button toggling writes.
+#[derive(Component)]
+struct WritesLabel;
+
+#[derive(Component)]
+struct ConsoleStatus;
+
+/// The grid the result table is spawned into.
+#[derive(Component)]
+struct ConsoleTable;
+
+fn open_console(keys: Res<ButtonInput<KeyCode>>, mut next_state: ResMut<NextState<MenuState>>) {
+    if keys.just_pressed(KeyCode::F12) {
+        next_state.set(MenuState::Console);
+    }
+}
+
+fn console_enter(mut commands: Commands, font: Res<GameFont>) {
+    commands.insert_resource(Console::default());
+
+    spawn_screen(&mut commands, &font, MenuState::Console, |ui| {
+        ui.label("console.sql");
+        ui.spawn((
+            Node {
+                width: Val::Px(CONSOLE_WIDTH),
+                height: Val::Px(CONSOLE_HEIGHT),
+                ..default()
+            },
+            TextInputContents::default(),
+            ThemedBackground::TextInput,
+            TextInputNode {
+                clear_on_submit: false,
+                // Multi-line, so long queries can be laid out.
+                mode: TextInputMode::default(),
+                focus_on_pointer_down: true,
+                unfocus_on_submit: false,
+                ..default()
+            },
+            InputLabel("console.sql"),
+            small_text_style(&font),
+            ConsoleInput,
+        ));
+
+        ui.row(|ui| {
+            ui.button("console.run", run_sql_on_click)
+                .insert(SubmitButton);
+            ui.dynamic_button(WritesLabel, toggle_writes_on_click);
+        });
+        ui.result_panel(ConsoleStatus);
+        ui.spawn((
+            Node {
+                display: Display::Grid,
+                grid_template_columns: vec![],
+                column_gap: Val::Px(2.0),
+                row_gap: Val::Px(2.0),
+                margin: UiRect::all(Val::Px(10.0)),
+                max_width: Val::Px(CONSOLE_WIDTH),
+                overflow: Overflow::clip(),
+                ..default()
+            },
+            ConsoleTable,
+        ));
+        ui.back_button();
+    });
+}
+
+fn run_sql_on_click(
+    mut click: Trigger<Pointer<Click>>,
+    input: Single<&TextInputContents, With<ConsoleInput>>,
+    mut console: ResMut<Console>,
+    db: NonSend<Database>,
+) {
+    click.propagate(false);
+
+    if click.button != PointerButton::Primary {
+        return;
+    }
+
+    let sql = input.get().trim();
+    if sql.is_empty() {
+        return;
+    }
+
+    console.output = Some(run_sql(&db, sql, console.allow_writes));
+}
+
+fn toggle_writes_on_click(mut click: Trigger<Pointer<Click>>, mut console: ResMut<Console>) {
+    click.propagate(false);
+
+    if click.button == PointerButton::Primary {
+        console.allow_writes = !console.allow_writes;
+    }
+}
+
+/// Runs one statement, refusing it if it writes and `allow_writes` is off.
+fn run_sql(db: &Database, sql: &str, allow_writes: bool) -> Result<SqlOutput, ConsoleError> {
+    let keyword = first_keyword(sql);
+    if REFUSED_KEYWORDS
+        .iter()
+        .any(|refused| refused.eq_ignore_ascii_case(keyword))
+    {
+        return Err(ConsoleError::Refused);
+    }
+
+    let mut statement = db.connection.prepare(sql)?;
+
+    if statement.readonly() {
+        let output = step(&mut statement)?;
+        // `BEGIN` and `SAVEPOINT` count as reads, but would leave a
+        // transaction open that every recorded change after would fail on.
+        if !db.connection.is_autocommit() {
+            db.connection.execute_batch("ROLLBACK")?;
+            return Err(ConsoleError::Refused);
+        }
+        return Ok(output);
+    }
+    if !allow_writes {
+        return Err(ConsoleError::ReadOnly);
+    }
+
+    // Writes are recorded, so they can be undone in the data editor, even
+    // those that return rows.
+    db.record(sql, |_| step(&mut statement))
+}
+
+/// The first word of `sql`, past any whitespace and comments.
+fn first_keyword(sql: &str) -> &str {
+    let mut rest = sql.trim_start();
+    loop {
+        if let Some(comment) = rest.strip_prefix("--") {
+            rest = comment.split_once('\n').map_or("", |(_, rest)| rest);
+        } else if let Some(comment) = rest.strip_prefix("/*") {
+            rest = comment.split_once("*/").map_or("", |(_, rest)| rest);
+        } else {
+            break;
+        }
+        rest = rest.trim_start();
+    }
+
+    let end = rest
+        .find(|c: char| !c.is_ascii_alphabetic())
+        .unwrap_or(rest.len());
+    &rest[..end]
+}
+
+/// Runs a statement to the end, keeping the rows it returns if it has any.
+fn step(statement: &mut rusqlite::Statement) -> Result<SqlOutput, ConsoleError> {
+    if statement.column_count() == 0 {
+        return Ok(SqlOutput::Changed(statement.execute([])?));
+    }
+
+    let columns = statement
+        .column_names()
+        .into_iter()
+        .map(String::from)
+        .collect::<Vec<_>>();
+
+    let mut rows = Vec::new();
+    let mut total = 0;
+    let mut results = statement.query([])?;
+    while let Some(row) = results.next()? {
+        total += 1;
+        if rows.len() < MAX_ROWS {
+            rows.push(
+                (0..columns.len())
+                    .map(|i| row.get_ref(i).map(value_text))
+                    .collect::<Result<_, _>>()?,
+            );
+        }
+    }
+
+    Ok(SqlOutput::Rows {
+        columns,
+        rows,
+        total,
+    })
+}
+
+fn console_redraw(
+    mut commands: Commands,
+    console: Res<Console>,
+    mut writes_label: Single<&mut Text, (With<WritesLabel>, Without<ConsoleStatus>)>,
+    mut status: Single<&mut Text, (With<ConsoleStatus>, Without<WritesLabel>)>,
+    table: Single<(Entity, &mut Node), With<ConsoleTable>>,
+    font: Res<GameFont>,
+    locale: Res<Locale>,
+) {
+    writes_label.0 = locale
+        .get(if console.allow_writes {
+            "console.writes_on"
+        } else {
+            "console.writes_off"
+        })
+        .into();
+
+    let (table, mut node) = table.into_inner();
+    commands.entity(table).despawn_related::<Children>();
+
+    let (columns, rows) = match &console.output {
+        None => {
+            status.0 = "".into();
+            return;
+        }
+        Some(Err(ConsoleError::ReadOnly)) => {
+            status.0 = locale.get("console.read_only").into();
+            return;
+        }
+        Some(Err(ConsoleError::Refused)) => {
+            status.0 = locale.get("console.refused").into();
+            return;
+        }
+        Some(Err(ConsoleError::Sqlite(e))) => {
+            status.0 = locale.format("console.error", &[e]);
+            return;
+        }
+        Some(Ok(SqlOutput::Changed(changed))) => {
+            status.0 = locale.format("console.changed", &[changed]);
+            return;
+        }
+        Some(Ok(SqlOutput::Rows {
+            columns,
+            rows,
+            total,
+        })) => {
+            status.0 = if *total > rows.len() {
+                locale.format("console.rows_truncated", &[total, &rows.len()])
+            } else {
+                locale.format("console.rows", &[total])
+            };
+            (columns, rows)
+        }
+    };
+
+    node.grid_template_columns = RepeatedGridTrack::auto(columns.len() as u16);
+
+    let style = small_text_style(&font);
+    commands.entity(table).with_children(|builder| {
+        let header = columns.iter().map(|cell| (cell, ThemedBackground::Button));
+        let body = rows
+            .iter()
+            .flatten()
+            .map(|cell| (cell, ThemedBackground::TextInput));
+
+        for (cell, background) in header.chain(body) {
+            builder.spawn((
+                Node {
+                    padding: UiRect::horizontal(Val::Px(5.0)),
+                    ..default()
+                },
+                background,
+                children![(style.clone(), Text::new(cell.clone()), Pickable::IGNORE)],
+            ));
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transactions_are_refused() {
+        let db = Database::open(None).unwrap();
+
+        for sql in ["BEGIN", "SAVEPOINT console"] {
+            assert!(matches!(
+                run_sql(&db, sql, true),
+                Err(ConsoleError::Refused)
+            ));
+            assert!(db.connection.is_autocommit());
+        }
+        db.record("Still recorded", |db| {
+            db.connection
+                .execute("DELETE FROM pokemon WHERE pokemon_id = 150", ())
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn attaching_is_refused() {
+        let db = Database::open(None).unwrap();
+
+        for sql in [
+            "ATTACH 'other.db' AS other",
+            "  -- a comment\n/* another */ detach other",
+        ] {
+            assert!(matches!(
+                run_sql(&db, sql, true),
+                Err(ConsoleError::Refused)
+            ));
+        }
+    }
+
+    #[test]
+    fn writes_wait_for_writes_on() {
+        let db = Database::open(None).unwrap();
+        let delete = "DELETE FROM pokemon WHERE pokemon_id = 150";
+
+        assert!(matches!(
+            run_sql(&db, delete, false),
+            Err(ConsoleError::ReadOnly)
+        ));
+        assert!(matches!(
+            run_sql(&db, delete, true),
+            Ok(SqlOutput::Changed(1))
+        ));
+        assert!(matches!(
+            run_sql(&db, "SELECT name FROM pokemon", false),
+            Ok(SqlOutput::Rows { .. })
+        ));
+    }
+}
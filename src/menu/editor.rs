@@ -0,0 +1,511 @@
+//! The data editor, for adding, fixing and removing rows of the data tables
+//! without touching the schema.
+//!
+//! Mistakes are shown under the field they are in, whether they are caught
+//! by the form or by a constraint in the database. Every change can be
+//! undone with Ctrl+Z and redone with Ctrl+Shift+Z, even after a restart.
+
+use super::*;
+
+use rusqlite::types::Value;
+use strum::IntoEnumIterator;
+
+/// How many rows are listed on one page.
+const ROW_PAGE_SIZE: usize = 6;
+const ROW_WIDTH: f32 = 900.0;
+const FORM_COLUMN_WIDTH: f32 = 520.0;
+
+pub struct EditorPlugin;
+
+impl Plugin for EditorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(MenuState::Editor), editor_enter)
+            .add_systems(
+                Update,
+                (
+                    replay_on_key,
+                    editor_redraw.run_if(resource_exists_and_changed::<Editor>),
+                    feedback_redraw.run_if(resource_exists_and_changed::<EditorFeedback>),
+                )
+                    .chain()
+                    .run_if(in_state(MenuState::Editor)),
+            );
+    }
+}
+
+/// The table being edited, and which of its rows.
+#[derive(Resource)]
+struct Editor {
+    table: Table,
+    rows: Vec<Row>,
+    page: usize,
+    /// The index of the row being edited, or `None` for a new row.
+    selected: Option<usize>,
+    /// Whether the form needs filling in again. Paging leaves it alone, so
+    /// what has been typed isn't thrown away.
+    refill_form: bool,
+}
+
+impl Editor {
+    fn new(table: Table, db: &Database) -> Self {
+        Self {
+            table,
+            rows: get_rows(db, table),
+            page: 0,
+            selected: None,
+            refill_form: true,
+        }
+    }
+
+    /// Starts editing the row at `selected`, or a new row.
+    fn select(&mut self, selected: Option<usize>) {
+        self.selected = selected;
+        self.refill_form = true;
+    }
+
+    fn page_count(&self) -> usize {
+        self.rows.len().div_ceil(ROW_PAGE_SIZE).max(1)
+    }
+
+    /// Reloads the rows after a change, selecting the one with primary key `key`.
+    fn refresh(&mut self, db: &Database, key: Option<&[Value]>) {
+        self.rows = get_rows(db, self.table);
+        self.select(key.and_then(|key| {
+            self.rows
+                .iter()
+                .position(|row| self.table.key_of(row) == key)
+        }));
+        self.page = match self.selected {
+            Some(selected) => selected / ROW_PAGE_SIZE,
+            None => self.page.min(self.page_count() - 1),
+        };
+    }
+}
+
+/// What came of the last save or delete.
+#[derive(Resource, Default)]
+struct EditorFeedback {
+    /// Problems with fields, by their index into [`Table::fields`].
+    fields: Vec<(usize, Problem)>,
+    status: String,
+}
+
+impl EditorFeedback {
+    fn status(status: String) -> Self {
+        Self {
+            fields: Vec::new(),
+            status,
+        }
+    }
+
+    fn from_error(e: EditError, locale: &Locale) -> Self {
+        match e {
+            EditError::Fields(fields) => Self {
+                fields,
+                status: locale.get("editor.invalid").into(),
+            },
+            EditError::Sqlite(e) => Self::status(locale.format("editor.error", &[&e])),
+        }
+    }
+}
+
+#[derive(Component)]
+struct TableLabel;
+
+#[derive(Component)]
+struct RowList;
+
+#[derive(Component)]
+struct RowPageLabel;
+
+/// A row in the list, holding its index into [`Editor::rows`].
+#[derive(Component)]
+struct RowButton(usize);
+
+/// The grid the fields of the form are spawned into.
+#[derive(Component)]
+struct EditorForm;
+
+/// The input of a field, holding its index into [`Table::fields`].
+#[derive(Component)]
+struct FieldInput(usize);
+
+/// Where problems with a field are shown.
+#[derive(Component)]
+struct FieldProblems(usize);
+
+#[derive(Component)]
+struct EditorStatus;
+
+/// The [`Locale`] key of a table's name.
+fn table_key(table: Table) -> &'static str {
+    match table {
+        Table::Pokemon => "editor.table.pokemon",
+        Table::EggGroup => "editor.table.egg_group",
+        Table::Move => "editor.table.move",
+        Table::PokemonEggGroup => "editor.table.pokemon_egg_group",
+        Table::PokemonMove => "editor.table.pokemon_move",
+    }
+}
+
+/// The [`Locale`] key of a field's label.
+fn field_key(field: &Field) -> &'static str {
+    match field.kind {
+        FieldKind::Id { generated: false } => "editor.field.id",
+        FieldKind::Id { generated: true } => "editor.field.generated_id",
+        FieldKind::Reference(Table::Pokemon) => "editor.field.pokemon",
+        FieldKind::Reference(Table::EggGroup) => "editor.field.egg_group",
+        FieldKind::Reference(Table::Move) => "editor.field.move",
+        _ => match field.column {
+            "name" => "editor.field.name",
+            "primary_type" => "editor.field.primary_type",
+            "secondary_type" => "editor.field.secondary_type",
+            "type" => "editor.field.type",
+            "category" => "editor.field.category",
+            "power" => "editor.field.power",
+            "accuracy" => "editor.field.accuracy",
+            "method" => "editor.field.method",
+            other => other,
+        },
+    }
+}
+
+fn problem_text(problem: &Problem, locale: &Locale) -> String {
+    match problem {
+        Problem::Required => locale.get("editor.required").into(),
+        Problem::NotANumber => locale.get("editor.not_a_number").into(),
+        Problem::NotPositive => locale.get("editor.not_positive").into(),
+        Problem::NotAChoice(choices) => {
+            locale.format("editor.not_a_choice", &[&choices.join(", ")])
+        }
+        Problem::Missing(table) => {
+            locale.format("editor.missing", &[&locale.get(table_key(*table))])
+        }
+        Problem::Taken => locale.get("editor.taken").into(),
+        Problem::Check(message) => locale.format("editor.check", &[message]),
+        Problem::InUse => locale.get("editor.in_use").into(),
+    }
+}
+
+fn editor_enter(mut commands: Commands, font: Res<GameFont>, db: NonSend<Database>) {
+    commands.insert_resource(Editor::new(Table::Pokemon, &db));
+    commands.insert_resource(EditorFeedback::default());
+
+    spawn_screen(&mut commands, &font, MenuState::Editor, |ui| {
+        ui.pager(TableLabel, table_on_click(-1), table_on_click(1));
+        ui.list(RowList);
+        ui.pager(RowPageLabel, row_page_on_click(-1), row_page_on_click(1));
+        ui.grid(2, FORM_COLUMN_WIDTH, EditorForm);
+        ui.result_panel(EditorStatus);
+        ui.row(|ui| {
+            ui.button("editor.new", new_row_on_click);
+            ui.button("editor.save", save_row_on_click)
+                .insert(SubmitButton);
+            ui.button("editor.delete", delete_row_on_click);
+        });
+        ui.row(|ui| {
+            ui.button("editor.undo", replay_on_click(false));
+            ui.button("editor.redo", replay_on_click(true));
+            ui.back_button();
+        });
+    });
+}
+
+fn table_on_click(
+    step: isize,
+) -> impl Fn(Trigger<Pointer<Click>>, ResMut<Editor>, ResMut<EditorFeedback>, NonSend<Database>) {
+    move |mut click, mut editor, mut feedback, db| {
+        click.propagate(false);
+
+        if click.button != PointerButton::Primary {
+            return;
+        }
+
+        let table = step_through(Table::iter().collect(), editor.table, step);
+
+        *editor = Editor::new(table, &db);
+        *feedback = default();
+    }
+}
+
+fn row_page_on_click(step: isize) -> impl Fn(Trigger<Pointer<Click>>, ResMut<Editor>) {
+    move |mut click, mut editor| {
+        click.propagate(false);
+
+        if click.button != PointerButton::Primary {
+            return;
+        }
+
+        let page = editor
+            .page
+            .saturating_add_signed(step)
+            .min(editor.page_count() - 1);
+
+        if page != editor.page {
+            editor.page = page;
+        }
+    }
+}
+
+fn row_on_click(
+    mut click: Trigger<Pointer<Click>>,
+    buttons: Query<&RowButton>,
+    mut editor: ResMut<Editor>,
+    mut feedback: ResMut<EditorFeedback>,
+) {
+    click.propagate(false);
+
+    if click.button != PointerButton::Primary {
+        return;
+    }
+
+    if let Ok(RowButton(index)) = buttons.get(click.target()) {
+        editor.select(Some(*index));
+        *feedback = default();
+    }
+}
+
+fn new_row_on_click(
+    mut click: Trigger<Pointer<Click>>,
+    mut editor: ResMut<Editor>,
+    mut feedback: ResMut<EditorFeedback>,
+) {
+    click.propagate(false);
+
+    if click.button == PointerButton::Primary {
+        editor.select(None);
+        *feedback = default();
+    }
+}
+
+fn save_row_on_click(
+    mut click: Trigger<Pointer<Click>>,
+    inputs: Query<(&TextInputContents, &FieldInput)>,
+    mut editor: ResMut<Editor>,
+    mut feedback: ResMut<EditorFeedback>,
+    locale: Res<Locale>,
+    db: NonSend<Database>,
+) {
+    click.propagate(false);
+
+    if click.button != PointerButton::Primary {
+        return;
+    }
+
+    let table = editor.table;
+    let mut texts = vec![String::new(); table.fields().len()];
+    for (contents, FieldInput(field)) in &inputs {
+        texts[*field] = contents.get().into();
+    }
+
+    let saved = match editor.selected {
+        None => insert_row(&db, table, &texts),
+        Some(index) => update_row(&db, table, &table.key_of(&editor.rows[index]), &texts),
+    };
+
+    match saved {
+        Ok(key) => {
+            editor.refresh(&db, Some(&key));
+            *feedback = EditorFeedback::status(locale.get("editor.saved").into());
+        }
+        Err(e) => *feedback = EditorFeedback::from_error(e, &locale),
+    }
+}
+
+fn delete_row_on_click(
+    mut click: Trigger<Pointer<Click>>,
+    mut editor: ResMut<Editor>,
+    mut feedback: ResMut<EditorFeedback>,
+    locale: Res<Locale>,
+    db: NonSend<Database>,
+) {
+    click.propagate(false);
+
+    if click.button != PointerButton::Primary {
+        return;
+    }
+
+    let Some(index) = editor.selected else {
+        return;
+    };
+    let table = editor.table;
+
+    match delete_row(&db, table, &table.key_of(&editor.rows[index])) {
+        Ok(()) => {
+            editor.refresh(&db, None);
+            *feedback = EditorFeedback::status(locale.get("editor.deleted").into());
+        }
+        Err(e) => *feedback = EditorFeedback::from_error(e, &locale),
+    }
+}
+
+/// Undoes the last change, or redoes the last undone one, and shows the
+/// rows as they are now.
+fn replay_change(
+    redo: bool,
+    editor: &mut Editor,
+    feedback: &mut EditorFeedback,
+    locale: &Locale,
+    db: &Database,
+) {
+    let replayed = if redo { db.redo() } else { db.undo() };
+
+    *feedback = match replayed {
+        Ok(Some(description)) => {
+            let key = editor
+                .selected
+                .map(|index| editor.table.key_of(&editor.rows[index]));
+            editor.refresh(db, key.as_deref());
+
+            let key = if redo {
+                "editor.redone"
+            } else {
+                "editor.undone"
+            };
+            EditorFeedback::status(locale.format(key, &[&description]))
+        }
+        Ok(None) => {
+            let key = if redo {
+                "editor.nothing_to_redo"
+            } else {
+                "editor.nothing_to_undo"
+            };
+            EditorFeedback::status(locale.get(key).into())
+        }
+        Err(e) => EditorFeedback::status(locale.format("editor.error", &[&e])),
+    };
+}
+
+fn replay_on_click(
+    redo: bool,
+) -> impl Fn(
+    Trigger<Pointer<Click>>,
+    ResMut<Editor>,
+    ResMut<EditorFeedback>,
+    Res<Locale>,
+    NonSend<Database>,
+) {
+    move |mut click, mut editor, mut feedback, locale, db| {
+        click.propagate(false);
+
+        if click.button == PointerButton::Primary {
+            replay_change(redo, &mut editor, &mut feedback, &locale, &db);
+        }
+    }
+}
+
+/// Ctrl+Z undoes, and Ctrl+Shift+Z redoes, unless a text input has the
+/// focus, where they are for the text.
+fn replay_on_key(
+    keys: Res<ButtonInput<KeyCode>>,
+    focus: Res<InputFocus>,
+    text_inputs: Query<(), With<TextInputNode>>,
+    mut editor: ResMut<Editor>,
+    mut feedback: ResMut<EditorFeedback>,
+    locale: Res<Locale>,
+    db: NonSend<Database>,
+) {
+    if !keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight])
+        || !keys.just_pressed(KeyCode::KeyZ)
+        || focus.0.is_some_and(|focused| text_inputs.contains(focused))
+    {
+        return;
+    }
+
+    let redo = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    replay_change(redo, &mut editor, &mut feedback, &locale, &db);
+}
+
+/// Lists the rows on the current page, and fills in the form for the
+/// selected one.
+fn editor_redraw(
+    mut commands: Commands,
+    mut editor: ResMut<Editor>,
+    list: Single<Entity, With<RowList>>,
+    form: Single<Entity, With<EditorForm>>,
+    mut table_label: Single<&mut Text, (With<TableLabel>, Without<RowPageLabel>)>,
+    mut page_label: Single<&mut Text, (With<RowPageLabel>, Without<TableLabel>)>,
+    font: Res<GameFont>,
+    locale: Res<Locale>,
+) {
+    table_label.0 = locale.get(table_key(editor.table)).into();
+    page_label.0 = locale.format("common.page", &[&(editor.page + 1), &editor.page_count()]);
+
+    commands.entity(*list).despawn_related::<Children>();
+
+    let style = small_text_style(&font);
+    commands.entity(*list).with_children(|builder| {
+        for (index, row) in editor
+            .rows
+            .iter()
+            .enumerate()
+            .skip(editor.page * ROW_PAGE_SIZE)
+            .take(ROW_PAGE_SIZE)
+        {
+            let text = row
+                .iter()
+                .map(|value| value_text(value.into()))
+                .collect::<Vec<_>>()
+                .join(" | ");
+
+            let mut button = builder.spawn((
+                Button,
+                Node {
+                    width: Val::Px(ROW_WIDTH),
+                    padding: UiRect::horizontal(Val::Px(10.0)),
+                    ..default()
+                },
+                ThemedBackground::Button,
+                RowButton(index),
+                children![(style.clone(), Text::new(text), Pickable::IGNORE)],
+            ));
+            button.observe(row_on_click);
+
+            if editor.selected == Some(index) {
+                button.insert(SelectedOption);
+            }
+        }
+    });
+
+    if !editor.refill_form {
+        return;
+    }
+    editor.bypass_change_detection().refill_form = false;
+
+    let row = editor.selected.map(|index| &editor.rows[index]);
+    commands.entity(*form).despawn_related::<Children>();
+    commands.entity(*form).with_children(|builder| {
+        let mut ui = Ui::new(builder, &font);
+
+        for (index, field) in editor.table.fields().iter().enumerate() {
+            let mut queue = TextInputQueue::default();
+            if let Some(row) = row {
+                set_input_text(&mut queue, &value_input(&row[index]));
+            }
+
+            ui.column(|ui| {
+                ui.text_input(field_key(field), (FieldInput(index), queue));
+                ui.result_panel(FieldProblems(index));
+            });
+        }
+    });
+}
+
+fn feedback_redraw(
+    feedback: Res<EditorFeedback>,
+    mut problems: Query<(&mut Text, &FieldProblems), Without<EditorStatus>>,
+    mut status: Single<&mut Text, With<EditorStatus>>,
+    locale: Res<Locale>,
+) {
+    for (mut text, FieldProblems(field)) in &mut problems {
+        text.0 = feedback
+            .fields
+            .iter()
+            .filter(|(problem_field, _)| problem_field == field)
+            .map(|(_, problem)| problem_text(problem, &locale))
+            .collect::<Vec<_>>()
+            .join("\n");
+    }
+
+    status.0 = feedback.status.clone();
+}
@@ -0,0 +1,256 @@
+//! The egg group matrix, showing how many species each pair of groups shares.
+//!
+
+use std::collections::HashMap;
+
+use super::*;
+
+use bevy::input_focus::tab_navigation::TabIndex;
+
+const CELL_WIDTH: f32 = 90.0;
+const CELL_HEIGHT: f32 = 40.0;
+
+pub struct EggGroupsPlugin;
+
+impl Plugin for EggGroupsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(MenuState::EggGroups), egg_groups_enter)
+            .add_systems(
+                Update,
+                egg_group_members_redraw.run_if(
+                    in_state(MenuState::EggGroups)
+                        .and(resource_exists_and_changed::<EggGroupSelection>),
+                ),
+            );
+    }
+}
+
+/// The pair of egg groups whose shared members are listed.
+///
+/// Selecting a group with itself lists all of its members.
+#[derive(Resource, Default)]
+struct EggGroupSelection(Option<(EggGroup, EggGroup)>);
+
+/// A cell of the matrix, for the species in both of these groups.
+#[derive(Component)]
+struct EggGroupCell(EggGroup, EggGroup);
+
+#[derive(Component)]
+struct EggGroupMembers;
+
+#[derive(Clone, Debug)]
+struct EggGroup {
+    egg_group_id: i64,
+    name: String,
+}
+
+fn egg_groups_enter(mut commands: Commands, font: Res<GameFont>, db: NonSend<Database>) {
+    commands.insert_resource(EggGroupSelection::default());
+
+    let groups = get_egg_groups(&db);
+    let overlap = get_egg_group_overlap(&db);
+    let most = overlap.values().copied().max().unwrap_or(1).max(1);
+
+    let cell_text_style = small_text_style(&font);
+
+    let cell_node = Node {
+        width: Val::Px(CELL_WIDTH),
+        height: Val::Px(CELL_HEIGHT),
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        ..default()
+    };
+
+    spawn_screen(&mut commands, &font, MenuState::EggGroups, |ui| {
+        ui.row(|ui| {
+            ui.spawn(Node {
+                display: Display::Grid,
+                grid_template_columns: RepeatedGridTrack::px(groups.len() + 1, CELL_WIDTH),
+                column_gap: Val::Px(2.0),
+                row_gap: Val::Px(2.0),
+                ..default()
+            })
+            .with_children(|builder| {
+                builder.spawn(cell_node.clone());
+                for group in &groups {
+                    builder.spawn((
+                        cell_node.clone(),
+                        cell_text_style.clone(),
+                        Text::new(group.name.clone()),
+                        Pickable::IGNORE,
+                    ));
+                }
+
+                for row in &groups {
+                    builder
+                        .spawn((
+                            cell_node.clone(),
+                            ThemedBackground::Button,
+                            EggGroupCell(row.clone(), row.clone()),
+                            TabIndex(0),
+                            children![(
+                                cell_text_style.clone(),
+                                Text::new(row.name.clone()),
+                                Pickable::IGNORE
+                            )],
+                        ))
+                        .observe(select_egg_group_on_click);
+
+                    for column in &groups {
+                        let count = overlap
+                            .get(&(row.egg_group_id, column.egg_group_id))
+                            .copied()
+                            .unwrap_or(0);
+                        let heat = count as f32 / most as f32;
+
+                        builder
+                            .spawn((
+                                cell_node.clone(),
+                                ThemedBackground::Heat(heat),
+                                EggGroupCell(row.clone(), column.clone()),
+                                TabIndex(0),
+                                children![(
+                                    cell_text_style.clone(),
+                                    Text::new(count.to_string()),
+                                    Pickable::IGNORE
+                                )],
+                            ))
+                            .observe(select_egg_group_on_click);
+                    }
+                }
+            });
+
+            let (members_font, members_color, _) = ui.text_style();
+            ui.spawn((
+                Node {
+                    width: Val::Px(350.0),
+                    margin: UiRect::left(Val::Px(30.0)),
+                    ..default()
+                },
+                members_font,
+                members_color,
+                TextLayout::new_with_justify(JustifyText::Left),
+                Text::new(""),
+                Pickable::IGNORE,
+                LiveRegion,
+                EggGroupMembers,
+            ));
+        });
+
+        ui.back_button();
+    });
+}
+
+fn select_egg_group_on_click(
+    mut click: Trigger<Pointer<Click>>,
+    cells: Query<&EggGroupCell>,
+    mut selection: ResMut<EggGroupSelection>,
+) {
+    click.propagate(false);
+
+    if click.button != PointerButton::Primary {
+        return;
+    }
+
+    if let Ok(EggGroupCell(row, column)) = cells.get(click.target()) {
+        selection.0 = Some((row.clone(), column.clone()));
+    }
+}
+
+fn egg_group_members_redraw(
+    selection: Res<EggGroupSelection>,
+    mut members: Single<&mut Text, With<EggGroupMembers>>,
+    locale: Res<Locale>,
+    db: NonSend<Database>,
+) {
+    let Some((row, column)) = &selection.0 else {
+        members.0 = locale.get("egg_groups.select").into();
+        return;
+    };
+
+    let shared = get_egg_group_members(&db, row.egg_group_id, column.egg_group_id, locale.code());
+
+    let title = if row.egg_group_id == column.egg_group_id {
+        row.name.clone()
+    } else {
+        format!("{} & {}", row.name, column.name)
+    };
+
+    members.0 = format!(
+        "{title} ({})\n{}",
+        shared.len(),
+        if shared.is_empty() {
+            locale.get("common.none").into()
+        } else {
+            shared.join("\n")
+        }
+    );
+}
+
+fn get_egg_groups(db: &Database) -> Vec<EggGroup> {
+    let query = r#"
+        SELECT egg_group.egg_group_id, egg_group.name
+            FROM egg_group
+            ORDER BY egg_group.egg_group_id
+    "#;
+    let mut query = db.connection.prepare_cached(query).unwrap();
+
+    query
+        .query_map((), |row| {
+            Ok(EggGroup {
+                egg_group_id: row.get(0)?,
+                name: row.get(1)?,
+            })
+        })
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap()
+}
+
+/// How many species are in both of each pair of egg groups.
+///
+/// The count for a group paired with itself is the size of that group.
+fn get_egg_group_overlap(db: &Database) -> HashMap<(i64, i64), u32> {
+    let query = r#"
+        SELECT a.egg_group_id, b.egg_group_id, COUNT(*)
+            FROM pokemon_egg_group AS a
+                JOIN pokemon_egg_group AS b ON a.pokemon_id = b.pokemon_id
+            GROUP BY a.egg_group_id, b.egg_group_id
+    "#;
+    let mut query = db.connection.prepare_cached(query).unwrap();
+
+    query
+        .query_map((), |row| Ok(((row.get(0)?, row.get(1)?), row.get(2)?)))
+        .unwrap()
+        .collect::<Result<HashMap<_, _>, _>>()
+        .unwrap()
+}
+
+/// The Pokemon in both egg groups, named in `language`.
+fn get_egg_group_members(db: &Database, first: i64, second: i64, language: &str) -> Vec<String> {
+    let query = r#"
+        SELECT COALESCE(pokemon_name.name, pokemon.name)
+            FROM pokemon
+                JOIN pokemon_egg_group AS a ON pokemon.pokemon_id = a.pokemon_id
+                JOIN pokemon_egg_group AS b ON pokemon.pokemon_id = b.pokemon_id
+                LEFT JOIN pokemon_name ON pokemon.pokemon_id = pokemon_name.pokemon_id
+                    AND pokemon_name.language = :language
+            WHERE a.egg_group_id = :first
+              AND b.egg_group_id = :second
+            ORDER BY pokemon.pokemon_id
+    "#;
+    let mut query = db.connection.prepare_cached(query).unwrap();
+
+    query
+        .query_map(
+            rusqlite::named_params! {
+                ":first": first,
+                ":second": second,
+                ":language": language,
+            },
+            |row| row.get::<_, String>(0),
+        )
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap()
+}
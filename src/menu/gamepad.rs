@@ -0,0 +1,444 @@
+//! Gamepad navigation, on top of the keyboard focus.
+//!
+//! The D-pad and left stick move the focus, A activates and B goes back.
+//! Text inputs can't be typed into with a gamepad, so activating one opens
+//! an on-screen keyboard instead.
+
+use super::*;
+
+use bevy::input_focus::tab_navigation::{NavAction, TabGroup, TabNavigation, TabNavigationError};
+use bevy::input_focus::{InputFocus, InputFocusVisible};
+
+use navigation::PointerTarget;
+
+/// How far the stick has to be pushed to move the focus.
+const STICK_THRESHOLD: f32 = 0.5;
+/// Digits and `/` are there for IVs, seeds and paths.
+const KEYBOARD_KEYS: &str = "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ-'./ ";
+const KEYBOARD_COLUMNS: usize = 10;
+
+pub struct GamepadNavigationPlugin;
+
+impl Plugin for GamepadNavigationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, (gamepad_navigate, gamepad_buttons).chain());
+    }
+}
+
+/// The on-screen keyboard, typing into `input`.
+#[derive(Component)]
+struct OnScreenKeyboard {
+    input: Entity,
+}
+
+#[derive(Component, Clone, Copy)]
+enum KeyboardKey {
+    Char(char),
+    Backspace,
+    Done,
+}
+
+/// Moves the focus with the D-pad, or once each time the stick is pushed.
+fn gamepad_navigate(
+    gamepads: Query<&Gamepad>,
+    nav: TabNavigation,
+    mut focus: ResMut<InputFocus>,
+    mut visible: ResMut<InputFocusVisible>,
+    mut stick_held: Local<bool>,
+) {
+    let mut action = None;
+    let mut stick_pushed = false;
+
+    for gamepad in &gamepads {
+        if gamepad.any_just_pressed([GamepadButton::DPadDown, GamepadButton::DPadRight]) {
+            action = Some(NavAction::Next);
+        } else if gamepad.any_just_pressed([GamepadButton::DPadUp, GamepadButton::DPadLeft]) {
+            action = Some(NavAction::Previous);
+        }
+
+        let stick = gamepad.left_stick();
+        if stick.length() > STICK_THRESHOLD {
+            stick_pushed = true;
+            if !*stick_held {
+                action = Some(if stick.y < -STICK_THRESHOLD || stick.x > STICK_THRESHOLD {
+                    NavAction::Next
+                } else {
+                    NavAction::Previous
+                });
+            }
+        }
+    }
+    *stick_held = stick_pushed;
+
+    let Some(action) = action else {
+        return;
+    };
+
+    match nav.navigate(&focus, action) {
+        Ok(next) => focus.set(next),
+        Err(TabNavigationError::NoTabGroupForCurrentFocus { new_focus, .. }) => {
+            focus.set(new_focus)
+        }
+        Err(e) => {
+            warn!("Gamepad navigation error: {e}");
+            return;
+        }
+    }
+    visible.0 = true;
+}
+
+fn gamepad_buttons(
+    mut commands: Commands,
+    gamepads: Query<&Gamepad>,
+    mut focus: ResMut<InputFocus>,
+    text_inputs: Query<(), With<TextInputNode>>,
+    keyboard: Query<(Entity, &OnScreenKeyboard)>,
+    back: Query<Entity, With<BackButton>>,
+    pointer: PointerTarget,
+    state: Res<State<MenuState>>,
+    font: Res<GameFont>,
+    locale: Res<Locale>,
+) {
+    let south = gamepads
+        .iter()
+        .any(|gamepad| gamepad.just_pressed(GamepadButton::South));
+    let east = gamepads
+        .iter()
+        .any(|gamepad| gamepad.just_pressed(GamepadButton::East));
+
+    if south {
+        match focus.0 {
+            Some(input) if text_inputs.contains(input) && keyboard.is_empty() => {
+                let first_key = spawn_keyboard(&mut commands, input, *state.get(), &font, &locale);
+                focus.set(first_key);
+            }
+            Some(focused) => pointer.click(&mut commands, focused),
+            None => {}
+        }
+    }
+
+    if east {
+        if let Ok((entity, keyboard)) = keyboard.single() {
+            commands.entity(entity).despawn();
+            focus.set(keyboard.input);
+        } else if let Some(back) = back.iter().next() {
+            pointer.click(&mut commands, back);
+        }
+    }
+}
+
+/// Spawns the on-screen keyboard, returning its first key so it can be focused.
+fn spawn_keyboard(
+    commands: &mut Commands,
+    input: Entity,
+    state: MenuState,
+    font: &GameFont,
+    locale: &Locale,
+) -> Entity {
+    let key_text_style = text_style(font);
+
+    let key_node = Node {
+        width: Val::Px(60.0),
+        height: Val::Px(50.0),
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        ..default()
+    };
+
+    let keys = KEYBOARD_KEYS
+        .chars()
+        .map(|c| (KeyboardKey::Char(c), c.to_string()))
+        .chain([
+            (KeyboardKey::Backspace, locale.get("keyboard.delete").into()),
+            (KeyboardKey::Done, locale.get("keyboard.done").into()),
+        ]);
+
+    let mut first_key = None;
+
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(20.0),
+                padding: UiRect::all(Val::Px(10.0)),
+                display: Display::Grid,
+                grid_template_columns: RepeatedGridTrack::px(KEYBOARD_COLUMNS, 60.0),
+                column_gap: Val::Px(5.0),
+                row_gap: Val::Px(5.0),
+                ..default()
+            },
+            ThemedBackground::Pressed,
+            GlobalZIndex(10),
+            TabGroup::modal(),
+            StateScoped(state),
+            OnScreenKeyboard { input },
+        ))
+        .with_children(|builder| {
+            for (key, label) in keys {
+                let key = builder
+                    .spawn((
+                        Button,
+                        key_node.clone(),
+                        ThemedBackground::Button,
+                        key,
+                        children![(key_text_style.clone(), Text::new(label), Pickable::IGNORE)],
+                    ))
+                    .observe(keyboard_key_on_click)
+                    .id();
+                first_key.get_or_insert(key);
+            }
+        });
+
+    first_key.unwrap()
+}
+
+fn keyboard_key_on_click(
+    mut click: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    keys: Query<&KeyboardKey>,
+    keyboard: Single<(Entity, &OnScreenKeyboard)>,
+    mut queues: Query<&mut TextInputQueue>,
+    mut focus: ResMut<InputFocus>,
+) {
+    click.propagate(false);
+
+    if click.button != PointerButton::Primary {
+        return;
+    }
+
+    let Ok(key) = keys.get(click.target()) else {
+        return;
+    };
+    let (entity, keyboard) = *keyboard;
+
+    match *key {
+        KeyboardKey::Char(c) => {
+            if let Ok(mut queue) = queues.get_mut(keyboard.input) {
+                queue.add(TextInputAction::Edit(TextInputEdit::Insert(c, false)));
+            }
+        }
+        KeyboardKey::Backspace => {
+            if let Ok(mut queue) = queues.get_mut(keyboard.input) {
+                queue.add(TextInputAction::Edit(TextInputEdit::Backspace));
+            }
+        }
+        KeyboardKey::Done => {
+            commands.entity(entity).despawn();
+            focus.set(keyboard.input);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use bevy::input::InputPlugin;
+    use bevy::input::gamepad::{
+        GamepadConnection, GamepadConnectionEvent, RawGamepadAxisChangedEvent,
+        RawGamepadButtonChangedEvent, RawGamepadEvent,
+    };
+    use bevy::input_focus::tab_navigation::TabIndex;
+    use bevy::state::app::StatesPlugin;
+    use bevy::window::PrimaryWindow;
+
+    /// The buttons clicked, in order.
+    #[derive(Resource, Default)]
+    struct Clicked(Vec<Entity>);
+
+    struct Screen {
+        first: Entity,
+        second: Entity,
+        input: Entity,
+        back: Entity,
+    }
+
+    fn app() -> (App, Screen) {
+        let mut app = App::new();
+        app.add_plugins((StatesPlugin, InputPlugin))
+            .init_state::<MenuState>()
+            .init_resource::<InputFocus>()
+            .init_resource::<InputFocusVisible>()
+            .init_resource::<Clicked>()
+            .insert_resource(Locale::new(Language::English))
+            .insert_resource(GameFont {
+                font: default(),
+                size: 40.0,
+            })
+            .add_plugins(GamepadNavigationPlugin);
+
+        let world = app.world_mut();
+        world.spawn((Window::default(), PrimaryWindow));
+        world.spawn(Camera2d);
+
+        let root = world.spawn(TabGroup::new(0)).id();
+        let button = |world: &mut World| {
+            world
+                .spawn((Button, TabIndex(0), ChildOf(root)))
+                .observe(
+                    |click: Trigger<Pointer<Click>>, mut clicked: ResMut<Clicked>| {
+                        clicked.0.push(click.target());
+                    },
+                )
+                .id()
+        };
+        let first = button(world);
+        let second = button(world);
+        let input = world
+            .spawn((TextInputNode::default(), TabIndex(0), ChildOf(root)))
+            .id();
+        let back = button(world);
+        world.entity_mut(back).insert(BackButton);
+
+        // Plugged in the way a gamepad backend does it.
+        let gamepad = world.spawn_empty().id();
+        world.send_event(GamepadConnectionEvent::new(
+            gamepad,
+            GamepadConnection::Connected {
+                name: "Test Gamepad".into(),
+                vendor_id: None,
+                product_id: None,
+            },
+        ));
+        app.update();
+
+        (
+            app,
+            Screen {
+                first,
+                second,
+                input,
+                back,
+            },
+        )
+    }
+
+    /// Sends a raw event from the gamepad, like a backend would, and runs a frame.
+    fn send(app: &mut App, event: impl FnOnce(Entity) -> RawGamepadEvent) {
+        let world = app.world_mut();
+        let gamepad = world
+            .query_filtered::<Entity, With<Gamepad>>()
+            .single(world)
+            .unwrap();
+        world.send_event(event(gamepad));
+        app.update();
+    }
+
+    /// Presses a button on the gamepad for a frame, then releases it for one.
+    fn press(app: &mut App, button: GamepadButton) {
+        for value in [1.0, 0.0] {
+            send(app, |gamepad| {
+                RawGamepadEvent::Button(RawGamepadButtonChangedEvent::new(gamepad, button, value))
+            });
+        }
+    }
+
+    fn push_stick(app: &mut App, y: f32) {
+        send(app, |gamepad| {
+            RawGamepadEvent::Axis(RawGamepadAxisChangedEvent::new(
+                gamepad,
+                GamepadAxis::LeftStickY,
+                y,
+            ))
+        });
+    }
+
+    fn focus(app: &App) -> Option<Entity> {
+        app.world().resource::<InputFocus>().0
+    }
+
+    fn clicked(app: &App) -> &[Entity] {
+        &app.world().resource::<Clicked>().0
+    }
+
+    #[test]
+    fn dpad_moves_focus() {
+        let (mut app, screen) = app();
+
+        press(&mut app, GamepadButton::DPadDown);
+        assert_eq!(focus(&app), Some(screen.first));
+        assert!(app.world().resource::<InputFocusVisible>().0);
+
+        press(&mut app, GamepadButton::DPadRight);
+        assert_eq!(focus(&app), Some(screen.second));
+
+        press(&mut app, GamepadButton::DPadUp);
+        assert_eq!(focus(&app), Some(screen.first));
+
+        press(&mut app, GamepadButton::DPadLeft);
+        assert_eq!(focus(&app), Some(screen.back));
+    }
+
+    #[test]
+    fn stick_moves_focus_once_per_push() {
+        let (mut app, screen) = app();
+
+        push_stick(&mut app, -1.0);
+        app.update();
+        assert_eq!(focus(&app), Some(screen.first));
+
+        // Letting go and pushing again moves it again.
+        push_stick(&mut app, 0.0);
+        push_stick(&mut app, -1.0);
+        assert_eq!(focus(&app), Some(screen.second));
+    }
+
+    #[test]
+    fn south_activates_focus() {
+        let (mut app, screen) = app();
+
+        press(&mut app, GamepadButton::South);
+        assert!(clicked(&app).is_empty());
+
+        app.world_mut()
+            .resource_mut::<InputFocus>()
+            .set(screen.second);
+        press(&mut app, GamepadButton::South);
+        assert_eq!(clicked(&app), [screen.second]);
+    }
+
+    #[test]
+    fn east_goes_back() {
+        let (mut app, screen) = app();
+
+        app.world_mut()
+            .resource_mut::<InputFocus>()
+            .set(screen.first);
+        press(&mut app, GamepadButton::East);
+        assert_eq!(clicked(&app), [screen.back]);
+    }
+
+    #[test]
+    fn south_on_input_opens_keyboard() {
+        let (mut app, screen) = app();
+
+        app.world_mut()
+            .resource_mut::<InputFocus>()
+            .set(screen.input);
+        press(&mut app, GamepadButton::South);
+
+        let world = app.world_mut();
+        let (keyboard, first_key) = world
+            .query::<(&OnScreenKeyboard, &Children)>()
+            .single(world)
+            .map(|(keyboard, keys)| (keyboard.input, keys[0]))
+            .unwrap();
+        assert_eq!(keyboard, screen.input);
+        assert_eq!(focus(&app), Some(first_key));
+        assert!(clicked(&app).is_empty());
+
+        // East closes the keyboard instead of going back.
+        press(&mut app, GamepadButton::East);
+        let world = app.world_mut();
+        assert_eq!(world.query::<&OnScreenKeyboard>().iter(world).count(), 0);
+        assert_eq!(focus(&app), Some(screen.input));
+        assert!(clicked(&app).is_empty());
+    }
+
+    #[test]
+    fn keyboard_types_numbers_and_paths() {
+        for c in "0123456789/".chars() {
+            assert!(KEYBOARD_KEYS.contains(c), "no key for {c:?}");
+        }
+    }
+}
@@ -0,0 +1,416 @@
+//! The History screen, listing every search and breeding submitted so any
+//! of them can be run again, and the lists of recent names under the
+//! Breed and Search inputs.
+
+use super::*;
+
+use serde::{Deserialize, Serialize};
+
+/// How many submissions are shown on one page of the history.
+const HISTORY_PAGE_SIZE: usize = 8;
+const ENTRY_WIDTH: f32 = 900.0;
+const ENTRY_HEIGHT: f32 = 45.0;
+/// How many names a list of recent names offers.
+const RECENT_LIMIT: usize = 5;
+
+pub struct HistoryPlugin;
+
+impl Plugin for HistoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(MenuState::History), history_enter)
+            .add_systems(
+                Update,
+                history_redraw.run_if(
+                    in_state(MenuState::History).and(resource_exists_and_changed::<HistoryScreen>),
+                ),
+            );
+    }
+}
+
+/// What came of a search, as kept in the history.
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq, Debug)]
+pub(super) struct SearchRecord {
+    pub found: bool,
+    pub compatible: Vec<PokemonEntry>,
+}
+
+/// Adds a submission to the history, unless nothing was typed.
+pub(super) fn record_history(db: &Database, submission: &Submission, result: &impl Serialize) {
+    let typed = match submission {
+        Submission::Search { pokemon } => !pokemon.trim().is_empty(),
+        Submission::Breed { mother, other } => {
+            !mother.trim().is_empty() && !other.trim().is_empty()
+        }
+    };
+    if !typed {
+        return;
+    }
+
+    let result = serde_json::to_string(result).unwrap();
+    if let Err(e) = add_history(db, submission, &result) {
+        error!("Failed to add to the history: {e}");
+    }
+}
+
+/// The history, newest first, and which page of it is shown.
+#[derive(Resource)]
+struct HistoryScreen {
+    entries: Vec<HistoryEntry>,
+    page: usize,
+}
+
+impl HistoryScreen {
+    fn page_count(&self) -> usize {
+        self.entries.len().div_ceil(HISTORY_PAGE_SIZE).max(1)
+    }
+}
+
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+enum HistoryText {
+    Status,
+    Page,
+}
+
+/// The column the history's entries are spawned into.
+#[derive(Component)]
+struct HistoryList;
+
+/// An entry of the history, by where it is in [`HistoryScreen`].
+#[derive(Component)]
+struct HistoryButton(usize);
+
+fn history_enter(mut commands: Commands, font: Res<GameFont>, db: NonSend<Database>) {
+    commands.insert_resource(HistoryScreen {
+        entries: get_history(&db),
+        page: 0,
+    });
+
+    spawn_screen(&mut commands, &font, MenuState::History, |ui| {
+        ui.result_panel(HistoryText::Status);
+        ui.list(HistoryList);
+        ui.pager(
+            HistoryText::Page,
+            history_page_on_click(-1),
+            history_page_on_click(1),
+        );
+        ui.row(|ui| {
+            ui.button("history.clear", clear_history_on_click);
+            ui.back_button();
+        });
+    });
+}
+
+/// One line about what was submitted and what came of it.
+fn entry_summary(entry: &HistoryEntry, locale: &Locale) -> String {
+    let summary = match &entry.submission {
+        Submission::Search { pokemon } => {
+            match serde_json::from_str::<SearchRecord>(&entry.result) {
+                Ok(record) if !record.found => locale.format(
+                    "history.search",
+                    &[pokemon, &locale.get("common.not_found")],
+                ),
+                Ok(record) => locale.format(
+                    "history.search",
+                    &[
+                        pokemon,
+                        &locale.format("search.breedable", &[&record.compatible.len()]),
+                    ],
+                ),
+                Err(_) => locale.format("history.search", &[pokemon, &"?"]),
+            }
+        }
+        Submission::Breed { mother, other } => {
+            let result = match serde_json::from_str::<BreedReport>(&entry.result) {
+                Ok(report) => match report.offspring {
+                    Some(offspring) => offspring.species,
+                    None => locale.get("breed.bad_match").into(),
+                },
+                Err(_) => "?".into(),
+            };
+            locale.format("history.breed", &[mother, other, &result])
+        }
+    };
+    format!("{}  {summary}", entry.created_at)
+}
+
+fn history_redraw(
+    mut commands: Commands,
+    history: Res<HistoryScreen>,
+    list: Single<Entity, With<HistoryList>>,
+    mut texts: Query<(&mut Text, &HistoryText)>,
+    font: Res<GameFont>,
+    locale: Res<Locale>,
+) {
+    for (mut text, panel) in &mut texts {
+        text.0 = match panel {
+            HistoryText::Status if history.entries.is_empty() => locale.get("history.empty").into(),
+            HistoryText::Status => locale.format("history.count", &[&history.entries.len()]),
+            HistoryText::Page if history.entries.is_empty() => String::new(),
+            HistoryText::Page => {
+                locale.format("common.page", &[&(history.page + 1), &history.page_count()])
+            }
+        };
+    }
+
+    commands.entity(*list).despawn_related::<Children>();
+    let (entry_font, _, _) = small_text_style(&font);
+    commands.entity(*list).with_children(|builder| {
+        for (i, entry) in history
+            .entries
+            .iter()
+            .enumerate()
+            .skip(history.page * HISTORY_PAGE_SIZE)
+            .take(HISTORY_PAGE_SIZE)
+        {
+            builder
+                .spawn((
+                    Button,
+                    Node {
+                        width: Val::Px(ENTRY_WIDTH),
+                        height: Val::Px(ENTRY_HEIGHT),
+                        padding: UiRect::horizontal(Val::Px(10.0)),
+                        align_items: AlignItems::Center,
+                        overflow: Overflow::clip(),
+                        ..default()
+                    },
+                    ThemedBackground::Button,
+                    HistoryButton(i),
+                    children![(
+                        entry_font.clone(),
+                        ThemedText,
+                        TextLayout::new_with_justify(JustifyText::Left),
+                        Text::new(entry_summary(entry, &locale)),
+                        Pickable::IGNORE,
+                    )],
+                ))
+                .observe(history_entry_on_click);
+        }
+    });
+}
+
+fn history_page_on_click(step: isize) -> impl Fn(Trigger<Pointer<Click>>, ResMut<HistoryScreen>) {
+    move |mut click, mut history| {
+        click.propagate(false);
+
+        if click.button != PointerButton::Primary {
+            return;
+        }
+
+        let page = history
+            .page
+            .saturating_add_signed(step)
+            .min(history.page_count() - 1);
+
+        if page != history.page {
+            history.page = page;
+        }
+    }
+}
+
+/// Runs a submission from the history again, on its own screen.
+fn history_entry_on_click(
+    mut click: Trigger<Pointer<Click>>,
+    buttons: Query<&HistoryButton>,
+    history: Res<HistoryScreen>,
+    mut session: ResMut<Session>,
+    mut results: ResMut<SearchResults>,
+    mut next_state: ResMut<NextState<MenuState>>,
+    locale: Res<Locale>,
+    db: NonSend<Database>,
+) {
+    click.propagate(false);
+
+    if click.button != PointerButton::Primary {
+        return;
+    }
+
+    let Some(entry) = buttons
+        .get(click.target())
+        .ok()
+        .and_then(|HistoryButton(i)| history.entries.get(*i))
+    else {
+        return;
+    };
+
+    match &entry.submission {
+        Submission::Search { pokemon } => {
+            session.search = pokemon.clone();
+            run_search(&mut results, pokemon, &db, &locale);
+            next_state.set(MenuState::Search);
+        }
+        Submission::Breed { mother, other } => {
+            session.mother = mother.clone();
+            session.other = other.clone();
+            run_breed(&mut session, &db, &locale);
+            next_state.set(MenuState::Breed);
+        }
+    }
+}
+
+fn clear_history_on_click(
+    mut click: Trigger<Pointer<Click>>,
+    mut history: ResMut<HistoryScreen>,
+    db: NonSend<Database>,
+) {
+    click.propagate(false);
+
+    if click.button != PointerButton::Primary {
+        return;
+    }
+
+    match clear_history(&db) {
+        Ok(()) => {
+            history.entries.clear();
+            history.page = 0;
+        }
+        Err(e) => error!("Failed to clear the history: {e}"),
+    }
+}
+
+/// An input that recent names can be picked for.
+#[derive(Component)]
+struct RecentInput(HistoryField);
+
+/// The hidden list of recent names under an input.
+#[derive(Component)]
+struct RecentList(HistoryField);
+
+/// The button that shows or hides the recent names for an input.
+#[derive(Component)]
+struct RecentToggle(HistoryField);
+
+/// A name in a list of recent names.
+#[derive(Component)]
+struct RecentName(HistoryField, String);
+
+/// A text input with a button next to it that shows the names last
+/// submitted in it, to pick one instead of typing it again.
+pub(super) fn recent_text_input(
+    ui: &mut Ui,
+    key: &'static str,
+    field: HistoryField,
+    marker: impl Bundle,
+) {
+    ui.label(key);
+    ui.row(|ui| {
+        ui.bare_text_input(key, (RecentInput(field), marker));
+        ui.small_button("v", toggle_recent_on_click)
+            .insert(RecentToggle(field));
+        ui.spawn((
+            Node {
+                display: Display::None,
+                position_type: PositionType::Absolute,
+                top: Val::Percent(100.0),
+                left: Val::Px(0.0),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(5.0),
+                padding: UiRect::all(Val::Px(5.0)),
+                ..default()
+            },
+            ThemedBackground::Pressed,
+            GlobalZIndex(5),
+            RecentList(field),
+        ));
+    });
+}
+
+/// Shows the recent names for the toggle's input, and hides the others, or
+/// hides them if they are shown.
+fn toggle_recent_on_click(
+    mut click: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    toggles: Query<&RecentToggle>,
+    mut lists: Query<(Entity, &RecentList, &mut Node)>,
+    font: Res<GameFont>,
+    locale: Res<Locale>,
+    db: NonSend<Database>,
+) {
+    click.propagate(false);
+
+    if click.button != PointerButton::Primary {
+        return;
+    }
+
+    let Ok(&RecentToggle(field)) = toggles.get(click.target()) else {
+        return;
+    };
+
+    for (list, RecentList(list_field), mut node) in &mut lists {
+        if *list_field != field || node.display != Display::None {
+            node.display = Display::None;
+            continue;
+        }
+
+        let names = get_recent(&db, field, RECENT_LIMIT);
+        let (name_font, _, _) = small_text_style(&font);
+        commands.entity(list).despawn_related::<Children>();
+        commands.entity(list).with_children(|builder| {
+            if names.is_empty() {
+                builder.spawn((
+                    name_font.clone(),
+                    ThemedText,
+                    Text::new(locale.get("history.no_recent")),
+                    Pickable::IGNORE,
+                ));
+            }
+            for name in names {
+                builder
+                    .spawn((
+                        Button,
+                        Node {
+                            min_width: Val::Px(200.0),
+                            padding: UiRect::all(Val::Px(5.0)),
+                            ..default()
+                        },
+                        ThemedBackground::Button,
+                        children![(
+                            name_font.clone(),
+                            ThemedText,
+                            Text::new(name.clone()),
+                            Pickable::IGNORE,
+                        )],
+                        RecentName(field, name),
+                    ))
+                    .observe(recent_name_on_click);
+            }
+        });
+        node.display = Display::Flex;
+    }
+}
+
+/// Fills the input with the name picked, and searches for it on the Search
+/// screen.
+fn recent_name_on_click(
+    mut click: Trigger<Pointer<Click>>,
+    names: Query<&RecentName>,
+    mut inputs: Query<(&mut TextInputQueue, &RecentInput)>,
+    mut lists: Query<&mut Node, With<RecentList>>,
+    mut session: ResMut<Session>,
+    mut results: ResMut<SearchResults>,
+    locale: Res<Locale>,
+    db: NonSend<Database>,
+) {
+    click.propagate(false);
+
+    if click.button != PointerButton::Primary {
+        return;
+    }
+
+    let Ok(RecentName(field, name)) = names.get(click.target()) else {
+        return;
+    };
+
+    for (mut queue, RecentInput(input_field)) in &mut inputs {
+        if input_field == field {
+            set_input_text(&mut queue, name);
+        }
+    }
+    for mut node in &mut lists {
+        node.display = Display::None;
+    }
+
+    if *field == HistoryField::Search {
+        session.search = name.clone();
+        run_search(&mut results, name, &db, &locale);
+    }
+}
@@ -0,0 +1,154 @@
+//! The loading screen, shown until the font, title and database are ready.
+//!
+//! It is spawned before the font has loaded, so it uses Bevy's default font.
+
+use super::*;
+
+use bevy::asset::LoadState;
+
+pub struct LoadingPlugin;
+
+impl Plugin for LoadingPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(MenuState::Loading), loading_enter)
+            .add_systems(
+                Update,
+                loading_progress.run_if(in_state(MenuState::Loading)),
+            );
+    }
+}
+
+#[derive(Component)]
+struct LoadingStatus;
+
+/// The buttons to get out of a failed load, hidden until something fails.
+#[derive(Component)]
+struct LoadingFailedButton;
+
+/// Only shown when the database is what failed.
+#[derive(Component)]
+struct DatabaseFailedButton;
+
+enum Progress {
+    Pending,
+    Done,
+    Failed(String),
+}
+
+impl Progress {
+    fn of_asset(state: Option<LoadState>) -> Self {
+        match state {
+            Some(LoadState::Loaded) => Progress::Done,
+            Some(LoadState::Failed(e)) => Progress::Failed(e.to_string()),
+            _ => Progress::Pending,
+        }
+    }
+}
+
+fn loading_enter(mut commands: Commands, settings: Res<Settings>) {
+    let font = GameFont {
+        font: default(),
+        size: settings.font_size,
+    };
+
+    spawn_screen(&mut commands, &font, MenuState::Loading, |ui| {
+        ui.label("loading.title");
+        ui.result_panel(LoadingStatus);
+        ui.button("loading.use_memory", use_memory_database_on_click)
+            .insert((LoadingFailedButton, DatabaseFailedButton));
+        ui.button("main.quit", quit_game_on_click)
+            .insert(LoadingFailedButton);
+    });
+}
+
+fn loading_progress(
+    asset_server: Res<AssetServer>,
+    font: Option<Res<GameFont>>,
+    title: Option<Res<TitleImage>>,
+    db: Option<NonSend<Database>>,
+    db_error: Option<Res<DatabaseError>>,
+    locale: Res<Locale>,
+    mut status: Single<&mut Text, With<LoadingStatus>>,
+    mut buttons: Query<(&mut Node, Has<DatabaseFailedButton>), With<LoadingFailedButton>>,
+    mut next_state: ResMut<NextState<MenuState>>,
+) {
+    let progress = [
+        (
+            "loading.font",
+            Progress::of_asset(font.and_then(|font| asset_server.get_load_state(&font.font))),
+        ),
+        (
+            "loading.title_image",
+            Progress::of_asset(title.and_then(|title| asset_server.get_load_state(&title.0))),
+        ),
+        (
+            "loading.database",
+            match (db, db_error) {
+                (Some(_), _) => Progress::Done,
+                (None, Some(error)) => Progress::Failed(error.0.clone()),
+                (None, None) => Progress::Pending,
+            },
+        ),
+    ];
+
+    let done = progress
+        .iter()
+        .filter(|(_, progress)| matches!(progress, Progress::Done))
+        .count();
+
+    if done == progress.len() {
+        next_state.set(MenuState::Main);
+        return;
+    }
+
+    let mut text = locale.format("loading.progress", &[&done, &progress.len()]);
+    for (key, progress) in &progress {
+        let state = match progress {
+            Progress::Pending => locale.get("loading.pending").into(),
+            Progress::Done => locale.get("loading.done").into(),
+            Progress::Failed(e) => locale.format("loading.failed", &[e]),
+        };
+        text.push('\n');
+        text.push_str(&locale.format("loading.line", &[&locale.get(key), &state]));
+    }
+
+    if status.0 != text {
+        status.0 = text;
+    }
+
+    let asset_failed = progress[..2]
+        .iter()
+        .any(|(_, progress)| matches!(progress, Progress::Failed(_)));
+    let db_failed = matches!(progress[2].1, Progress::Failed(_));
+
+    for (mut node, database_only) in &mut buttons {
+        let shown = if database_only {
+            db_failed
+        } else {
+            asset_failed || db_failed
+        };
+        let display = if shown { Display::Flex } else { Display::None };
+
+        if node.display != display {
+            node.display = display;
+        }
+    }
+}
+
+/// Gives up on the database in the settings for this run, and opens one in
+/// memory instead.
+fn use_memory_database_on_click(
+    mut click: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    settings: Res<Settings>,
+) {
+    click.propagate(false);
+
+    if click.button != PointerButton::Primary {
+        return;
+    }
+
+    commands.insert_resource(MemoryFallback(settings.database_path.clone()));
+    commands.remove_resource::<DatabaseError>();
+    commands.insert_resource(DatabaseTask::spawn(None));
+}
@@ -0,0 +1,493 @@
+//! The move browser, listing every move and who learns it.
+//!
+
+use super::*;
+
+/// How many moves are listed on one page.
+const MOVE_PAGE_SIZE: usize = 10;
+const CATEGORIES: [&str; 3] = ["Status", "Physical", "Special"];
+
+pub struct MovesPlugin;
+
+impl Plugin for MovesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(MenuState::Moves), moves_enter)
+            .add_systems(
+                Update,
+                moves_redraw.run_if(
+                    in_state(MenuState::Moves).and(resource_exists_and_changed::<MoveBrowser>),
+                ),
+            );
+    }
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+enum MoveSort {
+    #[default]
+    Name,
+    Power,
+    Accuracy,
+}
+
+impl MoveSort {
+    fn next(self) -> Self {
+        match self {
+            Self::Name => Self::Power,
+            Self::Power => Self::Accuracy,
+            Self::Accuracy => Self::Name,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Name => "Name",
+            Self::Power => "Power",
+            Self::Accuracy => "Accuracy",
+        }
+    }
+
+    fn key(self) -> &'static str {
+        match self {
+            Self::Name => "moves.sort.name",
+            Self::Power => "moves.sort.power",
+            Self::Accuracy => "moves.sort.accuracy",
+        }
+    }
+}
+
+/// The [`Locale`] key of a move category, which is stored in English.
+fn category_key(category: &str) -> &str {
+    match category {
+        "Status" => "moves.category.status",
+        "Physical" => "moves.category.physical",
+        "Special" => "moves.category.special",
+        other => other,
+    }
+}
+
+/// How a Pokemon learns a move, as stored in `pokemon_move.method`.
+fn method_key(method: &str) -> &str {
+    match method {
+        "egg" => "moves.method.egg",
+        "level-up" => "moves.method.level_up",
+        "machine" => "moves.method.machine",
+        "tutor" => "moves.method.tutor",
+        other => other,
+    }
+}
+
+/// The filters, sorting and selection of the move browser.
+#[derive(Resource, Default)]
+struct MoveBrowser {
+    types: Vec<String>,
+    type_filter: Option<String>,
+    category_filter: Option<&'static str>,
+    sort: MoveSort,
+    moves: Vec<MoveEntry>,
+    page: usize,
+    selected: Option<MoveEntry>,
+    /// The language moves are named in.
+    language: &'static str,
+}
+
+impl MoveBrowser {
+    fn page_count(&self) -> usize {
+        self.moves.len().div_ceil(MOVE_PAGE_SIZE).max(1)
+    }
+
+    fn refresh(&mut self, db: &Database) {
+        self.moves = get_moves(
+            db,
+            self.type_filter.as_deref(),
+            self.category_filter,
+            self.sort,
+            self.language,
+        );
+        self.page = 0;
+    }
+}
+
+#[derive(Component)]
+struct MoveList;
+
+#[derive(Component)]
+struct MovePageLabel;
+
+#[derive(Component)]
+struct MoveDetail;
+
+/// Holds the type badge of the selected move.
+#[derive(Component)]
+struct MoveDetailBadge;
+
+#[derive(Component)]
+struct TypeFilterLabel;
+
+#[derive(Component)]
+struct CategoryFilterLabel;
+
+#[derive(Component)]
+struct SortLabel;
+
+/// A row in the move list, holding the index into [`MoveBrowser::moves`].
+#[derive(Component)]
+struct MoveButton(usize);
+
+#[derive(Clone, Debug)]
+struct MoveEntry {
+    move_id: i64,
+    name: String,
+    move_type: String,
+    category: String,
+    power: Option<u32>,
+    accuracy: Option<u32>,
+}
+
+fn moves_enter(
+    mut commands: Commands,
+    font: Res<GameFont>,
+    locale: Res<Locale>,
+    db: NonSend<Database>,
+) {
+    let mut browser = MoveBrowser {
+        types: get_move_types(&db),
+        language: locale.code(),
+        ..default()
+    };
+    browser.refresh(&db);
+    commands.insert_resource(browser);
+
+    spawn_screen(&mut commands, &font, MenuState::Moves, |ui| {
+        ui.row(|ui| {
+            ui.dynamic_button(TypeFilterLabel, cycle_type_filter);
+            ui.dynamic_button(CategoryFilterLabel, cycle_category_filter);
+            ui.dynamic_button(SortLabel, cycle_sort);
+        });
+
+        ui.row(|ui| {
+            ui.column(|ui| {
+                ui.list(MoveList);
+                ui.pager(MovePageLabel, move_page_on_click(-1), move_page_on_click(1));
+            });
+
+            let (detail_font, detail_color, _) = ui.text_style();
+            ui.spawn(Node {
+                width: Val::Px(400.0),
+                margin: UiRect::left(Val::Px(30.0)),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Start,
+                ..default()
+            })
+            .with_children(|builder| {
+                builder.spawn((Node::default(), MoveDetailBadge));
+                builder.spawn((
+                    detail_font,
+                    detail_color,
+                    TextLayout::new_with_justify(JustifyText::Left),
+                    Text::new(""),
+                    Pickable::IGNORE,
+                    LiveRegion,
+                    MoveDetail,
+                ));
+            });
+        });
+
+        ui.back_button();
+    });
+}
+
+fn moves_redraw(
+    mut commands: Commands,
+    browser: Res<MoveBrowser>,
+    list: Single<Entity, With<MoveList>>,
+    badge: Single<Entity, With<MoveDetailBadge>>,
+    mut labels: ParamSet<(
+        Single<&mut Text, With<TypeFilterLabel>>,
+        Single<&mut Text, With<CategoryFilterLabel>>,
+        Single<&mut Text, With<SortLabel>>,
+        Single<&mut Text, With<MovePageLabel>>,
+        Single<&mut Text, With<MoveDetail>>,
+    )>,
+    font: Res<GameFont>,
+    icons: Res<TypeIcons>,
+    locale: Res<Locale>,
+    db: NonSend<Database>,
+) {
+    commands.entity(*badge).despawn_related::<Children>();
+    if let Some(selected) = &browser.selected {
+        commands
+            .entity(*badge)
+            .with_child(type_badge(&selected.move_type, &font, &icons));
+    }
+
+    let all = locale.get("common.all");
+    labels.p0().0 = locale.format(
+        "moves.type",
+        &[&browser.type_filter.as_deref().unwrap_or(all)],
+    );
+    labels.p1().0 = locale.format(
+        "moves.category",
+        &[&browser
+            .category_filter
+            .map_or(all, |c| locale.get(category_key(c)))],
+    );
+    labels.p2().0 = locale.format("moves.sort", &[&locale.get(browser.sort.key())]);
+    labels.p3().0 = locale.format("common.page", &[&(browser.page + 1), &browser.page_count()]);
+    labels.p4().0 = match &browser.selected {
+        None => "".into(),
+        Some(selected) => {
+            let learners = get_move_learners(&db, selected.move_id, locale.code());
+            let learners = if learners.is_empty() {
+                locale.get("common.none").into()
+            } else {
+                learners
+                    .iter()
+                    .map(|(name, method)| {
+                        locale.format("moves.learner", &[name, &locale.get(method_key(method))])
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            };
+
+            locale.format(
+                "moves.detail",
+                &[
+                    &selected.name,
+                    &locale.get(category_key(&selected.category)),
+                    &selected.power.map_or("-".into(), |p| p.to_string()),
+                    &selected.accuracy.map_or("-".into(), |a| a.to_string()),
+                    &learners,
+                ],
+            )
+        }
+    };
+
+    let row_text_style = small_text_style(&font);
+
+    commands
+        .entity(*list)
+        .despawn_related::<Children>()
+        .with_children(|builder| {
+            for (index, entry) in browser
+                .moves
+                .iter()
+                .enumerate()
+                .skip(browser.page * MOVE_PAGE_SIZE)
+                .take(MOVE_PAGE_SIZE)
+            {
+                builder
+                    .spawn((
+                        Button,
+                        Node {
+                            width: Val::Px(600.0),
+                            height: Val::Px(40.0),
+                            column_gap: Val::Px(10.0),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        ThemedBackground::Button,
+                        MoveButton(index),
+                        children![
+                            (
+                                row_text_style.clone(),
+                                Text::new(entry.name.clone()),
+                                Pickable::IGNORE
+                            ),
+                            type_badge(&entry.move_type, &font, &icons),
+                            (
+                                row_text_style.clone(),
+                                Text::new(locale.format(
+                                    "moves.row",
+                                    &[
+                                        &locale.get(category_key(&entry.category)),
+                                        &entry.power.map_or("-".into(), |p| p.to_string()),
+                                        &entry.accuracy.map_or("-".into(), |a| a.to_string()),
+                                    ],
+                                )),
+                                Pickable::IGNORE
+                            ),
+                        ],
+                    ))
+                    .observe(select_move_on_click);
+            }
+        });
+}
+
+fn cycle_type_filter(
+    mut click: Trigger<Pointer<Click>>,
+    mut browser: ResMut<MoveBrowser>,
+    db: NonSend<Database>,
+) {
+    click.propagate(false);
+
+    if click.button == PointerButton::Primary {
+        let next = match &browser.type_filter {
+            None => browser.types.first(),
+            Some(current) => browser.types.iter().skip_while(|t| *t != current).nth(1),
+        }
+        .cloned();
+        browser.type_filter = next;
+        browser.refresh(&db);
+    }
+}
+
+fn cycle_category_filter(
+    mut click: Trigger<Pointer<Click>>,
+    mut browser: ResMut<MoveBrowser>,
+    db: NonSend<Database>,
+) {
+    click.propagate(false);
+
+    if click.button == PointerButton::Primary {
+        browser.category_filter = match browser.category_filter {
+            None => Some(CATEGORIES[0]),
+            Some(current) => CATEGORIES
+                .iter()
+                .skip_while(|c| **c != current)
+                .nth(1)
+                .copied(),
+        };
+        browser.refresh(&db);
+    }
+}
+
+fn cycle_sort(
+    mut click: Trigger<Pointer<Click>>,
+    mut browser: ResMut<MoveBrowser>,
+    db: NonSend<Database>,
+) {
+    click.propagate(false);
+
+    if click.button == PointerButton::Primary {
+        browser.sort = browser.sort.next();
+        browser.refresh(&db);
+    }
+}
+
+fn move_page_on_click(step: isize) -> impl Fn(Trigger<Pointer<Click>>, ResMut<MoveBrowser>) {
+    move |mut click, mut browser| {
+        click.propagate(false);
+
+        if click.button != PointerButton::Primary {
+            return;
+        }
+
+        let page = browser
+            .page
+            .saturating_add_signed(step)
+            .min(browser.page_count() - 1);
+
+        if page != browser.page {
+            browser.page = page;
+        }
+    }
+}
+
+fn select_move_on_click(
+    mut click: Trigger<Pointer<Click>>,
+    buttons: Query<&MoveButton>,
+    mut browser: ResMut<MoveBrowser>,
+) {
+    click.propagate(false);
+
+    if click.button != PointerButton::Primary {
+        return;
+    }
+
+    if let Ok(MoveButton(index)) = buttons.get(click.target()) {
+        browser.selected = browser.moves.get(*index).cloned();
+    }
+}
+
+fn get_move_types(db: &Database) -> Vec<String> {
+    let query = r#"
+        SELECT DISTINCT move.type
+            FROM move
+            ORDER BY move.type
+    "#;
+    let mut query = db.connection.prepare_cached(query).unwrap();
+
+    query
+        .query_map((), |row| row.get::<_, String>(0))
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap()
+}
+
+fn get_moves(
+    db: &Database,
+    move_type: Option<&str>,
+    category: Option<&str>,
+    sort: MoveSort,
+    language: &str,
+) -> Vec<MoveEntry> {
+    let query = r#"
+        SELECT
+            move.move_id,
+            COALESCE(move_name.name, move.name) AS name,
+            move.type,
+            move.category,
+            move.power,
+            move.accuracy
+            FROM move
+                LEFT JOIN move_name ON move.move_id = move_name.move_id
+                    AND move_name.language = :language
+            WHERE (:move_type IS NULL OR move.type = :move_type)
+              AND (:category IS NULL OR move.category = :category)
+            ORDER BY
+                CASE :sort
+                    WHEN 'Power' THEN move.power
+                    WHEN 'Accuracy' THEN move.accuracy
+                END DESC NULLS LAST,
+                name
+    "#;
+    let mut query = db.connection.prepare_cached(query).unwrap();
+
+    query
+        .query_map(
+            rusqlite::named_params! {
+                ":move_type": move_type,
+                ":category": category,
+                ":sort": sort.as_str(),
+                ":language": language,
+            },
+            |row| {
+                Ok(MoveEntry {
+                    move_id: row.get(0)?,
+                    name: row.get(1)?,
+                    move_type: row.get(2)?,
+                    category: row.get(3)?,
+                    power: row.get(4)?,
+                    accuracy: row.get(5)?,
+                })
+            },
+        )
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap()
+}
+
+/// Every Pokemon that learns a move, named in `language`, along with how
+/// they learn it.
+fn get_move_learners(db: &Database, move_id: i64, language: &str) -> Vec<(String, String)> {
+    let query = r#"
+        SELECT COALESCE(pokemon_name.name, pokemon.name), pokemon_move.method
+            FROM pokemon_move
+                JOIN pokemon ON pokemon.pokemon_id = pokemon_move.pokemon_id
+                LEFT JOIN pokemon_name ON pokemon.pokemon_id = pokemon_name.pokemon_id
+                    AND pokemon_name.language = :language
+            WHERE pokemon_move.move_id = :move_id
+            ORDER BY pokemon.pokemon_id
+    "#;
+    let mut query = db.connection.prepare_cached(query).unwrap();
+
+    query
+        .query_map(
+            rusqlite::named_params! { ":move_id": move_id, ":language": language },
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap()
+}
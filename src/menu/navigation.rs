@@ -0,0 +1,182 @@
+//! Keyboard navigation between the buttons and inputs of a screen.
+//!
+//! Tab and Shift-Tab move the focus, Enter activates the focused button,
+//! and Escape presses the screen's Back button.
+
+use super::*;
+
+use bevy::input::ButtonState;
+use bevy::input::keyboard::KeyboardInput;
+use bevy::input_focus::tab_navigation::{TabGroup, TabIndex, TabNavigationPlugin};
+use bevy::input_focus::{InputDispatchPlugin, InputFocus, InputFocusVisible};
+use bevy::picking::backend::HitData;
+use bevy::picking::pointer::{Location, PointerId};
+use bevy::render::camera::RenderTarget;
+use bevy::window::{PrimaryWindow, WindowRef};
+use bevy_ui_text_input::SubmitText;
+
+const FOCUS_RING_WIDTH: f32 = 3.0;
+
+pub struct KeyboardNavigationPlugin;
+
+impl Plugin for KeyboardNavigationPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<InputDispatchPlugin>() {
+            app.add_plugins(InputDispatchPlugin);
+        }
+
+        app.add_plugins(TabNavigationPlugin).add_systems(
+            Update,
+            (
+                make_focusable,
+                clear_focus.run_if(state_changed::<MenuState>),
+                hide_focus_on_click,
+                activate_on_key,
+                submit_on_text_submit,
+                focus_ring,
+            )
+                .chain(),
+        );
+    }
+}
+
+/// The button pressed by Escape.
+#[derive(Component)]
+pub struct BackButton;
+
+/// The button pressed when a text input on the same screen is submitted.
+#[derive(Component)]
+pub struct SubmitButton;
+
+/// Everything a keyboard should reach gets a tab index, and each screen
+/// gets its own tab group.
+fn make_focusable(
+    mut commands: Commands,
+    focusable: Query<Entity, Or<(Added<Button>, Added<TextInputNode>)>>,
+    screens: Query<Entity, Added<StateScoped<MenuState>>>,
+) {
+    for entity in &focusable {
+        commands.entity(entity).insert_if_new(TabIndex(0));
+    }
+    for entity in &screens {
+        commands.entity(entity).insert_if_new(TabGroup::new(0));
+    }
+}
+
+fn clear_focus(mut focus: ResMut<InputFocus>) {
+    focus.clear();
+}
+
+/// The focus ring is only for the keyboard, so the mouse hides it again.
+fn hide_focus_on_click(
+    mouse: Res<ButtonInput<MouseButton>>,
+    mut visible: ResMut<InputFocusVisible>,
+) {
+    if mouse.get_just_pressed().next().is_some() && visible.0 {
+        visible.0 = false;
+    }
+}
+
+fn activate_on_key(
+    mut commands: Commands,
+    mut keys: EventReader<KeyboardInput>,
+    focus: Res<InputFocus>,
+    text_inputs: Query<(), With<TextInputNode>>,
+    back: Query<Entity, With<BackButton>>,
+    pointer: PointerTarget,
+) {
+    for key in keys.read() {
+        if key.state != ButtonState::Pressed || key.repeat {
+            continue;
+        }
+
+        match key.key_code {
+            KeyCode::Enter | KeyCode::NumpadEnter | KeyCode::Space => {
+                if let Some(focused) = focus.0.filter(|e| !text_inputs.contains(*e)) {
+                    pointer.click(&mut commands, focused);
+                }
+            }
+            KeyCode::Escape => {
+                if let Some(back) = back.iter().next() {
+                    pointer.click(&mut commands, back);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn submit_on_text_submit(
+    mut commands: Commands,
+    mut submits: EventReader<SubmitText>,
+    submit: Query<Entity, With<SubmitButton>>,
+    pointer: PointerTarget,
+) {
+    if submits.read().count() > 0 {
+        if let Some(submit) = submit.iter().next() {
+            pointer.click(&mut commands, submit);
+        }
+    }
+}
+
+/// Outlines whatever has focus, once the keyboard has been used to move it.
+fn focus_ring(
+    mut commands: Commands,
+    focus: Res<InputFocus>,
+    visible: Res<InputFocusVisible>,
+    focusable: Query<Entity, With<TabIndex>>,
+    theme: Res<Theme>,
+) {
+    if !focus.is_changed() && !visible.is_changed() && !theme.is_changed() {
+        return;
+    }
+
+    for entity in &focusable {
+        if visible.0 && focus.0 == Some(entity) {
+            commands.entity(entity).insert(Outline::new(
+                Val::Px(FOCUS_RING_WIDTH),
+                Val::Px(FOCUS_RING_WIDTH),
+                theme.text,
+            ));
+        } else {
+            commands.entity(entity).remove::<Outline>();
+        }
+    }
+}
+
+/// What's needed to fake a primary click, so keyboard presses reuse the
+/// same `Pointer<Click>` observers as the mouse.
+#[derive(bevy::ecs::system::SystemParam)]
+pub struct PointerTarget<'w, 's> {
+    window: Query<'w, 's, Entity, With<PrimaryWindow>>,
+    camera: Query<'w, 's, Entity, With<Camera2d>>,
+}
+
+impl PointerTarget<'_, '_> {
+    pub fn click(&self, commands: &mut Commands, target: Entity) {
+        let (Ok(window), Ok(camera)) = (self.window.single(), self.camera.single()) else {
+            return;
+        };
+        let Some(render_target) = RenderTarget::Window(WindowRef::Primary).normalize(Some(window))
+        else {
+            return;
+        };
+
+        commands.trigger_targets(
+            Pointer::new(
+                PointerId::Mouse,
+                Location {
+                    target: render_target,
+                    position: Vec2::ZERO,
+                },
+                target,
+                Click {
+                    button: PointerButton::Primary,
+                    hit: HitData::new(camera, 0.0, None, None),
+                    duration: default(),
+                },
+            ),
+            target,
+        );
+    }
+}
@@ -0,0 +1,117 @@
+//! The breeding plan for a project, worked out by [`plan_breeding`] and
+//! shown as a tree: the target first, and each Pokemon's parents indented
+//! under it, down to the ones in the boxes.
+
+use super::*;
+
+use super::boxes::gender_key;
+use crate::planner::{Item, PlanNode, Source, Target, plan_breeding};
+
+/// How far each generation is indented.
+const INDENT: f32 = 40.0;
+
+pub struct PlanPlugin;
+
+impl Plugin for PlanPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(MenuState::Plan), plan_enter);
+    }
+}
+
+/// Lists `node` and its parents, as how deep each line is and its text.
+fn tree_lines(
+    node: &PlanNode,
+    depth: usize,
+    item: Option<Item>,
+    locale: &Locale,
+    lines: &mut Vec<(usize, String)>,
+) {
+    let mut parts = vec![
+        node.species.clone(),
+        match node.gender {
+            Some(gender) => locale.get(gender_key(gender)).into(),
+            None => locale.get("plan.either_gender").into(),
+        },
+    ];
+    if !node.perfect.is_empty() {
+        let stats = node
+            .perfect
+            .iter()
+            .map(|stat| locale.get(stat.key()))
+            .collect::<Vec<_>>();
+        parts.push(locale.format("plan.perfect", &[&stats.join("/")]));
+    }
+    if let Some(nature) = node.nature {
+        parts.push(<&str>::from(nature).into());
+    }
+    if !node.moves.is_empty() {
+        parts.push(node.moves.join(", "));
+    }
+    if let Some(item) = item {
+        parts.push(locale.format("plan.holding", &[&item.name()]));
+    }
+    let text = parts.join(" | ");
+
+    match &node.source {
+        Source::Owned(owned) => lines.push((
+            depth,
+            locale.format("plan.owned", &[&text, &owned.box_number, &(owned.slot + 1)]),
+        )),
+        Source::Bred(step) => {
+            lines.push((
+                depth,
+                locale.format("plan.bred", &[&text, &format!("{:.1}", step.eggs)]),
+            ));
+            tree_lines(&step.mother, depth + 1, step.mother_item, locale, lines);
+            tree_lines(&step.father, depth + 1, step.father_item, locale, lines);
+        }
+    }
+}
+
+fn plan_enter(
+    mut commands: Commands,
+    font: Res<GameFont>,
+    target: Option<Res<PlanTarget>>,
+    locale: Res<Locale>,
+    db: NonSend<Database>,
+) {
+    let Some(PlanTarget(project)) = target.as_deref() else {
+        return;
+    };
+
+    let plan = plan_breeding(&db, &Target::from(project), &get_all_owned(&db));
+
+    let mut lines = Vec::new();
+    let title = match &plan {
+        None => locale.format("plan.none", &[&project.name]),
+        Some(plan) => {
+            tree_lines(&plan.root, 0, None, &locale, &mut lines);
+            locale.format(
+                "plan.title",
+                &[&project.name, &format!("{:.0}", plan.eggs), &plan.steps()],
+            )
+        }
+    };
+
+    spawn_screen(&mut commands, &font, MenuState::Plan, |ui| {
+        ui.result_panel(()).insert(Text::new(title));
+        let (line_font, _, _) = small_text_style(&font);
+        ui.list(()).with_children(|builder| {
+            for (depth, line) in lines {
+                builder.spawn((
+                    Node {
+                        margin: UiRect::left(Val::Px(depth as f32 * INDENT)),
+                        ..default()
+                    },
+                    line_font.clone(),
+                    ThemedText,
+                    TextLayout::new_with_justify(JustifyText::Left),
+                    Text::new(line),
+                    Pickable::IGNORE,
+                ));
+            }
+        });
+        ui.state_button("common.back", MenuState::Projects)
+            .insert(BackButton);
+    });
+}
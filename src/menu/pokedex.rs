@@ -0,0 +1,423 @@
+//! The Pokédex, listing every species to star it as a favorite or attach
+//! tags to it, and the filters by those marks shared with Search.
+
+use std::collections::HashMap;
+
+use super::*;
+
+/// How many species are listed on one page.
+const SPECIES_PAGE_SIZE: usize = 8;
+const ROW_WIDTH: f32 = 700.0;
+
+pub struct PokedexPlugin;
+
+impl Plugin for PokedexPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(MenuState::Pokedex), pokedex_enter)
+            .add_systems(
+                Update,
+                pokedex_redraw.run_if(
+                    in_state(MenuState::Pokedex).and(resource_exists_and_changed::<Pokedex>),
+                ),
+            );
+    }
+}
+
+/// A list that can be narrowed down with a [`MarkFilter`].
+pub(super) trait MarkFiltered: Resource {
+    fn filter(&self) -> &MarkFilter;
+
+    /// Changes the filter, going back to the first page.
+    fn set_filter(&mut self, filter: MarkFilter);
+}
+
+/// The text of a filter's buttons.
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+pub(super) enum FilterText {
+    Favorites,
+    Tag,
+}
+
+pub(super) fn filter_text(filter: &MarkFilter, text: FilterText, locale: &Locale) -> String {
+    match text {
+        FilterText::Favorites if filter.favorites_only => locale.get("filter.favorites").into(),
+        FilterText::Favorites => locale.get("filter.everyone").into(),
+        FilterText::Tag => match &filter.tag {
+            Some(tag) => locale.format("filter.tag", &[tag]),
+            None => locale.get("filter.any_tag").into(),
+        },
+    }
+}
+
+/// Switches between every species and favorites only.
+pub(super) fn favorites_filter_on_click<T: MarkFiltered>(
+    mut click: Trigger<Pointer<Click>>,
+    mut list: ResMut<T>,
+) {
+    click.propagate(false);
+
+    if click.button != PointerButton::Primary {
+        return;
+    }
+
+    let filter = MarkFilter {
+        favorites_only: !list.filter().favorites_only,
+        ..list.filter().clone()
+    };
+    list.set_filter(filter);
+}
+
+/// Steps through the tags in use, after any tag.
+pub(super) fn tag_filter_on_click<T: MarkFiltered>(
+    mut click: Trigger<Pointer<Click>>,
+    mut list: ResMut<T>,
+    db: NonSend<Database>,
+) {
+    click.propagate(false);
+
+    if click.button != PointerButton::Primary {
+        return;
+    }
+
+    let tags = get_tags(&db);
+    let current = list
+        .filter()
+        .tag
+        .as_ref()
+        .and_then(|tag| tags.iter().position(|t| t == tag));
+    let next = step_through(
+        std::iter::once(None)
+            .chain((0..tags.len()).map(Some))
+            .collect(),
+        current,
+        1,
+    );
+
+    let filter = MarkFilter {
+        tag: next.map(|i| tags[i].clone()),
+        ..list.filter().clone()
+    };
+    list.set_filter(filter);
+}
+
+/// Every species, how they are marked, and which of them is picked.
+#[derive(Resource)]
+struct Pokedex {
+    species: Vec<PokemonEntry>,
+    marks: HashMap<i64, SpeciesMarks>,
+    filter: MarkFilter,
+    page: usize,
+    /// The id of the species being starred or tagged.
+    selected: Option<i64>,
+    message: String,
+}
+
+impl Pokedex {
+    fn shown(&self) -> Vec<&PokemonEntry> {
+        self.species
+            .iter()
+            .filter(|entry| self.filter.allows(self.marks.get(&entry.pokemon_id)))
+            .collect()
+    }
+
+    fn page_count(&self) -> usize {
+        self.shown().len().div_ceil(SPECIES_PAGE_SIZE).max(1)
+    }
+
+    fn selected(&self) -> Option<&PokemonEntry> {
+        let selected = self.selected?;
+        self.species
+            .iter()
+            .find(|entry| entry.pokemon_id == selected)
+    }
+}
+
+impl MarkFiltered for Pokedex {
+    fn filter(&self) -> &MarkFilter {
+        &self.filter
+    }
+
+    fn set_filter(&mut self, filter: MarkFilter) {
+        self.filter = filter;
+        self.page = 0;
+    }
+}
+
+/// Text that is filled in from [`Pokedex`].
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+enum PokedexText {
+    Selected,
+    Star,
+    Page,
+    Status,
+}
+
+/// The column the species are spawned into.
+#[derive(Component)]
+struct SpeciesList;
+
+/// A species in the list, by its id.
+#[derive(Component)]
+struct SpeciesButton(i64);
+
+#[derive(Component)]
+struct TagInput;
+
+fn pokedex_enter(
+    mut commands: Commands,
+    font: Res<GameFont>,
+    locale: Res<Locale>,
+    db: NonSend<Database>,
+) {
+    commands.insert_resource(Pokedex {
+        species: get_all_pokemon(&db, locale.code()),
+        marks: get_all_marks(&db),
+        filter: MarkFilter::default(),
+        page: 0,
+        selected: None,
+        message: String::new(),
+    });
+
+    spawn_screen(&mut commands, &font, MenuState::Pokedex, |ui| {
+        ui.row(|ui| {
+            ui.dynamic_button(FilterText::Favorites, favorites_filter_on_click::<Pokedex>);
+            ui.dynamic_button(FilterText::Tag, tag_filter_on_click::<Pokedex>);
+            ui.back_button();
+        });
+        ui.list(SpeciesList);
+        ui.pager(
+            PokedexText::Page,
+            species_page_on_click(-1),
+            species_page_on_click(1),
+        );
+
+        ui.result_panel(PokedexText::Selected);
+        ui.text_input("pokedex.tag", TagInput);
+        ui.row(|ui| {
+            ui.dynamic_button(PokedexText::Star, star_on_click);
+            ui.button("pokedex.add_tag", add_tag_on_click);
+            ui.button("pokedex.remove_tag", remove_tag_on_click);
+        });
+        ui.result_panel(PokedexText::Status);
+    });
+}
+
+/// A species' name, marked if it is a favorite.
+pub(super) fn marked_name(name: &str, marks: Option<&SpeciesMarks>) -> String {
+    if marks.is_some_and(|marks| marks.favorite) {
+        format!("* {name}")
+    } else {
+        name.into()
+    }
+}
+
+fn pokedex_redraw(
+    mut commands: Commands,
+    pokedex: Res<Pokedex>,
+    list: Single<Entity, With<SpeciesList>>,
+    mut texts: Query<(&mut Text, &PokedexText), Without<FilterText>>,
+    mut filter_texts: Query<(&mut Text, &FilterText), Without<PokedexText>>,
+    font: Res<GameFont>,
+    icons: Res<TypeIcons>,
+    asset_server: Res<AssetServer>,
+    locale: Res<Locale>,
+) {
+    let shown = pokedex.shown();
+    let selected = pokedex.selected();
+    let selected_marks = selected.and_then(|entry| pokedex.marks.get(&entry.pokemon_id));
+
+    for (mut text, panel) in &mut texts {
+        text.0 = match panel {
+            PokedexText::Selected => match selected {
+                None => locale.get("pokedex.pick").into(),
+                Some(entry) => {
+                    let tags = selected_marks
+                        .filter(|marks| !marks.tags.is_empty())
+                        .map_or(locale.get("common.none").into(), |marks| {
+                            marks.tags.join(", ")
+                        });
+                    locale.format(
+                        "pokedex.selected",
+                        &[&marked_name(&entry.name, selected_marks), &tags],
+                    )
+                }
+            },
+            PokedexText::Star if selected_marks.is_some_and(|marks| marks.favorite) => {
+                locale.get("pokedex.unstar").into()
+            }
+            PokedexText::Star => locale.get("pokedex.star").into(),
+            PokedexText::Page if shown.is_empty() => locale.get("common.none").into(),
+            PokedexText::Page => {
+                locale.format("common.page", &[&(pokedex.page + 1), &pokedex.page_count()])
+            }
+            PokedexText::Status => pokedex.message.clone(),
+        };
+    }
+    for (mut text, filter) in &mut filter_texts {
+        text.0 = filter_text(&pokedex.filter, *filter, &locale);
+    }
+
+    commands.entity(*list).despawn_related::<Children>();
+    let style = small_text_style(&font);
+    commands.entity(*list).with_children(|builder| {
+        for entry in shown
+            .iter()
+            .skip(pokedex.page * SPECIES_PAGE_SIZE)
+            .take(SPECIES_PAGE_SIZE)
+        {
+            let marks = pokedex.marks.get(&entry.pokemon_id);
+            let mut text = format!("#{} {}", entry.pokemon_id, marked_name(&entry.name, marks));
+            if let Some(marks) = marks.filter(|marks| !marks.tags.is_empty()) {
+                text = format!("{text} [{}]", marks.tags.join(", "));
+            }
+
+            let mut button = builder.spawn((
+                Button,
+                Node {
+                    width: Val::Px(ROW_WIDTH),
+                    padding: UiRect::horizontal(Val::Px(10.0)),
+                    column_gap: Val::Px(10.0),
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                ThemedBackground::Button,
+                SpeciesButton(entry.pokemon_id),
+                children![
+                    pokemon_sprite(entry.pokemon_id, &asset_server),
+                    (style.clone(), Text::new(text), Pickable::IGNORE),
+                    type_badges(
+                        &entry.primary_type,
+                        entry.secondary_type.as_deref(),
+                        &font,
+                        &icons
+                    ),
+                ],
+            ));
+            button.observe(species_on_click);
+
+            if pokedex.selected == Some(entry.pokemon_id) {
+                button.insert(SelectedOption);
+            }
+        }
+    });
+}
+
+fn species_page_on_click(step: isize) -> impl Fn(Trigger<Pointer<Click>>, ResMut<Pokedex>) {
+    move |mut click, mut pokedex| {
+        click.propagate(false);
+
+        if click.button != PointerButton::Primary {
+            return;
+        }
+
+        let page = pokedex
+            .page
+            .saturating_add_signed(step)
+            .min(pokedex.page_count() - 1);
+
+        if page != pokedex.page {
+            pokedex.page = page;
+        }
+    }
+}
+
+fn species_on_click(
+    mut click: Trigger<Pointer<Click>>,
+    buttons: Query<&SpeciesButton>,
+    mut pokedex: ResMut<Pokedex>,
+) {
+    click.propagate(false);
+
+    if click.button != PointerButton::Primary {
+        return;
+    }
+
+    if let Ok(SpeciesButton(pokemon_id)) = buttons.get(click.target()) {
+        pokedex.selected = Some(*pokemon_id);
+        pokedex.message.clear();
+    }
+}
+
+fn star_on_click(
+    mut click: Trigger<Pointer<Click>>,
+    mut pokedex: ResMut<Pokedex>,
+    locale: Res<Locale>,
+    db: NonSend<Database>,
+) {
+    click.propagate(false);
+
+    if click.button != PointerButton::Primary {
+        return;
+    }
+
+    let Some(pokemon_id) = pokedex.selected else {
+        pokedex.message = locale.get("pokedex.pick").into();
+        return;
+    };
+
+    let favorite = pokedex
+        .marks
+        .get(&pokemon_id)
+        .is_some_and(|marks| marks.favorite);
+    match set_favorite(&db, pokemon_id, !favorite) {
+        Ok(()) => {
+            pokedex.marks = get_all_marks(&db);
+            pokedex.message.clear();
+        }
+        Err(e) => pokedex.message = locale.format("pokedex.error", &[&e]),
+    }
+}
+
+fn add_tag_on_click(
+    mut click: Trigger<Pointer<Click>>,
+    input: Single<&TextInputContents, With<TagInput>>,
+    mut pokedex: ResMut<Pokedex>,
+    locale: Res<Locale>,
+    db: NonSend<Database>,
+) {
+    click.propagate(false);
+
+    if click.button == PointerButton::Primary {
+        change_tag(&mut pokedex, input.get(), true, &locale, &db);
+    }
+}
+
+fn remove_tag_on_click(
+    mut click: Trigger<Pointer<Click>>,
+    input: Single<&TextInputContents, With<TagInput>>,
+    mut pokedex: ResMut<Pokedex>,
+    locale: Res<Locale>,
+    db: NonSend<Database>,
+) {
+    click.propagate(false);
+
+    if click.button == PointerButton::Primary {
+        change_tag(&mut pokedex, input.get(), false, &locale, &db);
+    }
+}
+
+/// Adds `tag` to the picked species, or takes it away.
+fn change_tag(pokedex: &mut Pokedex, tag: &str, add: bool, locale: &Locale, db: &Database) {
+    let Some(pokemon_id) = pokedex.selected else {
+        pokedex.message = locale.get("pokedex.pick").into();
+        return;
+    };
+    let tag = tag.trim();
+    if tag.is_empty() {
+        pokedex.message = locale.get("pokedex.tag_required").into();
+        return;
+    }
+
+    let changed = if add {
+        add_tag(db, pokemon_id, tag)
+    } else {
+        remove_tag(db, pokemon_id, tag)
+    };
+    match changed {
+        Ok(()) => {
+            pokedex.marks = get_all_marks(db);
+            pokedex.message.clear();
+        }
+        Err(e) => pokedex.message = locale.format("pokedex.error", &[&e]),
+    }
+}
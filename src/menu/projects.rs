@@ -0,0 +1,685 @@
+//! Breeding projects, each aiming for a Pokemon with the right nature,
+//! ability, ball, IVs and egg moves.
+//!
+//! Parents are linked from the boxes by name, and eggs are recorded from
+//! the boxes once they hatch. The screen shows how close the best egg so
+//! far is, and which pair to breed next.
+
+use super::*;
+
+use strum::IntoEnumIterator;
+
+/// Room for four move names, comma separated.
+const EGG_MOVES_MAX_CHARS: usize = 80;
+
+pub struct ProjectsPlugin;
+
+impl Plugin for ProjectsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(MenuState::Projects), projects_enter)
+            .add_systems(
+                Update,
+                projects_redraw.run_if(
+                    in_state(MenuState::Projects).and(resource_exists_and_changed::<Projects>),
+                ),
+            );
+    }
+}
+
+/// Every project, and everything about the selected one.
+#[derive(Resource)]
+struct Projects {
+    projects: Vec<Project>,
+    /// The index of the project being edited, or `None` for a new one.
+    selected: Option<usize>,
+    /// `None` for any nature.
+    nature: Option<Nature>,
+    /// An index into [`BALLS`], or `None` for any ball.
+    ball: Option<usize>,
+    status: ProjectStatus,
+    parents: Vec<OwnedPokemon>,
+    eggs: Vec<Egg>,
+    pairing: Option<Pairing>,
+    message: String,
+    /// Whether the text inputs need filling in again. Stepping through the
+    /// choices leaves them alone, so what has been typed isn't thrown away.
+    refill_form: bool,
+}
+
+impl Projects {
+    fn new(db: &Database, language: &str) -> Self {
+        let mut projects = Self {
+            projects: get_projects(db),
+            selected: None,
+            nature: None,
+            ball: None,
+            status: ProjectStatus::default(),
+            parents: Vec::new(),
+            eggs: Vec::new(),
+            pairing: None,
+            message: String::new(),
+            refill_form: true,
+        };
+        let first = (!projects.projects.is_empty()).then_some(0);
+        projects.select(first, db, language);
+        projects
+    }
+
+    fn project(&self) -> Option<&Project> {
+        self.selected.map(|index| &self.projects[index])
+    }
+
+    fn project_id(&self) -> Option<i64> {
+        self.project().and_then(|project| project.project_id)
+    }
+
+    /// Starts editing the project at `selected`, or a new one.
+    fn select(&mut self, selected: Option<usize>, db: &Database, language: &str) {
+        self.selected = selected;
+        let project = self.project().cloned();
+
+        self.nature = project.as_ref().and_then(|project| project.nature);
+        self.ball = project
+            .as_ref()
+            .and_then(|project| project.ball.as_ref())
+            .and_then(|ball| BALLS.iter().position(|b| b == ball));
+        self.status = project
+            .as_ref()
+            .map_or(ProjectStatus::default(), |project| project.status);
+        self.refill_form = true;
+        self.load_details(db, language);
+    }
+
+    /// Loads the parents and eggs of the selected project, and works out
+    /// the next pair to breed.
+    fn load_details(&mut self, db: &Database, language: &str) {
+        let (Some(project), Some(project_id)) = (self.project().cloned(), self.project_id()) else {
+            self.parents.clear();
+            self.eggs.clear();
+            self.pairing = None;
+            return;
+        };
+
+        self.parents = get_project_parents(db, project_id)
+            .into_iter()
+            .filter_map(|owned_id| get_owned(db, owned_id))
+            .collect();
+        self.eggs = get_eggs(db, project_id);
+
+        // The linked parents are the ones set aside for it, but until there
+        // are two of them anything in the boxes will do.
+        let candidates = if self.parents.len() >= 2 {
+            self.parents.clone()
+        } else {
+            get_all_owned(db)
+        };
+        self.pairing = propose_pairing(db, &project, &candidates, language);
+    }
+
+    /// Reloads the projects after a change, selecting the one with `project_id`.
+    fn refresh(&mut self, db: &Database, language: &str, project_id: Option<i64>) {
+        self.projects = get_projects(db);
+        let selected = project_id.and_then(|project_id| {
+            self.projects
+                .iter()
+                .position(|project| project.project_id == Some(project_id))
+        });
+        self.select(selected, db, language);
+    }
+}
+
+/// A choice in the form that is stepped through with its button.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Choice {
+    Nature,
+    Ball,
+    Status,
+}
+
+/// A text input of the form.
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+enum FormInput {
+    Name,
+    Species,
+    Ability,
+    Ivs,
+    EggMoves,
+}
+
+/// Text that is filled in from [`Projects`].
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+enum ProjectText {
+    /// Which project is shown.
+    Label,
+    /// The current value of a choice.
+    Choice(Choice),
+    Parents,
+    Progress,
+    Pairing,
+    Status,
+}
+
+/// The name of a Pokemon in the boxes, to link or to record as an egg.
+#[derive(Component)]
+struct OwnedInput;
+
+/// The [`Locale`] key of a project status.
+fn status_key(status: ProjectStatus) -> &'static str {
+    match status {
+        ProjectStatus::Planning => "projects.status.planning",
+        ProjectStatus::Breeding => "projects.status.breeding",
+        ProjectStatus::Done => "projects.status.done",
+        ProjectStatus::Abandoned => "projects.status.abandoned",
+    }
+}
+
+fn goal_text(goal: &Goal, locale: &Locale) -> String {
+    match goal {
+        Goal::Nature(nature) => <&str>::from(*nature).into(),
+        Goal::Ability(ability) => ability.clone(),
+        Goal::Ball(ball) => ball.clone(),
+        Goal::Iv(stat, least) => locale.format("projects.iv", &[&locale.get(stat.key()), least]),
+        Goal::EggMove(name) => name.clone(),
+    }
+}
+
+fn goals_text(goals: &[Goal], locale: &Locale) -> String {
+    goals
+        .iter()
+        .map(|goal| goal_text(goal, locale))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn projects_enter(
+    mut commands: Commands,
+    font: Res<GameFont>,
+    locale: Res<Locale>,
+    db: NonSend<Database>,
+) {
+    commands.insert_resource(Projects::new(&db, locale.code()));
+
+    spawn_screen(&mut commands, &font, MenuState::Projects, |ui| {
+        ui.pager(
+            ProjectText::Label,
+            project_on_click(-1),
+            project_on_click(1),
+        );
+        ui.row(|ui| {
+            ui.column(|ui| {
+                ui.text_input("projects.name", FormInput::Name);
+                ui.text_input("projects.species", FormInput::Species);
+                ui.text_input("projects.ability", FormInput::Ability);
+                ui.text_input("projects.ivs", FormInput::Ivs);
+                ui.long_text_input(
+                    "projects.egg_moves",
+                    Some(EGG_MOVES_MAX_CHARS),
+                    FormInput::EggMoves,
+                );
+            });
+            ui.column(|ui| {
+                for (name, choice) in [
+                    ("projects.nature", Choice::Nature),
+                    ("projects.ball", Choice::Ball),
+                    ("projects.status", Choice::Status),
+                ] {
+                    ui.label(name);
+                    ui.dynamic_button(ProjectText::Choice(choice), step_choice_on_click(choice, 1));
+                }
+            });
+            ui.column(|ui| {
+                ui.text_input("projects.owned", OwnedInput);
+                ui.row(|ui| {
+                    ui.button("projects.link", link_on_click(true));
+                    ui.button("projects.unlink", link_on_click(false));
+                });
+                ui.button("projects.record_egg", record_egg_on_click);
+                ui.result_panel(ProjectText::Parents);
+                ui.result_panel(ProjectText::Progress);
+                ui.result_panel(ProjectText::Pairing);
+                ui.row(|ui| {
+                    ui.button("projects.breed_pairing", breed_pairing_on_click);
+                    ui.button("projects.plan", plan_on_click);
+                });
+            });
+        });
+
+        ui.result_panel(ProjectText::Status);
+        ui.row(|ui| {
+            ui.button("projects.new", new_project_on_click);
+            ui.button("projects.save", save_project_on_click)
+                .insert(SubmitButton);
+            ui.button("projects.delete", delete_project_on_click);
+        });
+        ui.back_button();
+    });
+}
+
+fn project_on_click(
+    step: isize,
+) -> impl Fn(Trigger<Pointer<Click>>, ResMut<Projects>, Res<Locale>, NonSend<Database>) {
+    move |mut click, mut projects, locale, db| {
+        click.propagate(false);
+
+        if click.button != PointerButton::Primary || projects.projects.is_empty() {
+            return;
+        }
+
+        let count = projects.projects.len();
+        let index = projects
+            .selected
+            .map_or(0, |index| step_index(index, step, count));
+        projects.select(Some(index), &db, locale.code());
+        projects.message.clear();
+    }
+}
+
+fn step_choice_on_click(
+    choice: Choice,
+    step: isize,
+) -> impl Fn(Trigger<Pointer<Click>>, ResMut<Projects>) {
+    move |mut click, mut projects| {
+        click.propagate(false);
+
+        if click.button != PointerButton::Primary {
+            return;
+        }
+
+        match choice {
+            Choice::Nature => {
+                let natures = Nature::iter().map(Some);
+                projects.nature = step_through(
+                    std::iter::once(None).chain(natures).collect(),
+                    projects.nature,
+                    step,
+                );
+            }
+            Choice::Ball => {
+                let balls = (0..BALLS.len()).map(Some);
+                projects.ball = step_through(
+                    std::iter::once(None).chain(balls).collect(),
+                    projects.ball,
+                    step,
+                );
+            }
+            Choice::Status => {
+                projects.status =
+                    step_through(ProjectStatus::iter().collect(), projects.status, step);
+            }
+        }
+    }
+}
+
+/// Reads the form into the selected project, or a new one, or explains what
+/// is wrong with it.
+fn read_form(
+    projects: &Projects,
+    inputs: &Query<(&TextInputContents, &FormInput)>,
+    locale: &Locale,
+    db: &Database,
+) -> Result<Project, String> {
+    let (mut name, mut species, mut ability, mut ivs, mut egg_moves) = ("", "", "", "", "");
+    for (contents, input) in inputs {
+        let text = contents.get().trim();
+        match input {
+            FormInput::Name => name = text,
+            FormInput::Species => species = text,
+            FormInput::Ability => ability = text,
+            FormInput::Ivs => ivs = text,
+            FormInput::EggMoves => egg_moves = text,
+        }
+    }
+
+    if name.is_empty() {
+        return Err(locale.get("projects.name_required").into());
+    }
+    if species.is_empty() {
+        return Err(locale.get("boxes.species_required").into());
+    }
+    let Some(pokemon_id) = get_pokemon_id(db, species) else {
+        return Err(locale.format("boxes.unknown_species", &[&species]));
+    };
+
+    let ivs = if ivs.is_empty() {
+        Ivs::default()
+    } else {
+        Ivs::parse(ivs).ok_or_else(|| locale.get("boxes.bad_ivs").to_string())?
+    };
+
+    let egg_moves = egg_moves
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(|name| {
+            find_move(db, name).ok_or_else(|| locale.format("boxes.unknown_move", &[&name]))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if egg_moves.len() > MAX_MOVES {
+        return Err(locale.format("boxes.too_many_moves", &[&MAX_MOVES]));
+    }
+
+    Ok(Project {
+        project_id: projects.project_id(),
+        name: name.into(),
+        pokemon_id,
+        species: species.into(),
+        nature: projects.nature,
+        ability: (!ability.is_empty()).then(|| ability.into()),
+        ball: projects.ball.map(|ball| BALLS[ball].into()),
+        ivs,
+        egg_moves,
+        status: projects.status,
+    })
+}
+
+fn new_project_on_click(
+    mut click: Trigger<Pointer<Click>>,
+    mut projects: ResMut<Projects>,
+    locale: Res<Locale>,
+    db: NonSend<Database>,
+) {
+    click.propagate(false);
+
+    if click.button == PointerButton::Primary {
+        projects.select(None, &db, locale.code());
+        projects.message.clear();
+    }
+}
+
+fn save_project_on_click(
+    mut click: Trigger<Pointer<Click>>,
+    inputs: Query<(&TextInputContents, &FormInput)>,
+    mut projects: ResMut<Projects>,
+    locale: Res<Locale>,
+    db: NonSend<Database>,
+) {
+    click.propagate(false);
+
+    if click.button != PointerButton::Primary {
+        return;
+    }
+
+    let project = match read_form(&projects, &inputs, &locale, &db) {
+        Ok(project) => project,
+        Err(message) => {
+            projects.message = message;
+            return;
+        }
+    };
+
+    match save_project(&db, &project) {
+        Ok(project_id) => {
+            projects.refresh(&db, locale.code(), Some(project_id));
+            projects.message = locale.format("projects.saved", &[&project.name]);
+        }
+        Err(e) => projects.message = locale.format("editor.error", &[&e]),
+    }
+}
+
+fn delete_project_on_click(
+    mut click: Trigger<Pointer<Click>>,
+    mut projects: ResMut<Projects>,
+    locale: Res<Locale>,
+    db: NonSend<Database>,
+) {
+    click.propagate(false);
+
+    if click.button != PointerButton::Primary {
+        return;
+    }
+
+    let Some(project) = projects.project().cloned() else {
+        return;
+    };
+
+    match delete_project(&db, &project) {
+        Ok(()) => {
+            projects.refresh(&db, locale.code(), None);
+            projects.message = locale.format("projects.deleted", &[&project.name]);
+        }
+        Err(e) => projects.message = locale.format("editor.error", &[&e]),
+    }
+}
+
+/// Finds the Pokemon named in the boxes input, for a saved project.
+fn owned_for_project(
+    projects: &mut Projects,
+    input: &TextInputContents,
+    locale: &Locale,
+    db: &Database,
+) -> Option<(i64, OwnedPokemon)> {
+    let Some(project_id) = projects.project_id() else {
+        projects.message = locale.get("projects.save_first").into();
+        return None;
+    };
+
+    let name = input.get().trim();
+    match find_owned(db, name) {
+        Some(owned) => Some((project_id, owned)),
+        None => {
+            projects.message = locale.format("projects.not_owned", &[&name]);
+            None
+        }
+    }
+}
+
+fn link_on_click(
+    linked: bool,
+) -> impl Fn(
+    Trigger<Pointer<Click>>,
+    Single<&TextInputContents, With<OwnedInput>>,
+    ResMut<Projects>,
+    Res<Locale>,
+    NonSend<Database>,
+) {
+    move |mut click, input, mut projects, locale, db| {
+        click.propagate(false);
+
+        if click.button != PointerButton::Primary {
+            return;
+        }
+
+        let Some((project_id, owned)) = owned_for_project(&mut projects, &input, &locale, &db)
+        else {
+            return;
+        };
+
+        match link_parent(&db, project_id, &owned, linked) {
+            Ok(()) => {
+                projects.load_details(&db, locale.code());
+                let key = if linked {
+                    "projects.linked"
+                } else {
+                    "projects.unlinked"
+                };
+                projects.message = locale.format(key, &[&owned.name()]);
+            }
+            Err(e) => projects.message = locale.format("editor.error", &[&e]),
+        }
+    }
+}
+
+fn record_egg_on_click(
+    mut click: Trigger<Pointer<Click>>,
+    input: Single<&TextInputContents, With<OwnedInput>>,
+    mut projects: ResMut<Projects>,
+    locale: Res<Locale>,
+    db: NonSend<Database>,
+) {
+    click.propagate(false);
+
+    if click.button != PointerButton::Primary {
+        return;
+    }
+
+    let Some((project_id, owned)) = owned_for_project(&mut projects, &input, &locale, &db) else {
+        return;
+    };
+
+    match record_egg(&db, project_id, &Egg::from(&owned)) {
+        Ok(_) => {
+            projects.load_details(&db, locale.code());
+            projects.message = locale.format("projects.recorded", &[&owned.name()]);
+        }
+        Err(e) => projects.message = locale.format("editor.error", &[&e]),
+    }
+}
+
+/// Sends the proposed pair to the Breed screen.
+fn breed_pairing_on_click(
+    mut click: Trigger<Pointer<Click>>,
+    mut projects: ResMut<Projects>,
+    mut session: ResMut<Session>,
+    locale: Res<Locale>,
+    mut next_state: ResMut<NextState<MenuState>>,
+) {
+    click.propagate(false);
+
+    if click.button != PointerButton::Primary {
+        return;
+    }
+
+    let Some(pairing) = projects.pairing.clone() else {
+        projects.message = locale.get("projects.no_pairing").into();
+        return;
+    };
+
+    session.mother = pairing.mother.name().into();
+    session.other = pairing.other.name().into();
+    session.parents = BreedParents {
+        mother: Some(pairing.mother),
+        other: Some(pairing.other),
+    };
+    next_state.set(MenuState::Breed);
+}
+
+/// Opens the plan for the selected project.
+fn plan_on_click(
+    mut click: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    mut projects: ResMut<Projects>,
+    locale: Res<Locale>,
+    mut next_state: ResMut<NextState<MenuState>>,
+) {
+    click.propagate(false);
+
+    if click.button != PointerButton::Primary {
+        return;
+    }
+
+    let Some(project) = projects.project().cloned() else {
+        projects.message = locale.get("projects.save_first").into();
+        return;
+    };
+
+    commands.insert_resource(PlanTarget(project));
+    next_state.set(MenuState::Plan);
+}
+
+fn projects_redraw(
+    mut projects: ResMut<Projects>,
+    mut texts: Query<(&mut Text, &ProjectText)>,
+    mut inputs: Query<(&mut TextInputQueue, &FormInput)>,
+    locale: Res<Locale>,
+) {
+    for (mut text, panel) in &mut texts {
+        text.0 = project_text(&projects, *panel, &locale);
+    }
+
+    if !projects.refill_form {
+        return;
+    }
+    projects.bypass_change_detection().refill_form = false;
+
+    let project = projects.project();
+    for (mut queue, input) in &mut inputs {
+        let text = match (project, input) {
+            (None, _) => String::new(),
+            (Some(project), FormInput::Name) => project.name.clone(),
+            (Some(project), FormInput::Species) => project.species.clone(),
+            (Some(project), FormInput::Ability) => project.ability.clone().unwrap_or_default(),
+            (Some(project), FormInput::Ivs) => project.ivs.to_string(),
+            (Some(project), FormInput::EggMoves) => project.egg_moves.join(", "),
+        };
+        set_input_text(&mut queue, &text);
+    }
+}
+
+fn project_text(projects: &Projects, panel: ProjectText, locale: &Locale) -> String {
+    let project = projects.project();
+
+    match panel {
+        ProjectText::Label => match project {
+            Some(project) => locale.format(
+                "projects.project",
+                &[
+                    &project.name,
+                    &(projects.selected.unwrap_or(0) + 1),
+                    &projects.projects.len(),
+                ],
+            ),
+            None => locale.get("projects.new_project").into(),
+        },
+        ProjectText::Choice(Choice::Nature) => projects
+            .nature
+            .map_or(locale.get("projects.any"), <&str>::from)
+            .into(),
+        ProjectText::Choice(Choice::Ball) => projects
+            .ball
+            .map_or(locale.get("projects.any"), |ball| BALLS[ball])
+            .into(),
+        ProjectText::Choice(Choice::Status) => locale.get(status_key(projects.status)).into(),
+        ProjectText::Parents => {
+            let parents = projects
+                .parents
+                .iter()
+                .map(OwnedPokemon::name)
+                .collect::<Vec<_>>();
+            if projects.project_id().is_none() {
+                "".into()
+            } else if parents.is_empty() {
+                locale.format("projects.parents", &[&locale.get("common.none")])
+            } else {
+                locale.format("projects.parents", &[&parents.join(", ")])
+            }
+        }
+        ProjectText::Progress => {
+            let Some(project) = project else {
+                return "".into();
+            };
+            let goals = project.goals().len();
+
+            match project.best_egg(&projects.eggs) {
+                _ if goals == 0 => locale.get("projects.no_goals").into(),
+                None => locale.get("projects.no_eggs").into(),
+                Some((_, missed)) if missed.is_empty() => {
+                    locale.format("projects.goal_met", &[&projects.eggs.len()])
+                }
+                Some((_, missed)) => locale.format(
+                    "projects.progress",
+                    &[
+                        &projects.eggs.len(),
+                        &(goals - missed.len()),
+                        &goals,
+                        &goals_text(&missed, locale),
+                    ],
+                ),
+            }
+        }
+        ProjectText::Pairing => match (project, &projects.pairing) {
+            (None, _) => "".into(),
+            (Some(_), None) => locale.get("projects.no_pairing").into(),
+            (Some(project), Some(pairing)) => locale.format(
+                "projects.pairing",
+                &[
+                    &pairing.mother.name(),
+                    &pairing.other.name(),
+                    &pairing.passed_on.len(),
+                    &project.goals().len(),
+                    &goals_text(&pairing.passed_on, locale),
+                ],
+            ),
+        },
+        ProjectText::Status => projects.message.clone(),
+    }
+}
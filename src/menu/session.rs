@@ -0,0 +1,215 @@
+//! What was last typed and bred on the Breed, Simulate, Search and Compare
+//! screens, kept between runs in an autosave, and saved to or loaded from
+//! session files from the Breed screen.
+
+use super::*;
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use bevy::time::common_conditions::on_timer;
+use serde::{Deserialize, Serialize};
+
+use crate::simulator::{MIN_GENERATION, SimConfig};
+
+/// Environment variable to keep the autosave somewhere else.
+const AUTOSAVE_PATH_VAR: &str = "TCSS445_AUTOSAVE";
+const DEFAULT_AUTOSAVE_PATH: &str = "autosave.ron";
+/// How often the session is autosaved, if it changed.
+const AUTOSAVE_INTERVAL: Duration = Duration::from_secs(10);
+
+pub struct SessionPlugin;
+
+impl Plugin for SessionPlugin {
+    fn build(&self, app: &mut App) {
+        let path = Session::autosave_path();
+        let session = match Session::load(&path) {
+            Ok(session) => session,
+            Err(SessionError::Io(e)) if e.kind() == std::io::ErrorKind::NotFound => {
+                Session::new(app.world().resource::<Settings>())
+            }
+            Err(e) => {
+                warn!("Failed to load the autosave {}: {e}", path.display());
+                Session::new(app.world().resource::<Settings>())
+            }
+        };
+
+        app.insert_resource(session)
+            .add_systems(Update, track_inputs)
+            // Last, so closing the window still saves.
+            .add_systems(
+                Last,
+                autosave.run_if(on_timer(AUTOSAVE_INTERVAL).or(on_event::<AppExit>)),
+            );
+    }
+}
+
+/// Everything on the Breed, Simulate, Search and Compare screens worth
+/// keeping.
+#[derive(Resource, Serialize, Deserialize, Clone, Default, PartialEq, Debug)]
+#[serde(default)]
+pub(super) struct Session {
+    /// What is typed into the Breed screen's inputs.
+    pub mother: String,
+    pub other: String,
+    pub parents: BreedParents,
+    /// What the parents bred into when last submitted.
+    pub report: Option<BreedReport>,
+    /// How the Simulate screen hatches eggs.
+    pub simulation: SimConfig,
+    /// What is typed into the Search screen's input.
+    pub search: String,
+    /// What is typed into the Compare screen's input.
+    pub compare: String,
+}
+
+impl Session {
+    fn new(settings: &Settings) -> Self {
+        Self {
+            simulation: SimConfig {
+                generation: settings.default_generation.max(MIN_GENERATION),
+                ..default()
+            },
+            ..default()
+        }
+    }
+
+    fn autosave_path() -> PathBuf {
+        std::env::var_os(AUTOSAVE_PATH_VAR)
+            .map(PathBuf::from)
+            .unwrap_or_else(|| DEFAULT_AUTOSAVE_PATH.into())
+    }
+
+    pub fn load(path: &Path) -> Result<Self, SessionError> {
+        let contents = std::fs::read_to_string(path)?;
+        Ok(ron::from_str(&contents)?)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), SessionError> {
+        let contents = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())?;
+        std::fs::write(path, contents)?;
+        Ok(())
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub(super) enum SessionError {
+    #[error("failed to read the session: {0}")]
+    Parse(#[from] ron::error::SpannedError),
+    #[error("failed to write the session: {0}")]
+    Serialize(#[from] ron::Error),
+    #[error("{0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Keeps what is typed in the session, without counting as a change, so
+/// typing doesn't redraw the screens.
+fn track_inputs(
+    mut session: ResMut<Session>,
+    inputs: Query<
+        (
+            &TextInputContents,
+            Has<MotherPokemonInput>,
+            Has<OtherPokemonInput>,
+            Has<SearchPokemonInput>,
+            Has<CompareInput>,
+        ),
+        Changed<TextInputContents>,
+    >,
+) {
+    let session = session.bypass_change_detection();
+    for (contents, mother, other, search, compare) in &inputs {
+        let text = contents.get().to_string();
+        match (mother, other, search, compare) {
+            (true, _, _, _) => session.mother = text,
+            (_, true, _, _) => session.other = text,
+            (_, _, true, _) => session.search = text,
+            (_, _, _, true) => session.compare = text,
+            _ => {}
+        }
+    }
+}
+
+fn autosave(session: Res<Session>, mut saved: Local<Option<Session>>) {
+    if saved.as_ref() == Some(&*session) {
+        return;
+    }
+
+    let path = Session::autosave_path();
+    match session.save(&path) {
+        Ok(()) => *saved = Some(session.clone()),
+        Err(e) => error!("Failed to autosave to {}: {e}", path.display()),
+    }
+}
+
+/// The session file named on the Breed screen.
+#[derive(Component)]
+pub(super) struct SessionFileInput;
+
+/// Whether the session was saved or loaded, or why not.
+#[derive(Component)]
+pub(super) struct SessionStatus;
+
+pub(super) fn save_session_on_click(
+    mut click: Trigger<Pointer<Click>>,
+    input: Single<&TextInputContents, With<SessionFileInput>>,
+    mut status: Single<&mut Text, With<SessionStatus>>,
+    session: Res<Session>,
+    locale: Res<Locale>,
+) {
+    click.propagate(false);
+
+    if click.button != PointerButton::Primary {
+        return;
+    }
+
+    let path = input.get().trim();
+    status.0 = if path.is_empty() {
+        locale.get("breed.session_file_required").into()
+    } else {
+        match session.save(Path::new(path)) {
+            Ok(()) => locale.format("breed.session_saved", &[&path]),
+            Err(e) => locale.format("breed.session_error", &[&e]),
+        }
+    };
+}
+
+pub(super) fn load_session_on_click(
+    mut click: Trigger<Pointer<Click>>,
+    input: Single<&TextInputContents, With<SessionFileInput>>,
+    mut status: Single<&mut Text, With<SessionStatus>>,
+    mut parents: Query<
+        (&mut TextInputQueue, Has<MotherPokemonInput>),
+        Or<(With<MotherPokemonInput>, With<OtherPokemonInput>)>,
+    >,
+    mut session: ResMut<Session>,
+    locale: Res<Locale>,
+) {
+    click.propagate(false);
+
+    if click.button != PointerButton::Primary {
+        return;
+    }
+
+    let path = input.get().trim();
+    if path.is_empty() {
+        status.0 = locale.get("breed.session_file_required").into();
+        return;
+    }
+
+    match Session::load(Path::new(path)) {
+        Ok(loaded) => {
+            *session = loaded;
+            for (mut queue, mother) in &mut parents {
+                let text = if mother {
+                    &session.mother
+                } else {
+                    &session.other
+                };
+                set_input_text(&mut queue, text);
+            }
+            status.0 = locale.format("breed.session_loaded", &[&path]);
+        }
+        Err(e) => status.0 = locale.format("breed.session_error", &[&e]),
+    }
+}
@@ -0,0 +1,173 @@
+//! The settings screen, changing the [`Settings`] that are saved between runs.
+//!
+
+use super::*;
+
+const UI_SCALE_STEP: f32 = 0.1;
+const FONT_SIZE_STEP: f32 = 3.0;
+
+pub struct SettingsScreenPlugin;
+
+impl Plugin for SettingsScreenPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(MenuState::Settings),
+            (settings_enter, settings_redraw, database_path_prefill).chain(),
+        )
+        .add_systems(
+            Update,
+            settings_redraw
+                .after(ApplySettings)
+                .run_if(in_state(MenuState::Settings).and(resource_changed::<Settings>)),
+        );
+    }
+}
+
+/// A setting that is stepped through with the buttons beside it.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Setting {
+    UiScale,
+    FontSize,
+    Theme,
+    Language,
+    Generation,
+}
+
+/// The text showing the current value of a setting.
+#[derive(Component)]
+struct SettingValue(Setting);
+
+#[derive(Component)]
+struct DatabasePathInput;
+
+/// Which database is open, which may not be the one asked for if it failed.
+#[derive(Component)]
+struct DatabaseStatus;
+
+fn settings_enter(mut commands: Commands, font: Res<GameFont>) {
+    spawn_screen(&mut commands, &font, MenuState::Settings, |ui| {
+        for (name, setting) in [
+            ("settings.ui_scale", Setting::UiScale),
+            ("settings.font_size", Setting::FontSize),
+            ("settings.generation", Setting::Generation),
+        ] {
+            ui.row(|ui| {
+                ui.label(name);
+                ui.pager(
+                    SettingValue(setting),
+                    step_setting_on_click(setting, -1),
+                    step_setting_on_click(setting, 1),
+                );
+            });
+        }
+
+        for (name, setting) in [
+            ("settings.theme", Setting::Theme),
+            ("settings.language", Setting::Language),
+        ] {
+            ui.row(|ui| {
+                ui.label(name);
+                ui.dynamic_button(SettingValue(setting), step_setting_on_click(setting, 1));
+            });
+        }
+
+        ui.long_text_input("settings.database", None, DatabasePathInput);
+        ui.result_panel(DatabaseStatus);
+        ui.button("settings.open", open_database_on_click)
+            .insert(SubmitButton);
+
+        ui.back_button();
+    });
+}
+
+fn step_setting_on_click(
+    setting: Setting,
+    step: i8,
+) -> impl Fn(Trigger<Pointer<Click>>, ResMut<Settings>, Res<Themes>) {
+    move |mut click, mut settings, themes| {
+        click.propagate(false);
+
+        if click.button != PointerButton::Primary {
+            return;
+        }
+
+        match setting {
+            Setting::UiScale => {
+                let scale = settings.ui_scale + UI_SCALE_STEP * step as f32;
+                // Rounded so repeated steps don't drift away from tenths.
+                settings.ui_scale =
+                    ((scale * 10.0).round() / 10.0).clamp(MIN_UI_SCALE, MAX_UI_SCALE);
+            }
+            Setting::FontSize => {
+                settings.font_size = (settings.font_size + FONT_SIZE_STEP * step as f32)
+                    .clamp(MIN_FONT_SIZE, MAX_FONT_SIZE);
+            }
+            Setting::Theme => {
+                settings.theme = themes.next(&settings.theme).into();
+            }
+            Setting::Language => {
+                settings.language = settings.language.next();
+            }
+            Setting::Generation => {
+                settings.default_generation = settings
+                    .default_generation
+                    .saturating_add_signed(step)
+                    .clamp(1, MAX_GENERATION);
+            }
+        }
+    }
+}
+
+fn open_database_on_click(
+    mut click: Trigger<Pointer<Click>>,
+    input: Single<&TextInputContents, With<DatabasePathInput>>,
+    mut settings: ResMut<Settings>,
+) {
+    click.propagate(false);
+
+    if click.button != PointerButton::Primary {
+        return;
+    }
+
+    let path = input.get().trim();
+    let path = (!path.is_empty()).then(|| path.into());
+
+    if settings.database_path != path {
+        settings.database_path = path;
+    }
+}
+
+fn settings_redraw(
+    settings: Res<Settings>,
+    mut values: Query<(&mut Text, &SettingValue)>,
+    mut status: Single<&mut Text, (With<DatabaseStatus>, Without<SettingValue>)>,
+    locale: Res<Locale>,
+    db: NonSend<Database>,
+) {
+    for (mut text, SettingValue(setting)) in &mut values {
+        text.0 = match setting {
+            Setting::UiScale => format!("{:.1}x", settings.ui_scale),
+            Setting::FontSize => format!("{}", settings.font_size),
+            Setting::Theme => settings.theme.clone(),
+            Setting::Language => settings.language.name().into(),
+            Setting::Generation => {
+                locale.format("settings.generation_value", &[&settings.default_generation])
+            }
+        };
+    }
+
+    status.0 = match (&db.path, &settings.database_path) {
+        (open, wanted) if open != wanted => locale.get("settings.database_failed").into(),
+        (Some(path), _) => locale.format("settings.database_file", &[&path.display()]),
+        (None, _) => locale.get("settings.database_memory").into(),
+    };
+}
+
+fn database_path_prefill(
+    settings: Res<Settings>,
+    mut input: Single<&mut TextInputQueue, With<DatabasePathInput>>,
+) {
+    if let Some(path) = &settings.database_path {
+        set_input_text(&mut input, &path.to_string_lossy());
+    }
+}
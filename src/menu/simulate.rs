@@ -0,0 +1,348 @@
+//! Hatching many eggs at random from the two Pokemon from the boxes on the
+//! Breed screen, with [`simulate`], and showing what hatched as histograms.
+
+use super::*;
+
+use crate::planner::Item;
+use crate::simulator::{MIN_GENERATION, SimConfig, SimError, Simulation, simulate};
+
+/// How many bars of a histogram fit on screen, the rest are left out.
+const MAX_BARS: usize = 10;
+const BAR_WIDTH: f32 = 300.0;
+const BAR_HEIGHT: f32 = 18.0;
+const BAR_LABEL_WIDTH: f32 = 220.0;
+
+pub struct SimulatePlugin;
+
+impl Plugin for SimulatePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(MenuState::Simulate), simulate_enter)
+            .add_systems(
+                Update,
+                simulate_redraw.run_if(
+                    in_state(MenuState::Simulate).and(
+                        resource_exists_and_changed::<Simulator>.or(resource_changed::<Session>),
+                    ),
+                ),
+            );
+    }
+}
+
+/// The pair being simulated and what hatched last, simulated as the
+/// [`Session`] says.
+#[derive(Resource)]
+struct Simulator {
+    /// `None` unless both parents on the Breed screen are from the boxes.
+    parents: Option<(OwnedPokemon, OwnedPokemon)>,
+    simulation: Option<Simulation>,
+    /// Which of the simulation's histograms is shown.
+    chart: usize,
+    message: String,
+    /// Whether the text inputs need filling in again.
+    refill_form: bool,
+}
+
+/// A setting that is stepped through with its button.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Choice {
+    Generation,
+    MotherItem,
+    OtherItem,
+    Masuda,
+    ShinyCharm,
+}
+
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+enum FormInput {
+    Eggs,
+    Seed,
+}
+
+/// Text that is filled in from [`Simulator`].
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+enum SimText {
+    Parents,
+    Choice(Choice),
+    /// The title of the histogram shown.
+    Chart,
+    Status,
+}
+
+/// The column the bars of the histogram are spawned into.
+#[derive(Component)]
+struct ChartBars;
+
+fn simulate_enter(mut commands: Commands, font: Res<GameFont>, session: Res<Session>) {
+    let parents = &session.parents;
+    commands.insert_resource(Simulator {
+        parents: parents.mother.clone().zip(parents.other.clone()),
+        simulation: None,
+        chart: 0,
+        message: String::new(),
+        refill_form: true,
+    });
+
+    spawn_screen(&mut commands, &font, MenuState::Simulate, |ui| {
+        ui.result_panel(SimText::Parents);
+        ui.row(|ui| {
+            ui.column(|ui| {
+                for (name, choice) in [
+                    ("sim.generation", Choice::Generation),
+                    ("sim.mother_item", Choice::MotherItem),
+                    ("sim.other_item", Choice::OtherItem),
+                    ("sim.masuda", Choice::Masuda),
+                    ("sim.shiny_charm", Choice::ShinyCharm),
+                ] {
+                    ui.row(|ui| {
+                        ui.label(name);
+                        ui.dynamic_button(SimText::Choice(choice), step_choice_on_click(choice, 1));
+                    });
+                }
+            });
+            ui.column(|ui| {
+                ui.text_input("sim.eggs", FormInput::Eggs);
+                ui.text_input("sim.seed", FormInput::Seed);
+                ui.pager(SimText::Chart, chart_on_click(-1), chart_on_click(1));
+                ui.list(ChartBars);
+            });
+        });
+
+        ui.result_panel(SimText::Status);
+        ui.row(|ui| {
+            ui.button("sim.run", run_on_click).insert(SubmitButton);
+            ui.state_button("common.back", MenuState::Breed)
+                .insert(BackButton);
+        });
+    });
+}
+
+fn step_choice_on_click(
+    choice: Choice,
+    step: isize,
+) -> impl Fn(Trigger<Pointer<Click>>, ResMut<Session>) {
+    move |mut click, mut session| {
+        click.propagate(false);
+
+        if click.button != PointerButton::Primary {
+            return;
+        }
+
+        let items = || std::iter::once(None).chain(Item::all().map(Some)).collect();
+        let config = &mut session.simulation;
+        match choice {
+            Choice::Generation => {
+                config.generation = step_through(
+                    (MIN_GENERATION..=MAX_GENERATION).collect(),
+                    config.generation,
+                    step,
+                );
+            }
+            Choice::MotherItem => {
+                config.mother_item = step_through(items(), config.mother_item, step)
+            }
+            Choice::OtherItem => config.other_item = step_through(items(), config.other_item, step),
+            Choice::Masuda => config.masuda = !config.masuda,
+            Choice::ShinyCharm => config.shiny_charm = !config.shiny_charm,
+        }
+    }
+}
+
+fn chart_on_click(step: isize) -> impl Fn(Trigger<Pointer<Click>>, ResMut<Simulator>, Res<Locale>) {
+    move |mut click, mut simulator, locale| {
+        click.propagate(false);
+
+        if click.button != PointerButton::Primary {
+            return;
+        }
+
+        let Some(simulation) = &simulator.simulation else {
+            return;
+        };
+        let count = simulation.histograms(&locale).len();
+        simulator.chart = step_index(simulator.chart, step, count);
+    }
+}
+
+fn run_on_click(
+    mut click: Trigger<Pointer<Click>>,
+    inputs: Query<(&TextInputContents, &FormInput)>,
+    mut simulator: ResMut<Simulator>,
+    mut session: ResMut<Session>,
+    locale: Res<Locale>,
+    db: NonSend<Database>,
+) {
+    click.propagate(false);
+
+    if click.button != PointerButton::Primary {
+        return;
+    }
+
+    let Some((mother, other)) = simulator.parents.clone() else {
+        simulator.message = locale.get("sim.needs_boxes").into();
+        return;
+    };
+
+    for (contents, input) in &inputs {
+        let text = contents.get().trim();
+        let read = match input {
+            FormInput::Eggs => text.parse().map(|eggs| session.simulation.eggs = eggs),
+            FormInput::Seed => text.parse().map(|seed| session.simulation.seed = seed),
+        };
+        if read.is_err() {
+            simulator.message = locale.format("sim.not_a_number", &[&text]);
+            return;
+        }
+    }
+
+    match simulate(&db, &mother, &other, &session.simulation) {
+        Ok(simulation) => {
+            simulator.message = locale.format(
+                "sim.summary",
+                &[
+                    &simulation.config.eggs,
+                    &simulation.species,
+                    &simulation.config.generation,
+                ],
+            );
+            simulator.simulation = Some(simulation);
+        }
+        Err(SimError::NoEgg(..)) => {
+            simulator.message = locale.get("breed.bad_match").into();
+            simulator.simulation = None;
+        }
+        Err(e) => {
+            simulator.message = e.to_string();
+            simulator.simulation = None;
+        }
+    }
+}
+
+fn simulate_redraw(
+    mut commands: Commands,
+    mut simulator: ResMut<Simulator>,
+    mut texts: Query<(&mut Text, &SimText)>,
+    mut inputs: Query<(&mut TextInputQueue, &FormInput)>,
+    bars: Single<Entity, With<ChartBars>>,
+    session: Res<Session>,
+    font: Res<GameFont>,
+    locale: Res<Locale>,
+) {
+    let histogram = simulator.simulation.as_ref().and_then(|simulation| {
+        simulation
+            .histograms(&locale)
+            .into_iter()
+            .nth(simulator.chart)
+    });
+
+    for (mut text, panel) in &mut texts {
+        text.0 = match panel {
+            SimText::Parents => match &simulator.parents {
+                Some((mother, other)) => {
+                    locale.format("sim.parents", &[&mother.name(), &other.name()])
+                }
+                None => locale.get("sim.needs_boxes").into(),
+            },
+            SimText::Choice(choice) => choice_text(&session.simulation, *choice, &locale),
+            SimText::Chart => histogram
+                .as_ref()
+                .map_or(String::new(), |histogram| histogram.title.clone()),
+            SimText::Status => simulator.message.clone(),
+        };
+    }
+
+    commands.entity(*bars).despawn_related::<Children>();
+    if let Some(histogram) = histogram {
+        let eggs = simulator
+            .simulation
+            .as_ref()
+            .map_or(1, |simulation| simulation.config.eggs.max(1));
+        let most = histogram
+            .bars
+            .iter()
+            .map(|(_, count)| *count)
+            .max()
+            .unwrap_or(0)
+            .max(1);
+        let (bar_font, _, _) = small_text_style(&font);
+
+        commands.entity(*bars).with_children(|builder| {
+            if histogram.bars.is_empty() {
+                builder.spawn((
+                    bar_font.clone(),
+                    ThemedText,
+                    Text::new(locale.get("common.none")),
+                    Pickable::IGNORE,
+                ));
+            }
+            for (label, count) in histogram.bars.iter().take(MAX_BARS) {
+                builder
+                    .spawn((
+                        Node {
+                            column_gap: Val::Px(10.0),
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        Pickable::IGNORE,
+                    ))
+                    .with_children(|builder| {
+                        builder.spawn((
+                            Node {
+                                width: Val::Px(BAR_LABEL_WIDTH),
+                                ..default()
+                            },
+                            bar_font.clone(),
+                            ThemedText,
+                            TextLayout::new_with_justify(JustifyText::Right),
+                            Text::new(label.clone()),
+                            Pickable::IGNORE,
+                        ));
+                        builder.spawn((
+                            Node {
+                                width: Val::Px(BAR_WIDTH * *count as f32 / most as f32),
+                                height: Val::Px(BAR_HEIGHT),
+                                ..default()
+                            },
+                            ThemedBackground::Button,
+                            Pickable::IGNORE,
+                        ));
+                        builder.spawn((
+                            bar_font.clone(),
+                            ThemedText,
+                            Text::new(format!("{:.1}%", *count as f32 * 100.0 / eggs as f32)),
+                            Pickable::IGNORE,
+                        ));
+                    });
+            }
+        });
+    }
+
+    if !simulator.refill_form {
+        return;
+    }
+    simulator.bypass_change_detection().refill_form = false;
+
+    for (mut queue, input) in &mut inputs {
+        let text = match input {
+            FormInput::Eggs => session.simulation.eggs.to_string(),
+            FormInput::Seed => session.simulation.seed.to_string(),
+        };
+        set_input_text(&mut queue, &text);
+    }
+}
+
+fn choice_text(config: &SimConfig, choice: Choice, locale: &Locale) -> String {
+    let item = |item: Option<Item>| item.map_or(locale.get("common.none"), Item::name).into();
+    let toggle = |on: bool| {
+        locale
+            .get(if on { "common.on" } else { "common.off" })
+            .into()
+    };
+
+    match choice {
+        Choice::Generation => locale.format("settings.generation_value", &[&config.generation]),
+        Choice::MotherItem => item(config.mother_item),
+        Choice::OtherItem => item(config.other_item),
+        Choice::Masuda => toggle(config.masuda),
+        Choice::ShinyCharm => toggle(config.shiny_charm),
+    }
+}
@@ -0,0 +1,71 @@
+//! Pokemon sprites, loaded from a local directory of PNGs named by dex number.
+//!
+
+use super::*;
+
+use bevy::asset::LoadState;
+use bevy::asset::io::AssetSource;
+
+const PLACEHOLDER_PATH: &str = "embedded://assets/sprite_placeholder.png";
+/// The environment variable that points at the sprite directory.
+const SPRITE_DIR_VAR: &str = "TCSS445_SPRITE_DIR";
+const DEFAULT_SPRITE_DIR: &str = "sprites";
+const SPRITE_SOURCE: &str = "sprites";
+const SPRITE_SIZE: f32 = 48.0;
+
+/// Adds the `sprites://` asset source.
+///
+/// This has to happen before the `AssetPlugin` is added.
+pub fn register_sprite_source(app: &mut App) {
+    let dir = std::env::var(SPRITE_DIR_VAR).unwrap_or_else(|_| DEFAULT_SPRITE_DIR.into());
+
+    app.register_asset_source(
+        SPRITE_SOURCE,
+        AssetSource::build().with_reader(AssetSource::get_default_reader(dir)),
+    );
+}
+
+pub struct PokemonSpritePlugin;
+
+impl Plugin for PokemonSpritePlugin {
+    fn build(&self, app: &mut App) {
+        embed_asset!(app, "assets/sprite_placeholder.png");
+
+        app.add_systems(Update, sprite_fallback);
+    }
+}
+
+/// Marks a sprite that is still loading, so it can be swapped for the
+/// placeholder if the file is missing.
+#[derive(Component)]
+struct PendingSprite;
+
+/// The sprite for the Pokemon with this dex number.
+pub fn pokemon_sprite(pokemon_id: i64, asset_server: &AssetServer) -> impl Bundle + use<> {
+    (
+        Node {
+            width: Val::Px(SPRITE_SIZE),
+            height: Val::Px(SPRITE_SIZE),
+            ..default()
+        },
+        ImageNode::new(asset_server.load(format!("{SPRITE_SOURCE}://{pokemon_id}.png"))),
+        PendingSprite,
+        Pickable::IGNORE,
+    )
+}
+
+fn sprite_fallback(
+    mut commands: Commands,
+    mut sprites: Query<(Entity, &mut ImageNode), With<PendingSprite>>,
+    asset_server: Res<AssetServer>,
+) {
+    for (entity, mut image) in &mut sprites {
+        match asset_server.load_state(&image.image) {
+            LoadState::NotLoaded | LoadState::Loading => continue,
+            LoadState::Loaded => {}
+            LoadState::Failed(_) => image.image = asset_server.load(PLACEHOLDER_PATH),
+        }
+
+        commands.entity(entity).remove::<PendingSprite>();
+    }
+}
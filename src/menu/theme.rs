@@ -0,0 +1,260 @@
+//! Color themes, picked in the settings.
+//!
+//! Widgets don't color themselves, they are tagged with [`ThemedBackground`]
+//! or [`ThemedText`] and colored from the current [`Theme`], so switching
+//! themes recolors the screen in place.
+//!
+//! Besides the built in themes, every `.ron` file in the theme directory
+//! (`TCSS445_THEME_DIR`, or `themes`) is loaded as a [`Theme`], like:
+//!
+//! ```ron
+//! (
+//!     name: "Forest",
+//!     background: "#1B2B1B",
+//!     text: "#F0F0E0",
+//!     text_input: "#2F4F2F",
+//!     button: "#3B5B3B",
+//!     hovered_button: "#6B8B3B",
+//!     pressed_button: "#101810",
+//! )
+//! ```
+
+use std::path::PathBuf;
+
+use super::*;
+
+use serde::{Deserialize, Serialize};
+
+/// Environment variable to read user themes from somewhere else.
+const THEME_DIR_VAR: &str = "TCSS445_THEME_DIR";
+const DEFAULT_THEME_DIR: &str = "themes";
+
+pub struct ThemePlugin;
+
+impl Plugin for ThemePlugin {
+    fn build(&self, app: &mut App) {
+        let themes = Themes::load();
+        let theme = app
+            .world()
+            .get_resource::<Settings>()
+            .map_or_else(Theme::dark, |settings| themes.get(&settings.theme).clone());
+
+        app.insert_resource(ClearColor(theme.background))
+            .insert_resource(theme)
+            .insert_resource(themes)
+            .add_observer(background_on_add)
+            .add_observer(text_on_add)
+            .add_systems(
+                Update,
+                (
+                    select_theme.run_if(resource_changed::<Settings>),
+                    restyle.run_if(resource_changed::<Theme>),
+                )
+                    .chain()
+                    .after(ApplySettings),
+            );
+    }
+}
+
+#[derive(Resource, Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Theme {
+    pub name: String,
+    #[serde(with = "hex_color")]
+    pub background: Color,
+    #[serde(with = "hex_color")]
+    pub text: Color,
+    #[serde(with = "hex_color")]
+    pub text_input: Color,
+    #[serde(with = "hex_color")]
+    pub button: Color,
+    #[serde(with = "hex_color")]
+    pub hovered_button: Color,
+    #[serde(with = "hex_color")]
+    pub pressed_button: Color,
+}
+
+impl Theme {
+    pub fn dark() -> Self {
+        Self {
+            name: "Dark".into(),
+            background: Color::srgb_u8(0x2B, 0x2C, 0x2F),
+            text: Color::srgb_u8(0xFF, 0xFF, 0xFF),
+            text_input: Color::srgb_u8(0x33, 0x55, 0x33),
+            button: Color::srgb_u8(0x33, 0x55, 0x77),
+            hovered_button: Color::srgb_u8(0x77, 0x55, 0x33),
+            pressed_button: Color::srgb_u8(0x00, 0x00, 0x00),
+        }
+    }
+
+    pub fn light() -> Self {
+        Self {
+            name: "Light".into(),
+            background: Color::srgb_u8(0xEE, 0xEE, 0xE4),
+            text: Color::srgb_u8(0x1A, 0x1A, 0x1A),
+            text_input: Color::srgb_u8(0xC4, 0xDC, 0xC4),
+            button: Color::srgb_u8(0xA8, 0xC4, 0xE0),
+            hovered_button: Color::srgb_u8(0xE0, 0xC4, 0xA8),
+            pressed_button: Color::srgb_u8(0xFF, 0xFF, 0xFF),
+        }
+    }
+
+    pub fn high_contrast() -> Self {
+        Self {
+            name: "High Contrast".into(),
+            background: Color::srgb_u8(0x00, 0x00, 0x00),
+            text: Color::srgb_u8(0xFF, 0xFF, 0xFF),
+            text_input: Color::srgb_u8(0x00, 0x40, 0x00),
+            button: Color::srgb_u8(0x00, 0x00, 0x90),
+            hovered_button: Color::srgb_u8(0x80, 0x00, 0x80),
+            pressed_button: Color::srgb_u8(0x00, 0x00, 0x00),
+        }
+    }
+}
+
+/// Every theme that can be picked, the built in ones first.
+#[derive(Resource)]
+pub struct Themes(Vec<Theme>);
+
+impl Themes {
+    fn load() -> Self {
+        let mut themes = vec![Theme::dark(), Theme::light(), Theme::high_contrast()];
+
+        let dir = std::env::var_os(THEME_DIR_VAR)
+            .map(PathBuf::from)
+            .unwrap_or_else(|| DEFAULT_THEME_DIR.into());
+
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            return Self(themes);
+        };
+
+        let mut paths = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "ron"))
+            .collect::<Vec<_>>();
+        paths.sort();
+
+        for path in paths {
+            let theme = std::fs::read_to_string(&path)
+                .map_err(|e| e.to_string())
+                .and_then(|contents| ron::from_str::<Theme>(&contents).map_err(|e| e.to_string()));
+
+            match theme {
+                Ok(theme) if themes.iter().any(|t| t.name == theme.name) => {
+                    warn!(
+                        "Skipping theme {}, {} already exists",
+                        path.display(),
+                        theme.name
+                    )
+                }
+                Ok(theme) => themes.push(theme),
+                Err(e) => warn!("Failed to load theme {}: {e}", path.display()),
+            }
+        }
+
+        Self(themes)
+    }
+
+    /// The theme called `name`, or the first theme if there is none.
+    pub fn get(&self, name: &str) -> &Theme {
+        self.0
+            .iter()
+            .find(|theme| theme.name == name)
+            .unwrap_or(&self.0[0])
+    }
+
+    /// The name of the theme after the one called `name`.
+    pub fn next(&self, name: &str) -> &str {
+        let index = self
+            .0
+            .iter()
+            .position(|theme| theme.name == name)
+            .map_or(0, |index| (index + 1) % self.0.len());
+        &self.0[index].name
+    }
+}
+
+/// Which color of the theme something's background is.
+///
+/// Buttons get this too, but `button_highlight` keeps their color after that.
+#[derive(Component, Clone, Copy, Debug)]
+#[require(BackgroundColor)]
+pub enum ThemedBackground {
+    Button,
+    TextInput,
+    Pressed,
+    /// Between the text input and hovered button colors, by how much from 0 to 1.
+    Heat(f32),
+}
+
+impl ThemedBackground {
+    fn color(self, theme: &Theme) -> Color {
+        match self {
+            ThemedBackground::Button => theme.button,
+            ThemedBackground::TextInput => theme.text_input,
+            ThemedBackground::Pressed => theme.pressed_button,
+            ThemedBackground::Heat(heat) => theme.text_input.mix(&theme.hovered_button, heat),
+        }
+    }
+}
+
+/// Text in the theme's text color.
+#[derive(Component, Default)]
+#[require(TextColor)]
+pub struct ThemedText;
+
+fn background_on_add(
+    trigger: Trigger<OnAdd, ThemedBackground>,
+    mut backgrounds: Query<(&mut BackgroundColor, &ThemedBackground)>,
+    theme: Res<Theme>,
+) {
+    if let Ok((mut background, themed)) = backgrounds.get_mut(trigger.target()) {
+        background.0 = themed.color(&theme);
+    }
+}
+
+fn text_on_add(
+    trigger: Trigger<OnAdd, ThemedText>,
+    mut texts: Query<&mut TextColor>,
+    theme: Res<Theme>,
+) {
+    if let Ok(mut text) = texts.get_mut(trigger.target()) {
+        text.0 = theme.text;
+    }
+}
+
+fn select_theme(settings: Res<Settings>, themes: Res<Themes>, mut theme: ResMut<Theme>) {
+    if theme.name != settings.theme {
+        *theme = themes.get(&settings.theme).clone();
+    }
+}
+
+fn restyle(
+    theme: Res<Theme>,
+    mut clear_color: ResMut<ClearColor>,
+    mut backgrounds: Query<(&mut BackgroundColor, &ThemedBackground), Without<Button>>,
+    mut texts: Query<&mut TextColor, With<ThemedText>>,
+) {
+    clear_color.0 = theme.background;
+
+    for (mut background, themed) in &mut backgrounds {
+        background.0 = themed.color(&theme);
+    }
+    for mut text in &mut texts {
+        text.0 = theme.text;
+    }
+}
+
+/// Colors as hex strings, like `"#335577"`, so theme files are easy to write.
+mod hex_color {
+    use bevy::prelude::*;
+    use serde::{Deserialize, Deserializer, Serializer, de::Error};
+
+    pub fn serialize<S: Serializer>(color: &Color, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&color.to_srgba().to_hex())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+        Srgba::hex(&hex).map(Color::from).map_err(D::Error::custom)
+    }
+}
@@ -0,0 +1,162 @@
+//! Colored badges for Pokemon and move types.
+//!
+
+use super::*;
+
+use bevy::ecs::spawn::SpawnIter;
+use strum::{EnumCount, EnumString};
+
+const TYPE_ICONS_PATH: &str = "embedded://assets/types.png";
+/// The size of each icon in the type atlas, in pixels.
+const TYPE_ICON_SIZE: u32 = 16;
+
+pub struct TypeBadgePlugin;
+
+impl Plugin for TypeBadgePlugin {
+    fn build(&self, app: &mut App) {
+        embed_asset!(app, "assets/types.png");
+
+        app.add_systems(Startup, load_type_icons);
+    }
+}
+
+/// The atlas of type icons, one per [`PokemonType`] plus one for unknown types.
+#[derive(Resource)]
+pub struct TypeIcons {
+    image: Handle<Image>,
+    layout: Handle<TextureAtlasLayout>,
+}
+
+/// Every type, in the same order as the icons in the type atlas.
+#[derive(EnumString, EnumCount, Clone, Copy, PartialEq, Eq, Debug)]
+#[strum(ascii_case_insensitive)]
+pub enum PokemonType {
+    Normal,
+    Fire,
+    Water,
+    Electric,
+    Grass,
+    Ice,
+    Fighting,
+    Poison,
+    Ground,
+    Flying,
+    Psychic,
+    Bug,
+    Rock,
+    Ghost,
+    Dragon,
+    Dark,
+    Steel,
+    Fairy,
+}
+
+impl PokemonType {
+    pub fn color(self) -> Color {
+        match self {
+            Self::Normal => Color::srgb_u8(0xA8, 0xA8, 0x78),
+            Self::Fire => Color::srgb_u8(0xF0, 0x80, 0x30),
+            Self::Water => Color::srgb_u8(0x68, 0x90, 0xF0),
+            Self::Electric => Color::srgb_u8(0xF8, 0xD0, 0x30),
+            Self::Grass => Color::srgb_u8(0x78, 0xC8, 0x50),
+            Self::Ice => Color::srgb_u8(0x98, 0xD8, 0xD8),
+            Self::Fighting => Color::srgb_u8(0xC0, 0x30, 0x28),
+            Self::Poison => Color::srgb_u8(0xA0, 0x40, 0xA0),
+            Self::Ground => Color::srgb_u8(0xE0, 0xC0, 0x68),
+            Self::Flying => Color::srgb_u8(0xA8, 0x90, 0xF0),
+            Self::Psychic => Color::srgb_u8(0xF8, 0x58, 0x88),
+            Self::Bug => Color::srgb_u8(0xA8, 0xB8, 0x20),
+            Self::Rock => Color::srgb_u8(0xB8, 0xA0, 0x38),
+            Self::Ghost => Color::srgb_u8(0x70, 0x58, 0x98),
+            Self::Dragon => Color::srgb_u8(0x70, 0x38, 0xF8),
+            Self::Dark => Color::srgb_u8(0x70, 0x58, 0x48),
+            Self::Steel => Color::srgb_u8(0xB8, 0xB8, 0xD0),
+            Self::Fairy => Color::srgb_u8(0xEE, 0x99, 0xAC),
+        }
+    }
+}
+
+fn load_type_icons(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut layouts: ResMut<Assets<TextureAtlasLayout>>,
+) {
+    let layout = TextureAtlasLayout::from_grid(
+        UVec2::splat(TYPE_ICON_SIZE),
+        PokemonType::COUNT as u32 + 1,
+        1,
+        None,
+        None,
+    );
+
+    commands.insert_resource(TypeIcons {
+        image: asset_server.load(TYPE_ICONS_PATH),
+        layout: layouts.add(layout),
+    });
+}
+
+/// A badge with the type's icon and name on the type's color.
+///
+/// Types that aren't known are shown in gray with the unknown icon.
+pub fn type_badge(type_name: &str, font: &GameFont, icons: &TypeIcons) -> impl Bundle + use<> {
+    let pokemon_type = type_name.parse::<PokemonType>().ok();
+
+    (
+        Node {
+            padding: UiRect::axes(Val::Px(6.0), Val::Px(2.0)),
+            margin: UiRect::horizontal(Val::Px(3.0)),
+            column_gap: Val::Px(4.0),
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        BackgroundColor(pokemon_type.map_or(Color::srgb_u8(0x68, 0x68, 0x68), PokemonType::color)),
+        BorderRadius::all(Val::Px(6.0)),
+        Pickable::IGNORE,
+        children![
+            (
+                Node {
+                    width: Val::Px(TYPE_ICON_SIZE as f32),
+                    height: Val::Px(TYPE_ICON_SIZE as f32),
+                    ..default()
+                },
+                ImageNode::from_atlas_image(
+                    icons.image.clone(),
+                    TextureAtlas {
+                        layout: icons.layout.clone(),
+                        index: pokemon_type.map_or(PokemonType::COUNT, |t| t as usize),
+                    },
+                ),
+                Pickable::IGNORE,
+            ),
+            (
+                small_text_style(font),
+                Text::new(type_name),
+                Pickable::IGNORE,
+            )
+        ],
+    )
+}
+
+/// A row of badges for a Pokemon's one or two types.
+pub fn type_badges(
+    primary_type: &str,
+    secondary_type: Option<&str>,
+    font: &GameFont,
+    icons: &TypeIcons,
+) -> impl Bundle + use<> {
+    (
+        Node {
+            flex_direction: FlexDirection::Row,
+            ..default()
+        },
+        Pickable::IGNORE,
+        Children::spawn((
+            Spawn(type_badge(primary_type, font, icons)),
+            SpawnIter(
+                secondary_type
+                    .map(|secondary| type_badge(secondary, font, icons))
+                    .into_iter(),
+            ),
+        )),
+    )
+}
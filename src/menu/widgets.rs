@@ -0,0 +1,300 @@
+//! Builders for the widgets every screen is made of.
+//!
+//! A screen is spawned with [`spawn_screen`], and its contents are added
+//! through the [`Ui`] it is handed, which keeps every screen looking the same.
+//!
+//! Fixed text is given as keys into the [`Locale`], and is translated.
+
+use super::*;
+
+use bevy::ecs::system::IntoObserverSystem;
+
+const BUTTON_WIDTH: f32 = 300.0;
+const BUTTON_HEIGHT: f32 = 65.0;
+const SMALL_BUTTON_WIDTH: f32 = 65.0;
+const INPUT_WIDTH: f32 = 500.0;
+const INPUT_HEIGHT: f32 = 60.0;
+const INPUT_MAX_CHARS: usize = 32;
+/// How big small text is next to the normal font size.
+const SMALL_FONT_SCALE: f32 = 2.0 / 3.0;
+
+/// Spawns the root of a screen, scoped to `state`, with its widgets laid out
+/// in a centered column.
+pub fn spawn_screen(
+    commands: &mut Commands,
+    font: &GameFont,
+    state: MenuState,
+    build: impl FnOnce(&mut Ui),
+) {
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            StateScoped(state),
+        ))
+        .with_children(|builder| {
+            builder
+                .spawn(Node {
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    ..default()
+                })
+                .with_children(|builder| build(&mut Ui { builder, font }));
+        });
+}
+
+/// Spawns widgets as children of the node it was made for.
+pub struct Ui<'a, 'w> {
+    builder: &'a mut ChildSpawnerCommands<'w>,
+    font: &'a GameFont,
+}
+
+impl<'a, 'w> Ui<'a, 'w> {
+    pub fn new(builder: &'a mut ChildSpawnerCommands<'w>, font: &'a GameFont) -> Self {
+        Self { builder, font }
+    }
+
+    pub fn text_style(&self) -> (TextFont, ThemedText, TextLayout) {
+        text_style(self.font)
+    }
+
+    /// Spawns anything that doesn't have its own widget.
+    pub fn spawn(&mut self, bundle: impl Bundle) -> EntityCommands<'_> {
+        self.builder.spawn(bundle)
+    }
+
+    pub fn label(&mut self, key: &'static str) -> EntityCommands<'_> {
+        let style = self.text_style();
+        self.builder
+            .spawn((style, Localized(key), Pickable::IGNORE))
+    }
+
+    /// An empty label that is filled in later, like the results of a query.
+    ///
+    /// Changes to it are announced to screen readers.
+    pub fn result_panel(&mut self, marker: impl Bundle) -> EntityCommands<'_> {
+        let style = self.text_style();
+        self.builder
+            .spawn((style, Text::new(""), Pickable::IGNORE, LiveRegion, marker))
+    }
+
+    pub fn image(&mut self, image: Handle<Image>) -> EntityCommands<'_> {
+        let style = self.text_style();
+        self.builder
+            .spawn((style, ImageNode { image, ..default() }, Pickable::IGNORE))
+    }
+
+    pub fn button<M>(
+        &mut self,
+        key: &'static str,
+        on_click: impl IntoObserverSystem<Pointer<Click>, (), M>,
+    ) -> EntityCommands<'_> {
+        self.sized_button(BUTTON_WIDTH, Localized(key), on_click)
+    }
+
+    /// A button just big enough for a symbol, like the arrows of a pager,
+    /// which isn't translated.
+    pub fn small_button<M>(
+        &mut self,
+        text: impl Into<String>,
+        on_click: impl IntoObserverSystem<Pointer<Click>, (), M>,
+    ) -> EntityCommands<'_> {
+        self.sized_button(SMALL_BUTTON_WIDTH, Text::new(text), on_click)
+    }
+
+    /// A button whose text is filled in later, through `text_marker` on its text.
+    pub fn dynamic_button<M>(
+        &mut self,
+        text_marker: impl Bundle,
+        on_click: impl IntoObserverSystem<Pointer<Click>, (), M>,
+    ) -> EntityCommands<'_> {
+        self.sized_button(BUTTON_WIDTH, (Text::new(""), text_marker), on_click)
+    }
+
+    fn sized_button<M>(
+        &mut self,
+        width: f32,
+        text: impl Bundle,
+        on_click: impl IntoObserverSystem<Pointer<Click>, (), M>,
+    ) -> EntityCommands<'_> {
+        let style = self.text_style();
+        let mut button = self.builder.spawn((
+            Button,
+            Node {
+                width: Val::Px(width),
+                height: Val::Px(BUTTON_HEIGHT),
+                margin: UiRect::all(Val::Px(15.0)),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            ThemedBackground::Button,
+            children![(style, text, Pickable::IGNORE)],
+        ));
+        button.observe(on_click);
+        button
+    }
+
+    /// A button that changes to another screen.
+    pub fn state_button(&mut self, key: &'static str, state: MenuState) -> EntityCommands<'_> {
+        self.button(key, change_state_on_click(PointerButton::Primary, state))
+    }
+
+    /// The button that goes back to the main menu, also pressed by Escape.
+    pub fn back_button(&mut self) -> EntityCommands<'_> {
+        let mut button = self.state_button("common.back", MenuState::Main);
+        button.insert(BackButton);
+        button
+    }
+
+    /// A text input with a heading above it.
+    pub fn text_input(&mut self, key: &'static str, marker: impl Bundle) -> EntityCommands<'_> {
+        self.long_text_input(key, Some(INPUT_MAX_CHARS), marker)
+    }
+
+    /// A text input without its heading, to lay out next to other widgets.
+    /// Screen readers still read `key` as its name.
+    pub fn bare_text_input(
+        &mut self,
+        key: &'static str,
+        marker: impl Bundle,
+    ) -> EntityCommands<'_> {
+        self.input(key, Some(INPUT_MAX_CHARS), marker)
+    }
+
+    /// A text input for things longer than names, like paths.
+    pub fn long_text_input(
+        &mut self,
+        key: &'static str,
+        max_chars: Option<usize>,
+        marker: impl Bundle,
+    ) -> EntityCommands<'_> {
+        self.label(key);
+        self.input(key, max_chars, marker)
+    }
+
+    fn input(
+        &mut self,
+        key: &'static str,
+        max_chars: Option<usize>,
+        marker: impl Bundle,
+    ) -> EntityCommands<'_> {
+        let style = self.text_style();
+        self.builder.spawn((
+            Node {
+                width: Val::Px(INPUT_WIDTH),
+                height: Val::Px(INPUT_HEIGHT),
+                ..default()
+            },
+            TextInputContents::default(),
+            ThemedBackground::TextInput,
+            TextInputNode {
+                clear_on_submit: false,
+                mode: TextInputMode::SingleLine,
+                focus_on_pointer_down: true,
+                unfocus_on_submit: true,
+                max_chars,
+                ..default()
+            },
+            InputLabel(key),
+            style,
+            marker,
+        ))
+    }
+
+    /// A column that list items are spawned into later.
+    pub fn list(&mut self, marker: impl Bundle) -> EntityCommands<'_> {
+        self.builder.spawn((
+            Node {
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(5.0),
+                ..default()
+            },
+            marker,
+        ))
+    }
+
+    /// A grid of `columns` columns that items are spawned into later.
+    pub fn grid(
+        &mut self,
+        columns: usize,
+        column_width: f32,
+        marker: impl Bundle,
+    ) -> EntityCommands<'_> {
+        self.builder.spawn((
+            Node {
+                display: Display::Grid,
+                grid_template_columns: RepeatedGridTrack::px(columns, column_width),
+                column_gap: Val::Px(10.0),
+                row_gap: Val::Px(10.0),
+                margin: UiRect::all(Val::Px(10.0)),
+                ..default()
+            },
+            marker,
+        ))
+    }
+
+    /// Previous and next page buttons around a label for the page number.
+    pub fn pager<M1, M2>(
+        &mut self,
+        label_marker: impl Bundle,
+        on_previous: impl IntoObserverSystem<Pointer<Click>, (), M1>,
+        on_next: impl IntoObserverSystem<Pointer<Click>, (), M2>,
+    ) {
+        self.row(|ui| {
+            ui.small_button("<", on_previous);
+            ui.result_panel(label_marker);
+            ui.small_button(">", on_next);
+        });
+    }
+
+    pub fn row(&mut self, build: impl FnOnce(&mut Ui)) -> EntityCommands<'_> {
+        self.container(FlexDirection::Row, build)
+    }
+
+    pub fn column(&mut self, build: impl FnOnce(&mut Ui)) -> EntityCommands<'_> {
+        self.container(FlexDirection::Column, build)
+    }
+
+    fn container(
+        &mut self,
+        flex_direction: FlexDirection,
+        build: impl FnOnce(&mut Ui),
+    ) -> EntityCommands<'_> {
+        let font = self.font;
+        let mut container = self.builder.spawn(Node {
+            flex_direction,
+            align_items: AlignItems::Center,
+            ..default()
+        });
+        container.with_children(|builder| build(&mut Ui { builder, font }));
+        container
+    }
+}
+
+/// The style of all text on a screen.
+pub fn text_style(font: &GameFont) -> (TextFont, ThemedText, TextLayout) {
+    sized_text_style(font, font.size)
+}
+
+/// The style of text in dense places, like lists.
+pub fn small_text_style(font: &GameFont) -> (TextFont, ThemedText, TextLayout) {
+    sized_text_style(font, font.size * SMALL_FONT_SCALE)
+}
+
+fn sized_text_style(font: &GameFont, font_size: f32) -> (TextFont, ThemedText, TextLayout) {
+    (
+        TextFont {
+            font: font.font.clone(),
+            font_size,
+            ..default()
+        },
+        ThemedText,
+        TextLayout::new_with_justify(JustifyText::Center),
+    )
+}
@@ -0,0 +1,764 @@
+//! Planning the cheapest way to breed a target Pokemon out of the ones in
+//! the boxes, counted in eggs hatched on average.
+//!
+//! Breeding follows the rules from Generation 6 on: a Destiny Knot passes
+//! down five IVs from the parents, a Power item makes sure its stat is one
+//! of them, an Everstone always passes down its holder's nature, and egg
+//! moves come from either parent. Egg moves the target's parents don't know
+//! are chained in through other species sharing an egg group. The data has
+//! no gender ratios, so every egg is taken to be male or female evenly.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+
+use crate::breeding::{DITTO_ID, Gender, MAX_IV, Nature, Stat};
+use crate::sqlite::{
+    Database, MAX_MOVES, OwnedPokemon, Project, get_egg_moves, get_groups, get_pokemon_compatible,
+};
+
+/// How many generations deep a plan can go.
+const MAX_GENERATIONS: usize = 4;
+/// How many of each species and gender are kept to breed from, best first.
+const KEPT_PER_KIND: usize = 8;
+const NATURE_CHANCE: f64 = 1.0 / 25.0;
+/// The chance that an IV which isn't passed down comes out perfect.
+const RANDOM_PERFECT_CHANCE: f64 = 1.0 / (MAX_IV as f64 + 1.0);
+const GENDER_CHANCE: f64 = 0.5;
+
+/// An item held by a parent to control what the egg gets.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Item {
+    DestinyKnot,
+    Everstone,
+    /// Passes down the holder's IV in this stat.
+    Power(Stat),
+}
+
+impl Item {
+    /// Every item, the Power items in stat order.
+    pub fn all() -> impl Iterator<Item = Item> {
+        [Item::DestinyKnot, Item::Everstone]
+            .into_iter()
+            .chain(Stat::iter().map(Item::Power))
+    }
+
+    /// Finds an item by its name, in any case.
+    pub fn from_name(name: &str) -> Option<Item> {
+        Item::all().find(|item| item.name().eq_ignore_ascii_case(name))
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Item::DestinyKnot => "Destiny Knot",
+            Item::Everstone => "Everstone",
+            Item::Power(Stat::Hp) => "Power Weight",
+            Item::Power(Stat::Attack) => "Power Bracer",
+            Item::Power(Stat::Defense) => "Power Belt",
+            Item::Power(Stat::SpecialAttack) => "Power Lens",
+            Item::Power(Stat::SpecialDefense) => "Power Band",
+            Item::Power(Stat::Speed) => "Power Anklet",
+        }
+    }
+}
+
+/// What a plan is breeding towards.
+#[derive(Clone, Debug)]
+pub struct Target {
+    pub pokemon_id: i64,
+    /// The name of its species, in English.
+    pub species: String,
+    pub nature: Option<Nature>,
+    /// The stats that should be perfect.
+    pub perfect: Vec<Stat>,
+    /// The names of the egg moves it should know, in English, at most
+    /// [`MAX_MOVES`] since that's all a Pokemon can know.
+    pub egg_moves: Vec<String>,
+}
+
+impl From<&Project> for Target {
+    /// Only the stats the project wants at [`MAX_IV`] are planned for, the
+    /// rest are left to luck, and so are its ability and ball. Egg moves
+    /// past the first [`MAX_MOVES`] are left out.
+    fn from(project: &Project) -> Self {
+        Self {
+            pokemon_id: project.pokemon_id,
+            species: project.species.clone(),
+            nature: project.nature,
+            perfect: Stat::iter()
+                .filter(|stat| project.ivs.get(*stat) == MAX_IV)
+                .collect(),
+            egg_moves: project.egg_moves.iter().take(MAX_MOVES).cloned().collect(),
+        }
+    }
+}
+
+/// A Pokemon in a plan, either from the boxes or bred along the way.
+#[derive(Debug)]
+pub struct PlanNode {
+    pub species: String,
+    /// `None` for the target, which can be either.
+    pub gender: Option<Gender>,
+    /// The perfect stats it has that matter to the target.
+    pub perfect: Vec<Stat>,
+    /// The target's nature, if it has it.
+    pub nature: Option<Nature>,
+    /// The target's egg moves it knows.
+    pub moves: Vec<String>,
+    pub source: Source,
+}
+
+#[derive(Debug)]
+pub enum Source {
+    Owned(OwnedPokemon),
+    Bred(PlanStep),
+}
+
+/// Breeding one Pokemon of a plan from two others.
+#[derive(Debug)]
+pub struct PlanStep {
+    /// The parent whose species hatches.
+    pub mother: Arc<PlanNode>,
+    pub father: Arc<PlanNode>,
+    pub mother_item: Option<Item>,
+    pub father_item: Option<Item>,
+    /// How many eggs it takes on average to hatch the Pokemon wanted.
+    pub eggs: f64,
+}
+
+#[derive(Debug)]
+pub struct Plan {
+    pub root: Arc<PlanNode>,
+    /// How many eggs the whole plan takes on average.
+    pub eggs: f64,
+}
+
+impl Plan {
+    /// How many pairings the plan has.
+    pub fn steps(&self) -> usize {
+        fn count(node: &PlanNode) -> usize {
+            match &node.source {
+                Source::Owned(_) => 0,
+                Source::Bred(step) => 1 + count(&step.mother) + count(&step.father),
+            }
+        }
+        count(&self.root)
+    }
+}
+
+fn bit(stat: Stat) -> u8 {
+    1 << stat as u8
+}
+
+/// The chance that an egg has a perfect IV in every stat of `wanted`, with
+/// one parent holding a Destiny Knot and maybe the other a Power item for
+/// one of `holder`'s stats.
+fn perfect_chance(mother: u8, father: u8, power: Option<(Stat, u8)>, wanted: u8) -> f64 {
+    // Five stats are passed down, so one isn't, and it's never the Power
+    // item's stat.
+    let skipped = Stat::iter()
+        .filter(|stat| power.is_none_or(|(forced, _)| forced != *stat))
+        .collect::<Vec<_>>();
+
+    let total = skipped
+        .iter()
+        .map(|skipped| {
+            Stat::iter()
+                .filter(|stat| wanted & bit(*stat) != 0)
+                .map(|stat| match power {
+                    _ if stat == *skipped => RANDOM_PERFECT_CHANCE,
+                    Some((forced, holder)) if forced == stat => {
+                        (holder & bit(stat) != 0) as u8 as f64
+                    }
+                    _ => {
+                        ((mother & bit(stat) != 0) as u8 + (father & bit(stat) != 0) as u8) as f64
+                            / 2.0
+                    }
+                })
+                .product::<f64>()
+        })
+        .sum::<f64>();
+
+    total / skipped.len() as f64
+}
+
+/// A Pokemon that can be bred from, with what it has of the target as bits.
+#[derive(Clone)]
+struct Candidate {
+    pokemon_id: i64,
+    /// `None` only for the target itself.
+    gender: Option<Gender>,
+    /// Bits by [`Stat`].
+    perfect: u8,
+    nature: bool,
+    /// Bits by index into [`Target::egg_moves`].
+    moves: u8,
+    /// How many eggs it took to get, counting its parents.
+    eggs: f64,
+    node: Arc<PlanNode>,
+}
+
+impl Candidate {
+    /// Whether it has everything `other` has.
+    fn covers(&self, other: &Candidate) -> bool {
+        self.perfect & other.perfect == other.perfect
+            && (self.nature || !other.nature)
+            && self.moves & other.moves == other.moves
+    }
+
+    fn goals(&self) -> u32 {
+        self.perfect.count_ones() + self.nature as u32 + self.moves.count_ones()
+    }
+}
+
+/// What the planner knows about a species it may breed.
+struct Species {
+    name: String,
+    groups: Vec<String>,
+    /// The target's egg moves this species can pass down, as bits.
+    egg_moves: u8,
+}
+
+struct Planner<'a> {
+    target: &'a Target,
+    perfect: u8,
+    all_moves: u8,
+    /// The target and everything that shares an egg group with it.
+    species: HashMap<i64, Species>,
+}
+
+impl<'a> Planner<'a> {
+    fn new(db: &Database, target: &'a Target) -> Self {
+        let species = get_pokemon_compatible(db, &target.species, "en")
+            .into_iter()
+            .map(|entry| {
+                let egg_moves = get_egg_moves(db, &entry.name, "en");
+                let species = Species {
+                    groups: get_groups(db, &entry.name),
+                    egg_moves: Self::move_bits(target, &egg_moves),
+                    name: entry.name,
+                };
+                (entry.pokemon_id, species)
+            })
+            .collect();
+
+        Self {
+            target,
+            perfect: target
+                .perfect
+                .iter()
+                .fold(0, |bits, stat| bits | bit(*stat)),
+            all_moves: ((1u16 << target.egg_moves.len()) - 1) as u8,
+            species,
+        }
+    }
+
+    fn move_bits(target: &Target, moves: &[String]) -> u8 {
+        target
+            .egg_moves
+            .iter()
+            .enumerate()
+            .filter(|(_, name)| moves.contains(name))
+            .fold(0, |bits, (i, _)| bits | 1 << i)
+    }
+
+    fn node(
+        &self,
+        species: &str,
+        gender: Option<Gender>,
+        perfect: u8,
+        nature: bool,
+        moves: u8,
+        source: Source,
+    ) -> Arc<PlanNode> {
+        Arc::new(PlanNode {
+            species: species.into(),
+            gender,
+            perfect: Stat::iter()
+                .filter(|stat| perfect & bit(*stat) != 0)
+                .collect(),
+            nature: self.target.nature.filter(|_| nature),
+            moves: self
+                .target
+                .egg_moves
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| moves & 1 << *i != 0)
+                .map(|(_, name)| name.clone())
+                .collect(),
+            source,
+        })
+    }
+
+    /// A Pokemon from the boxes, if it's of any use.
+    fn leaf(&self, owned: &OwnedPokemon) -> Option<Candidate> {
+        if owned.pokemon_id != DITTO_ID && !self.species.contains_key(&owned.pokemon_id) {
+            return None;
+        }
+
+        let perfect = Stat::iter()
+            .filter(|stat| owned.ivs.get(*stat) == MAX_IV)
+            .fold(0, |bits, stat| bits | bit(stat))
+            & self.perfect;
+        let nature = self.target.nature == Some(owned.nature);
+        let moves = Self::move_bits(self.target, &owned.moves);
+
+        Some(Candidate {
+            pokemon_id: owned.pokemon_id,
+            gender: Some(owned.gender),
+            perfect,
+            nature,
+            moves,
+            eggs: 0.0,
+            node: self.node(
+                &owned.species,
+                Some(owned.gender),
+                perfect,
+                nature,
+                moves,
+                Source::Owned(owned.clone()),
+            ),
+        })
+    }
+
+    /// Whether the target itself is already in the boxes.
+    fn is_target(&self, candidate: &Candidate) -> bool {
+        candidate.pokemon_id == self.target.pokemon_id
+            && candidate.perfect == self.perfect
+            && (candidate.nature || self.target.nature.is_none())
+            && candidate.moves == self.all_moves
+    }
+
+    /// Whether `mother` and `father` can breed, with `mother`'s species hatching.
+    fn can_breed(&self, mother: &Candidate, father: &Candidate) -> bool {
+        if mother.pokemon_id == DITTO_ID || !self.species.contains_key(&mother.pokemon_id) {
+            return false;
+        }
+        if father.pokemon_id == DITTO_ID {
+            return true;
+        }
+
+        let groups = |candidate: &Candidate| &self.species[&candidate.pokemon_id].groups;
+        mother.gender == Some(Gender::Female)
+            && father.gender == Some(Gender::Male)
+            && groups(mother)
+                .iter()
+                .any(|group| groups(father).contains(group))
+    }
+
+    /// The chance an egg has the `perfect` stats, and the nature if
+    /// `nature`, with the best items for the parents to hold.
+    fn best_items(
+        &self,
+        mother: &Candidate,
+        father: &Candidate,
+        perfect: u8,
+        nature: bool,
+    ) -> (f64, [Option<Item>; 2]) {
+        let parents = [mother, father];
+        let mut best = (0.0, [None, None]);
+
+        for knot in 0..2 {
+            let other = 1 - knot;
+
+            let mut items = vec![None];
+            if nature && parents[other].nature {
+                items.push(Some(Item::Everstone));
+            }
+            items.extend(
+                Stat::iter()
+                    .filter(|stat| perfect & parents[other].perfect & bit(*stat) != 0)
+                    .map(|stat| Some(Item::Power(stat))),
+            );
+
+            for item in items {
+                let power = match item {
+                    Some(Item::Power(stat)) => Some((stat, parents[other].perfect)),
+                    _ => None,
+                };
+                let mut chance = perfect_chance(mother.perfect, father.perfect, power, perfect);
+                if nature && item != Some(Item::Everstone) {
+                    chance *= NATURE_CHANCE;
+                }
+
+                if chance > best.0 {
+                    let mut held = [None, None];
+                    held[knot] = Some(Item::DestinyKnot);
+                    held[other] = item;
+                    best = (chance, held);
+                }
+            }
+        }
+
+        best
+    }
+
+    /// Breeds for an egg with the `perfect` stats and maybe the nature, of
+    /// `gender`, or of either for the target.
+    fn breed(
+        &self,
+        mother: &Candidate,
+        father: &Candidate,
+        perfect: u8,
+        nature: bool,
+        gender: Option<Gender>,
+    ) -> Option<Candidate> {
+        let species = &self.species[&mother.pokemon_id];
+        let moves = (mother.moves | father.moves) & species.egg_moves;
+
+        let (mut chance, [mother_item, father_item]) =
+            self.best_items(mother, father, perfect, nature);
+        if gender.is_some() {
+            chance *= GENDER_CHANCE;
+        }
+        if chance <= 0.0 {
+            return None;
+        }
+
+        let step = PlanStep {
+            mother: mother.node.clone(),
+            father: father.node.clone(),
+            mother_item,
+            father_item,
+            eggs: 1.0 / chance,
+        };
+        Some(Candidate {
+            pokemon_id: mother.pokemon_id,
+            gender,
+            perfect,
+            nature,
+            moves,
+            eggs: mother.eggs + father.eggs + step.eggs,
+            node: self.node(
+                &species.name,
+                gender,
+                perfect,
+                nature,
+                moves,
+                Source::Bred(step),
+            ),
+        })
+    }
+
+    /// What is worth breeding for out of two parents: everything they have
+    /// between them, or all but one of their perfect stats, with and without
+    /// the nature.
+    fn aims(&self, mother: &Candidate, father: &Candidate) -> Vec<(u8, bool)> {
+        let perfect = mother.perfect | father.perfect;
+        let mut masks = vec![perfect];
+        masks.extend(
+            Stat::iter()
+                .filter(|stat| perfect & bit(*stat) != 0)
+                .map(|stat| perfect & !bit(stat)),
+        );
+
+        let natures: &[bool] = if mother.nature || father.nature {
+            &[true, false]
+        } else {
+            &[false]
+        };
+        masks
+            .into_iter()
+            .flat_map(|mask| natures.iter().map(move |nature| (mask, *nature)))
+            .collect()
+    }
+}
+
+/// Adds `candidate` to the ones of its species and gender unless one of
+/// them has as much for as few eggs, returning whether it was added.
+fn keep(pool: &mut HashMap<(i64, Option<Gender>), Vec<Candidate>>, candidate: Candidate) -> bool {
+    let kind = pool
+        .entry((candidate.pokemon_id, candidate.gender))
+        .or_default();
+
+    if kind
+        .iter()
+        .any(|kept| kept.covers(&candidate) && kept.eggs <= candidate.eggs)
+    {
+        return false;
+    }
+
+    kind.retain(|kept| !(candidate.covers(kept) && candidate.eggs <= kept.eggs));
+    kind.push(candidate);
+    kind.sort_by(|a, b| b.goals().cmp(&a.goals()).then(a.eggs.total_cmp(&b.eggs)));
+    kind.truncate(KEPT_PER_KIND);
+    true
+}
+
+/// The cheapest plan for breeding `target` out of `owned`, or `None` if
+/// it can't be bred from them, like when nothing knows an egg move.
+pub fn plan_breeding(db: &Database, target: &Target, owned: &[OwnedPokemon]) -> Option<Plan> {
+    let planner = Planner::new(db, target);
+    let mut pool = HashMap::new();
+    let mut best: Option<Candidate> = None;
+
+    let mut consider = |candidate: Candidate| {
+        if best.as_ref().is_none_or(|best| candidate.eggs < best.eggs) {
+            best = Some(candidate);
+        }
+    };
+
+    for leaf in owned.iter().filter_map(|owned| planner.leaf(owned)) {
+        if planner.is_target(&leaf) {
+            consider(leaf.clone());
+        }
+        keep(&mut pool, leaf);
+    }
+
+    for _ in 0..MAX_GENERATIONS {
+        let parents = pool.values().flatten().cloned().collect::<Vec<_>>();
+        let mut changed = false;
+
+        for mother in &parents {
+            for father in &parents {
+                if !planner.can_breed(mother, father) {
+                    continue;
+                }
+
+                if mother.pokemon_id == target.pokemon_id {
+                    let done = planner.breed(
+                        mother,
+                        father,
+                        planner.perfect,
+                        target.nature.is_some(),
+                        None,
+                    );
+                    if let Some(done) = done.filter(|done| done.moves == planner.all_moves) {
+                        consider(done);
+                    }
+                }
+
+                for (perfect, nature) in planner.aims(mother, father) {
+                    for gender in [Gender::Female, Gender::Male] {
+                        if let Some(child) =
+                            planner.breed(mother, father, perfect, nature, Some(gender))
+                        {
+                            changed |= keep(&mut pool, child);
+                        }
+                    }
+                }
+            }
+        }
+
+        if !changed {
+            break;
+        }
+    }
+
+    best.map(|best| Plan {
+        root: best.node,
+        eggs: best.eggs,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::breeding::Ivs;
+    use crate::sqlite::ProjectStatus;
+
+    const HP: u8 = 1 << Stat::Hp as u8;
+    const ATTACK: u8 = 1 << Stat::Attack as u8;
+
+    fn owned(species: &str, pokemon_id: i64, gender: Gender, perfect: &[Stat]) -> OwnedPokemon {
+        let mut ivs = Ivs::default();
+        for stat in perfect {
+            ivs.set(*stat, MAX_IV);
+        }
+
+        OwnedPokemon {
+            owned_id: None,
+            pokemon_id,
+            species: species.into(),
+            nickname: None,
+            gender,
+            nature: Nature::Hardy,
+            ability: None,
+            ball: "Poke Ball".into(),
+            ivs,
+            moves: Vec::new(),
+            box_number: 1,
+            slot: 0,
+        }
+    }
+
+    fn bulbasaur(perfect: &[Stat], egg_moves: &[&str]) -> Target {
+        Target {
+            pokemon_id: 1,
+            species: "Bulbasaur".into(),
+            nature: None,
+            perfect: perfect.to_vec(),
+            egg_moves: egg_moves.iter().map(|name| name.to_string()).collect(),
+        }
+    }
+
+    fn candidate(perfect: u8, eggs: f64) -> Candidate {
+        let owned = owned("Bulbasaur", 1, Gender::Female, &[]);
+        Candidate {
+            pokemon_id: 1,
+            gender: Some(Gender::Female),
+            perfect,
+            nature: false,
+            moves: 0,
+            eggs,
+            node: Arc::new(PlanNode {
+                species: owned.species.clone(),
+                gender: Some(owned.gender),
+                perfect: Vec::new(),
+                nature: None,
+                moves: Vec::new(),
+                source: Source::Owned(owned),
+            }),
+        }
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-9, "{actual} != {expected}");
+    }
+
+    #[test]
+    fn destiny_knot_chance() {
+        // HP is passed down from the perfect mother unless it's the stat
+        // left out, a sixth of the time, when it's perfect by luck.
+        let chance = perfect_chance(0b11_1111, 0, None, HP);
+        assert_close(chance, (5.0 * 0.5 + 1.0 / 32.0) / 6.0);
+
+        // With HP from the mother and Attack from the father, both have to
+        // come from the right parent, or one be left out and come by luck.
+        let chance = perfect_chance(HP, ATTACK, None, HP | ATTACK);
+        assert_close(chance, (4.0 * 0.25 + 2.0 * 0.5 / 32.0) / 6.0);
+    }
+
+    #[test]
+    fn power_item_chance() {
+        // A Power Bracer on the father always passes down Attack, and it's
+        // never the stat left out.
+        let chance = perfect_chance(HP, ATTACK, Some((Stat::Attack, ATTACK)), HP | ATTACK);
+        assert_close(chance, (4.0 * 0.5 + 1.0 / 32.0) / 5.0);
+
+        // Nor can it pass down a perfect IV its holder doesn't have.
+        assert_eq!(
+            perfect_chance(HP, ATTACK, Some((Stat::Attack, 0)), ATTACK),
+            0.0
+        );
+    }
+
+    #[test]
+    fn keep_drops_covered_candidates() {
+        let mut pool = HashMap::new();
+
+        assert!(keep(&mut pool, candidate(HP | ATTACK, 2.0)));
+        // Less for more eggs isn't worth keeping.
+        assert!(!keep(&mut pool, candidate(HP, 3.0)));
+        // Less for fewer eggs is.
+        assert!(keep(&mut pool, candidate(HP, 1.0)));
+        assert_eq!(pool[&(1, Some(Gender::Female))].len(), 2);
+
+        // More for fewer eggs replaces both.
+        assert!(keep(&mut pool, candidate(HP | ATTACK, 0.5)));
+        let kept = &pool[&(1, Some(Gender::Female))];
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].perfect, HP | ATTACK);
+        assert_eq!(kept[0].eggs, 0.5);
+    }
+
+    #[test]
+    fn target_in_the_boxes() {
+        let db = Database::open(None).unwrap();
+        let target = bulbasaur(&[Stat::Hp], &[]);
+        let owned = [owned("Bulbasaur", 1, Gender::Male, &[Stat::Hp])];
+
+        let plan = plan_breeding(&db, &target, &owned).unwrap();
+        assert_eq!(plan.eggs, 0.0);
+        assert_eq!(plan.steps(), 0);
+    }
+
+    #[test]
+    fn one_step() {
+        let db = Database::open(None).unwrap();
+        let target = bulbasaur(&[Stat::Hp, Stat::Attack], &[]);
+        let owned = [
+            owned("Bulbasaur", 1, Gender::Female, &[Stat::Hp]),
+            owned("Bulbasaur", 1, Gender::Male, &[Stat::Attack]),
+        ];
+
+        let plan = plan_breeding(&db, &target, &owned).unwrap();
+        assert_eq!(plan.steps(), 1);
+        assert_close(plan.eggs, 5.0 / (4.0 * 0.5 + 1.0 / 32.0));
+
+        let Source::Bred(step) = &plan.root.source else {
+            panic!("the target isn't bred");
+        };
+        assert_eq!(step.mother.gender, Some(Gender::Female));
+        assert_eq!(step.mother_item, Some(Item::DestinyKnot));
+        assert_eq!(step.father_item, Some(Item::Power(Stat::Attack)));
+    }
+
+    #[test]
+    fn egg_move_chained_in() {
+        let db = Database::open(None).unwrap();
+        db.connection
+            .execute(
+                "INSERT INTO pokemon_move (pokemon_id, move_id, method)
+                    SELECT 4, move_id, 'egg' FROM move WHERE name = 'Amnesia'",
+                (),
+            )
+            .unwrap();
+        let target = bulbasaur(&[], &["Amnesia"]);
+
+        // Only a mother knows it, so it has to go through a father of her
+        // species first.
+        let mut charmander = owned("Charmander", 4, Gender::Female, &[]);
+        charmander.moves = vec!["Amnesia".into()];
+        let owned = [
+            owned("Bulbasaur", 1, Gender::Female, &[]),
+            charmander,
+            owned("Charmander", 4, Gender::Male, &[]),
+        ];
+
+        let plan = plan_breeding(&db, &target, &owned).unwrap();
+        assert_eq!(plan.steps(), 2);
+        // A male Charmander takes two eggs, then any Bulbasaur one.
+        assert_close(plan.eggs, 3.0);
+        assert_eq!(plan.root.moves, ["Amnesia"]);
+
+        let Source::Bred(step) = &plan.root.source else {
+            panic!("the target isn't bred");
+        };
+        assert_eq!(step.father.species, "Charmander");
+        assert_eq!(step.father.moves, ["Amnesia"]);
+    }
+
+    #[test]
+    fn impossible_target() {
+        let db = Database::open(None).unwrap();
+        let target = bulbasaur(&[], &["Amnesia"]);
+        let owned = [
+            owned("Bulbasaur", 1, Gender::Female, &[Stat::Hp]),
+            owned("Bulbasaur", 1, Gender::Male, &[Stat::Attack]),
+        ];
+
+        assert!(plan_breeding(&db, &target, &owned).is_none());
+    }
+
+    #[test]
+    fn target_keeps_at_most_four_egg_moves() {
+        let project = Project {
+            project_id: None,
+            name: "Moves".into(),
+            pokemon_id: 1,
+            species: "Bulbasaur".into(),
+            nature: None,
+            ability: None,
+            ball: None,
+            ivs: Ivs::default(),
+            egg_moves: (0..20).map(|i| format!("Move {i}")).collect(),
+            status: ProjectStatus::Planning,
+        };
+
+        assert_eq!(Target::from(&project).egg_moves.len(), MAX_MOVES);
+    }
+}
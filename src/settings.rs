@@ -0,0 +1,143 @@
+//! User preferences, kept in a RON file between runs.
+//!
+
+use std::path::{Path, PathBuf};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::locale::Language;
+use crate::sqlite::{Database, MemoryFallback};
+
+/// Environment variable to read the settings from somewhere else.
+const SETTINGS_PATH_VAR: &str = "TCSS445_SETTINGS";
+const DEFAULT_SETTINGS_PATH: &str = "settings.ron";
+
+pub const MIN_UI_SCALE: f32 = 0.5;
+pub const MAX_UI_SCALE: f32 = 2.0;
+pub const MIN_FONT_SIZE: f32 = 18.0;
+pub const MAX_FONT_SIZE: f32 = 60.0;
+pub const MAX_GENERATION: u8 = 9;
+
+#[derive(Resource, Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(default)]
+pub struct Settings {
+    pub ui_scale: f32,
+    /// The size of normal text, everything else is sized relative to it.
+    pub font_size: f32,
+    /// The name of the color theme.
+    pub theme: String,
+    pub language: Language,
+    /// The generation whose breeding mechanics are assumed by default.
+    pub default_generation: u8,
+    /// Where the database is kept, or `None` to keep it in memory.
+    pub database_path: Option<PathBuf>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            ui_scale: 1.0,
+            font_size: 33.0,
+            theme: "Dark".into(),
+            language: Language::English,
+            default_generation: MAX_GENERATION,
+            database_path: None,
+        }
+    }
+}
+
+impl Settings {
+    /// Where the settings are saved.
+    pub fn path() -> PathBuf {
+        std::env::var_os(SETTINGS_PATH_VAR)
+            .map(PathBuf::from)
+            .unwrap_or_else(|| DEFAULT_SETTINGS_PATH.into())
+    }
+
+    /// Reads the settings, falling back to the defaults if there are none
+    /// or they can't be read.
+    pub fn load(path: &Path) -> Self {
+        match std::fs::read_to_string(path) {
+            Ok(contents) => ron::from_str(&contents)
+                .inspect_err(|e| warn!("Failed to parse settings {}: {e}", path.display()))
+                .unwrap_or_default(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Self::default(),
+            Err(e) => {
+                warn!("Failed to read settings {}: {e}", path.display());
+                Self::default()
+            }
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), SettingsError> {
+        let contents = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())?;
+        std::fs::write(path, contents)?;
+        Ok(())
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum SettingsError {
+    #[error("failed to serialize settings: {0}")]
+    Serialize(#[from] ron::Error),
+    #[error("failed to write settings: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// The systems that apply changed settings, for anything showing their effects.
+#[derive(SystemSet, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct ApplySettings;
+
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        let settings = Settings::load(&Settings::path());
+
+        app.insert_resource(UiScale(settings.ui_scale))
+            .insert_resource(settings)
+            .add_systems(
+                Update,
+                (apply_settings, save_settings, reopen_database)
+                    .in_set(ApplySettings)
+                    .run_if(resource_changed::<Settings>.and(not(resource_added::<Settings>))),
+            );
+    }
+}
+
+fn apply_settings(settings: Res<Settings>, mut ui_scale: ResMut<UiScale>) {
+    ui_scale.0 = settings.ui_scale;
+}
+
+fn save_settings(settings: Res<Settings>) {
+    let path = Settings::path();
+    if let Err(e) = settings.save(&path) {
+        error!("Failed to save settings to {}: {e}", path.display());
+    }
+}
+
+/// Switches to the database in the settings, if it isn't the one open already.
+fn reopen_database(world: &mut World) {
+    let path = world.resource::<Settings>().database_path.clone();
+    // Not open yet, which the loading screen takes care of.
+    let Some(db) = world.get_non_send_resource::<Database>() else {
+        return;
+    };
+    if db.path == path {
+        return;
+    }
+    // Keep to the fallback until another database is picked.
+    if world
+        .get_resource::<MemoryFallback>()
+        .is_some_and(|MemoryFallback(failed)| *failed == path)
+    {
+        return;
+    }
+    world.remove_resource::<MemoryFallback>();
+
+    match Database::open(path.as_deref()) {
+        Ok(db) => world.insert_non_send_resource(db),
+        Err(e) => error!("Failed to open database at {path:?} with: {e}"),
+    }
+}
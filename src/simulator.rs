@@ -0,0 +1,494 @@
+//! Hatching eggs at random from two Pokemon in the boxes, to see how the
+//! odds play out over many eggs.
+//!
+//! The same seed always hatches the same eggs, so runs from the command line
+//! can be compared between versions. Breeding follows the rules of the
+//! chosen generation, from Generation 3 when IVs and natures came in:
+//!
+//! - Three IVs are passed down, or five with a Destiny Knot from Generation 6.
+//!   Generation 3 draws them the way Emerald does, so a stat can be drawn
+//!   twice, and the second draw is never HP nor the third HP or Defense.
+//! - A Power item passes down its stat from Generation 4.
+//! - An Everstone passes down its holder's nature half the time, and always
+//!   from Generation 6. Before Generation 5 only the mother or a Ditto can.
+//! - The mother passes down her ability 80% of the time from Generation 5,
+//!   and so does a father bred with a Ditto from Generation 6. Otherwise the
+//!   ability is left to chance.
+//! - Egg moves come from the father, and from either parent from Generation 6.
+//!
+//! The data has no hidden abilities, and doesn't know which game a Pokemon
+//! came from, so the Masuda method is a setting of its own.
+
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+
+use crate::breeding::{DITTO_ID, Gender, Ivs, MAX_IV, Nature, Stat, breed};
+use crate::locale::Locale;
+use crate::planner::Item;
+use crate::settings::MAX_GENERATION;
+use crate::sqlite::{Database, MAX_MOVES, OwnedPokemon, get_egg_moves};
+
+/// The first generation with IVs and natures.
+pub const MIN_GENERATION: u8 = 3;
+pub const DEFAULT_EGGS: u32 = 1000;
+const ABILITY_CHANCE: f64 = 0.8;
+
+/// How to hatch the eggs of a simulation.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(default)]
+pub struct SimConfig {
+    /// The generation whose breeding rules are followed.
+    pub generation: u8,
+    pub eggs: u32,
+    pub seed: u64,
+    pub mother_item: Option<Item>,
+    pub other_item: Option<Item>,
+    /// Whether the parents are from games in different languages.
+    pub masuda: bool,
+    pub shiny_charm: bool,
+}
+
+impl Default for SimConfig {
+    fn default() -> Self {
+        Self {
+            generation: MAX_GENERATION,
+            eggs: DEFAULT_EGGS,
+            seed: 0,
+            mother_item: None,
+            other_item: None,
+            masuda: false,
+            shiny_charm: false,
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum SimError {
+    #[error("generation {0} has no IVs or natures to breed, they start in {MIN_GENERATION}")]
+    Generation(u8),
+    #[error("{0} and {1} can't make an egg")]
+    NoEgg(String, String),
+}
+
+/// How many eggs got each IV in one stat, from 0 to [`MAX_IV`].
+#[derive(Serialize, Clone, Debug)]
+pub struct IvCounts {
+    pub stat: Stat,
+    pub counts: Vec<u32>,
+}
+
+/// What hatched from a simulation, counted in eggs.
+#[derive(Serialize, Clone, Debug)]
+pub struct Simulation {
+    /// The species that hatches, in English.
+    pub species: String,
+    pub config: SimConfig,
+    /// By how many perfect IVs an egg has, from none to six.
+    pub perfect: Vec<u32>,
+    pub ivs: Vec<IvCounts>,
+    /// The natures that came up, the most common first.
+    pub natures: Vec<(Nature, u32)>,
+    /// The abilities passed down, the most common first, with `None` for
+    /// the ones left to chance.
+    pub abilities: Vec<(Option<String>, u32)>,
+    pub shiny: u32,
+    /// The egg moves passed down, in English.
+    pub egg_moves: Vec<(String, u32)>,
+}
+
+/// A histogram ready to show, with a label for each bar.
+#[derive(Clone, Debug)]
+pub struct Histogram {
+    pub title: String,
+    pub bars: Vec<(String, u32)>,
+}
+
+impl Simulation {
+    /// Everything that was counted, as histograms labelled in the locale's
+    /// language.
+    pub fn histograms(&self, locale: &Locale) -> Vec<Histogram> {
+        let histogram = |key, bars| Histogram {
+            title: locale.get(key).into(),
+            bars,
+        };
+
+        vec![
+            histogram(
+                "sim.chart.perfect",
+                self.perfect
+                    .iter()
+                    .enumerate()
+                    .map(|(perfect, count)| (perfect.to_string(), *count))
+                    .collect(),
+            ),
+            histogram(
+                "sim.chart.stats",
+                self.ivs
+                    .iter()
+                    .map(|ivs| {
+                        (
+                            locale.get(ivs.stat.key()).into(),
+                            ivs.counts[MAX_IV as usize],
+                        )
+                    })
+                    .collect(),
+            ),
+            histogram(
+                "sim.chart.natures",
+                self.natures
+                    .iter()
+                    .map(|(nature, count)| (<&str>::from(*nature).into(), *count))
+                    .collect(),
+            ),
+            histogram(
+                "sim.chart.abilities",
+                self.abilities
+                    .iter()
+                    .map(|(ability, count)| {
+                        let name = ability
+                            .clone()
+                            .unwrap_or_else(|| locale.get("sim.random_ability").into());
+                        (name, *count)
+                    })
+                    .collect(),
+            ),
+            histogram(
+                "sim.chart.shiny",
+                vec![
+                    (locale.get("sim.shiny").into(), self.shiny),
+                    (
+                        locale.get("sim.not_shiny").into(),
+                        self.config.eggs - self.shiny,
+                    ),
+                ],
+            ),
+            histogram("sim.chart.egg_moves", self.egg_moves.clone()),
+        ]
+    }
+}
+
+/// A small random number generator (SplitMix64), written out so the same
+/// seed hatches the same eggs on every platform and in every version.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A number from 0 up to, but not including, `n`.
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    /// Whether something with a chance of `chance`, from 0 to 1, happens.
+    fn chance(&mut self, chance: f64) -> bool {
+        ((self.next() >> 11) as f64 / (1u64 << 53) as f64) < chance
+    }
+}
+
+/// The chance that an egg is shiny.
+fn shiny_chance(config: &SimConfig) -> f64 {
+    let generation = config.generation;
+    let odds = if generation >= 6 { 4096.0 } else { 8192.0 };
+    let masuda = match generation {
+        _ if !config.masuda => 0,
+        3 => 0,
+        4 => 4,
+        _ => 5,
+    };
+    let charm = if config.shiny_charm && generation >= 5 {
+        2
+    } else {
+        0
+    };
+
+    1.0 - (1.0 - 1.0 / odds).powi(1 + masuda + charm)
+}
+
+/// Rolls an egg's IVs, passing `inherited` of them down from the parents,
+/// the stat of a Power item among them.
+fn egg_ivs(
+    rng: &mut Rng,
+    generation: u8,
+    inherited: usize,
+    power: &[(usize, Stat)],
+    parents: &[&OwnedPokemon; 2],
+) -> Ivs {
+    let mut ivs = Ivs::default();
+    for stat in Stat::iter() {
+        ivs.set(stat, rng.below(MAX_IV as usize + 1) as u8);
+    }
+
+    let mut left = Stat::iter().collect::<Vec<_>>();
+    if generation == 3 {
+        // Emerald meant to leave out the stats already drawn, but leaves out
+        // the first ones instead.
+        for skipped in 0..inherited {
+            let stat = left[skipped + rng.below(left.len() - skipped)];
+            ivs.set(stat, parents[rng.below(2)].ivs.get(stat));
+        }
+        return ivs;
+    }
+
+    let mut inherited = inherited;
+    if !power.is_empty() {
+        let (holder, stat) = power[rng.below(power.len())];
+        left.retain(|s| *s != stat);
+        ivs.set(stat, parents[holder].ivs.get(stat));
+        inherited -= 1;
+    }
+    for _ in 0..inherited {
+        let stat = left.swap_remove(rng.below(left.len()));
+        ivs.set(stat, parents[rng.below(2)].ivs.get(stat));
+    }
+
+    ivs
+}
+
+/// Adds one to the count of `value`, starting it if it is new.
+fn count<T: PartialEq>(counts: &mut Vec<(T, u32)>, value: T) {
+    match counts.iter_mut().find(|(v, _)| *v == value) {
+        Some((_, count)) => *count += 1,
+        None => counts.push((value, 1)),
+    }
+}
+
+/// Hatches `config.eggs` eggs from `mother` and `other`, following the rules
+/// of `config.generation`.
+pub fn simulate(
+    db: &Database,
+    mother: &OwnedPokemon,
+    other: &OwnedPokemon,
+    config: &SimConfig,
+) -> Result<Simulation, SimError> {
+    let generation = config.generation;
+    if !(MIN_GENERATION..=MAX_GENERATION).contains(&generation) {
+        return Err(SimError::Generation(generation));
+    }
+
+    let parents = [mother, other];
+    let is_ditto = |parent: &OwnedPokemon| parent.pokemon_id == DITTO_ID;
+    let dittos = parents.iter().filter(|parent| is_ditto(parent)).count();
+    let pair = dittos == 1 || (mother.gender == Gender::Female && other.gender == Gender::Male);
+    let offspring = breed(db, &mother.species, &other.species, "en")
+        .offspring
+        .filter(|_| pair)
+        .ok_or_else(|| SimError::NoEgg(mother.name().into(), other.name().into()))?;
+    // The same parent as `breed` hatches the species of.
+    let species_parent = parents[is_ditto(mother) as usize];
+
+    let items = [config.mother_item, config.other_item];
+    let inherited = if generation >= 6 && items.contains(&Some(Item::DestinyKnot)) {
+        5
+    } else {
+        3
+    };
+    let power = items
+        .iter()
+        .enumerate()
+        .filter_map(|(holder, item)| match item {
+            Some(Item::Power(stat)) if generation >= 4 => Some((holder, *stat)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let everstone = (0..parents.len())
+        .filter(|holder| {
+            let parent = parents[*holder];
+            items[*holder] == Some(Item::Everstone)
+                && (generation >= 5 || parent.gender == Gender::Female || is_ditto(parent))
+        })
+        .collect::<Vec<_>>();
+    let everstone_chance = if generation >= 6 { 1.0 } else { 0.5 };
+    let passes_ability = (generation >= 5 && species_parent.gender == Gender::Female)
+        || (generation >= 6 && dittos == 1);
+    let shiny_chance = shiny_chance(config);
+
+    // Egg moves don't depend on chance, so every egg knows the same ones.
+    let egg_moves = get_egg_moves(db, &offspring.species, "en")
+        .into_iter()
+        .filter(|egg_move| {
+            parents
+                .iter()
+                .filter(|parent| generation >= 6 || parent.gender != Gender::Female)
+                .any(|parent| {
+                    parent
+                        .moves
+                        .iter()
+                        .any(|m| m.eq_ignore_ascii_case(egg_move))
+                })
+        })
+        .take(MAX_MOVES)
+        .map(|egg_move| (egg_move, config.eggs))
+        .collect();
+
+    let all_natures = Nature::iter().collect::<Vec<_>>();
+    let mut rng = Rng(config.seed);
+    let mut perfect = vec![0; Stat::iter().count() + 1];
+    let mut ivs = Stat::iter()
+        .map(|stat| IvCounts {
+            stat,
+            counts: vec![0; MAX_IV as usize + 1],
+        })
+        .collect::<Vec<_>>();
+    let mut natures = all_natures
+        .iter()
+        .map(|nature| (*nature, 0))
+        .collect::<Vec<_>>();
+    let mut abilities = Vec::new();
+    let mut shiny = 0;
+
+    for _ in 0..config.eggs {
+        let egg = egg_ivs(&mut rng, generation, inherited, &power, &parents);
+        for counts in &mut ivs {
+            counts.counts[egg.get(counts.stat) as usize] += 1;
+        }
+        perfect[egg.perfect()] += 1;
+
+        let nature = if !everstone.is_empty() && rng.chance(everstone_chance) {
+            parents[everstone[rng.below(everstone.len())]].nature
+        } else {
+            all_natures[rng.below(all_natures.len())]
+        };
+        natures[nature as usize].1 += 1;
+
+        let ability = (passes_ability && rng.chance(ABILITY_CHANCE))
+            .then(|| species_parent.ability.clone())
+            .flatten();
+        count(&mut abilities, ability);
+
+        if rng.chance(shiny_chance) {
+            shiny += 1;
+        }
+    }
+
+    natures.retain(|(_, count)| *count > 0);
+    natures.sort_by(|a, b| b.1.cmp(&a.1));
+    abilities.sort_by(|a, b| b.1.cmp(&a.1));
+
+    Ok(Simulation {
+        species: offspring.species,
+        config: config.clone(),
+        perfect,
+        ivs,
+        natures,
+        abilities,
+        shiny,
+        egg_moves,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bulbasaur(gender: Gender, nature: Nature, ability: &str, iv: u8) -> OwnedPokemon {
+        OwnedPokemon {
+            owned_id: None,
+            pokemon_id: 1,
+            species: "Bulbasaur".into(),
+            nickname: None,
+            gender,
+            nature,
+            ability: Some(ability.into()),
+            ball: "Poke Ball".into(),
+            ivs: Ivs([iv; 6]),
+            moves: vec!["Amnesia".into()],
+            box_number: 1,
+            slot: 0,
+        }
+    }
+
+    /// A perfect Modest mother and a father with no IVs at all.
+    fn run(config: SimConfig) -> Simulation {
+        let db = Database::open(None).unwrap();
+        let mother = bulbasaur(Gender::Female, Nature::Modest, "Overgrow", MAX_IV);
+        let father = bulbasaur(Gender::Male, Nature::Adamant, "Chlorophyll", 0);
+        simulate(&db, &mother, &father, &config).unwrap()
+    }
+
+    #[test]
+    fn generation_3() {
+        let simulation = run(SimConfig {
+            generation: 3,
+            eggs: 100,
+            seed: 1,
+            ..SimConfig::default()
+        });
+
+        // A stat can be drawn twice, so never more than three are perfect.
+        assert_eq!(simulation.perfect, [20, 39, 33, 8, 0, 0, 0]);
+        assert_eq!(simulation.ivs[0].counts[0], 10);
+        assert_eq!(simulation.ivs[0].counts[MAX_IV as usize], 9);
+        assert_eq!(
+            simulation.natures[..2],
+            [(Nature::Naive, 8), (Nature::Quiet, 8)]
+        );
+        assert_eq!(simulation.abilities, [(None, 100)]);
+        assert_eq!(simulation.shiny, 0);
+        assert_eq!(simulation.egg_moves, [("Amnesia".to_string(), 100)]);
+    }
+
+    #[test]
+    fn destiny_knot() {
+        let simulation = run(SimConfig {
+            generation: 9,
+            eggs: 100,
+            seed: 2,
+            mother_item: Some(Item::DestinyKnot),
+            ..SimConfig::default()
+        });
+
+        assert_eq!(simulation.perfect, [4, 18, 27, 28, 16, 7, 0]);
+        assert_eq!(simulation.ivs[0].counts[0], 44);
+        assert_eq!(simulation.ivs[0].counts[MAX_IV as usize], 38);
+        assert_eq!(
+            simulation.natures[..2],
+            [(Nature::Naive, 7), (Nature::Calm, 7)]
+        );
+        assert_eq!(
+            simulation.abilities,
+            [(Some("Overgrow".to_string()), 78), (None, 22)]
+        );
+        assert_eq!(simulation.shiny, 0);
+    }
+
+    #[test]
+    fn everstone() {
+        let simulation = run(SimConfig {
+            generation: 5,
+            eggs: 100,
+            seed: 3,
+            mother_item: Some(Item::Everstone),
+            ..SimConfig::default()
+        });
+
+        assert_eq!(simulation.perfect, [12, 31, 43, 14, 0, 0, 0]);
+        assert_eq!(
+            simulation.natures[..2],
+            [(Nature::Modest, 52), (Nature::Lonely, 4)]
+        );
+        assert_eq!(
+            simulation.abilities,
+            [(Some("Overgrow".to_string()), 83), (None, 17)]
+        );
+        assert_eq!(simulation.shiny, 0);
+    }
+
+    #[test]
+    fn same_seed_same_eggs() {
+        let config = SimConfig {
+            eggs: 100,
+            seed: 7,
+            ..SimConfig::default()
+        };
+        let (a, b) = (run(config.clone()), run(config));
+        assert_eq!(a.perfect, b.perfect);
+        assert_eq!(a.natures, b.natures);
+        assert_eq!(a.abilities, b.abilities);
+    }
+}
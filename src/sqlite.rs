@@ -1,11 +1,30 @@
 //! The SQLite Database backend!
 //!
 
+mod audit;
+mod favorites;
+mod history;
+mod owned;
+mod projects;
+mod queries;
+mod rows;
+
+pub use favorites::*;
+pub use history::*;
+pub use owned::*;
+pub use projects::*;
+pub use queries::*;
+pub use rows::*;
+
+use std::path::{Path, PathBuf};
+
 use bevy::prelude::*;
+use bevy::tasks::{AsyncComputeTaskPool, Task, block_on, futures_lite::future};
 use rusqlite::Connection;
 
+use crate::settings::Settings;
+
 const ADD_SCHEMA: &str = "
-    BEGIN TRANSACTION;
     PRAGMA foreign_keys = ON;
 
     -- Schema
@@ -129,33 +148,412 @@ const ADD_SCHEMA: &str = "
       SELECT 7, move_id, 'egg' FROM move WHERE name = 'Haze';
 
     -- Caterpie has no egg moves (it can’t breed in later gens either)
-    COMMIT;
     ";
 
+/// Names of Pokemon and moves in languages other than English, which is
+/// kept in the `name` columns.
+const ADD_NAMES: &str = "
+    CREATE TABLE pokemon_name(
+      pokemon_id INTEGER,
+      language   TEXT NOT NULL,
+      name       TEXT NOT NULL COLLATE NOCASE,
+      PRIMARY KEY(pokemon_id, language),
+      FOREIGN KEY(pokemon_id) REFERENCES pokemon(pokemon_id) ON DELETE CASCADE ON UPDATE CASCADE
+    ) STRICT;
+
+    CREATE TABLE move_name(
+      move_id  INTEGER,
+      language TEXT NOT NULL,
+      name     TEXT NOT NULL COLLATE NOCASE,
+      PRIMARY KEY(move_id, language),
+      FOREIGN KEY(move_id) REFERENCES move(move_id) ON DELETE CASCADE ON UPDATE CASCADE
+    ) STRICT;
+
+    INSERT INTO pokemon_name (pokemon_id, language, name) VALUES
+      (1, 'fr', 'Bulbizarre'),   (1, 'de', 'Bisasam'),
+      (2, 'fr', 'Herbizarre'),   (2, 'de', 'Bisaknosp'),
+      (3, 'fr', 'Florizarre'),   (3, 'de', 'Bisaflor'),
+      (4, 'fr', 'Salamèche'),    (4, 'de', 'Glumanda'),
+      (5, 'fr', 'Reptincel'),    (5, 'de', 'Glutexo'),
+      (6, 'fr', 'Dracaufeu'),    (6, 'de', 'Glurak'),
+      (7, 'fr', 'Carapuce'),     (7, 'de', 'Schiggy'),
+      (8, 'fr', 'Carabaffe'),    (8, 'de', 'Schillok'),
+      (9, 'fr', 'Tortank'),      (9, 'de', 'Turtok'),
+      (10, 'fr', 'Chenipan'),    (10, 'de', 'Raupy'),
+      (132, 'fr', 'Métamorph'),  (132, 'de', 'Ditto'),
+      (150, 'fr', 'Mewtwo'),     (150, 'de', 'Mewtu');
+
+    INSERT INTO move_name (move_id, language, name)
+      SELECT move_id, 'fr', CASE name
+          WHEN 'Amnesia' THEN 'Amnésie'
+          WHEN 'Skull Bash' THEN 'Coud''Krâne'
+          WHEN 'Dragon Dance' THEN 'Danse Draco'
+          WHEN 'Fake Out' THEN 'Bluff'
+          WHEN 'Haze' THEN 'Buée Noire'
+          WHEN 'Mirror Coat' THEN 'Voile Miroir'
+        END
+        FROM move WHERE name IN
+          ('Amnesia', 'Skull Bash', 'Dragon Dance', 'Fake Out', 'Haze', 'Mirror Coat');
+    INSERT INTO move_name (move_id, language, name)
+      SELECT move_id, 'de', CASE name
+          WHEN 'Amnesia' THEN 'Amnesie'
+          WHEN 'Skull Bash' THEN 'Schädelwumme'
+          WHEN 'Dragon Dance' THEN 'Drachentanz'
+          WHEN 'Fake Out' THEN 'Mogelhieb'
+          WHEN 'Haze' THEN 'Dunkelnebel'
+          WHEN 'Mirror Coat' THEN 'Spiegelcape'
+        END
+        FROM move WHERE name IN
+          ('Amnesia', 'Skull Bash', 'Dragon Dance', 'Fake Out', 'Haze', 'Mirror Coat');
+    ";
+
+/// The history of changes to the data, see [`Database::record`].
+const ADD_AUDIT: &str = "
+    CREATE TABLE audit_change(
+      change_id   INTEGER PRIMARY KEY AUTOINCREMENT,
+      description TEXT NOT NULL,
+      undone      INTEGER NOT NULL DEFAULT 0 CHECK(undone IN (0, 1)),
+      made_at     TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+    ) STRICT;
+
+    -- A row as JSON before and after the change, NULL if it didn't exist.
+    CREATE TABLE audit_row(
+      row_id     INTEGER PRIMARY KEY AUTOINCREMENT,
+      change_id  INTEGER NOT NULL,
+      table_name TEXT NOT NULL,
+      old_row    TEXT CHECK(old_row IS NULL OR json_valid(old_row)),
+      new_row    TEXT CHECK(new_row IS NULL OR json_valid(new_row)),
+      FOREIGN KEY(change_id) REFERENCES audit_change(change_id) ON DELETE CASCADE
+    ) STRICT;
+
+    CREATE INDEX audit_row_change ON audit_row(change_id);
+    ";
+
+/// The Pokemon we own, see [`OwnedPokemon`].
+const ADD_BOXES: &str = "
+    CREATE TABLE owned_pokemon(
+      owned_id           INTEGER PRIMARY KEY AUTOINCREMENT,
+      pokemon_id         INTEGER NOT NULL,
+      nickname           TEXT DEFAULT NULL,
+      gender             TEXT NOT NULL CHECK(gender IN ('Male', 'Female', 'Genderless')),
+      nature             TEXT NOT NULL CHECK(nature IN (
+          'Hardy', 'Lonely', 'Brave', 'Adamant', 'Naughty',
+          'Bold', 'Docile', 'Relaxed', 'Impish', 'Lax',
+          'Timid', 'Hasty', 'Serious', 'Jolly', 'Naive',
+          'Modest', 'Mild', 'Quiet', 'Bashful', 'Rash',
+          'Calm', 'Gentle', 'Sassy', 'Careful', 'Quirky'
+      )),
+      ability            TEXT DEFAULT NULL,
+      ball               TEXT NOT NULL DEFAULT 'Poke Ball',
+      iv_hp              INTEGER NOT NULL DEFAULT 0 CHECK(iv_hp BETWEEN 0 AND 31),
+      iv_attack          INTEGER NOT NULL DEFAULT 0 CHECK(iv_attack BETWEEN 0 AND 31),
+      iv_defense         INTEGER NOT NULL DEFAULT 0 CHECK(iv_defense BETWEEN 0 AND 31),
+      iv_special_attack  INTEGER NOT NULL DEFAULT 0 CHECK(iv_special_attack BETWEEN 0 AND 31),
+      iv_special_defense INTEGER NOT NULL DEFAULT 0 CHECK(iv_special_defense BETWEEN 0 AND 31),
+      iv_speed           INTEGER NOT NULL DEFAULT 0 CHECK(iv_speed BETWEEN 0 AND 31),
+      -- Boxes and slots as the Boxes screen has them, see BOX_COUNT and BOX_SIZE.
+      box                INTEGER NOT NULL CHECK(box BETWEEN 1 AND 16),
+      slot               INTEGER NOT NULL CHECK(slot >= 0 AND slot < 30),
+      UNIQUE(box, slot),
+      -- Deleting a species someone owns is refused rather than releasing them.
+      FOREIGN KEY(pokemon_id) REFERENCES pokemon(pokemon_id) ON DELETE RESTRICT ON UPDATE CASCADE
+    ) STRICT;
+
+    CREATE TABLE owned_pokemon_move(
+      owned_id INTEGER,
+      slot     INTEGER CHECK(slot >= 0 AND slot < 4),
+      move_id  INTEGER NOT NULL,
+      PRIMARY KEY(owned_id, slot),
+      FOREIGN KEY(owned_id) REFERENCES owned_pokemon(owned_id) ON DELETE CASCADE ON UPDATE CASCADE,
+      FOREIGN KEY(move_id)  REFERENCES move(move_id) ON DELETE CASCADE ON UPDATE CASCADE
+    ) STRICT;
+    ";
+
+/// Breeding projects and the eggs hatched for them, see [`Project`].
+const ADD_PROJECTS: &str = "
+    -- The IVs are the least each stat should have, 0 for any.
+    CREATE TABLE project(
+      project_id         INTEGER PRIMARY KEY AUTOINCREMENT,
+      name               TEXT NOT NULL,
+      pokemon_id         INTEGER NOT NULL,
+      nature             TEXT DEFAULT NULL CHECK(nature IS NULL OR nature IN (
+          'Hardy', 'Lonely', 'Brave', 'Adamant', 'Naughty',
+          'Bold', 'Docile', 'Relaxed', 'Impish', 'Lax',
+          'Timid', 'Hasty', 'Serious', 'Jolly', 'Naive',
+          'Modest', 'Mild', 'Quiet', 'Bashful', 'Rash',
+          'Calm', 'Gentle', 'Sassy', 'Careful', 'Quirky'
+      )),
+      ability            TEXT DEFAULT NULL,
+      ball               TEXT DEFAULT NULL,
+      iv_hp              INTEGER NOT NULL DEFAULT 0 CHECK(iv_hp BETWEEN 0 AND 31),
+      iv_attack          INTEGER NOT NULL DEFAULT 0 CHECK(iv_attack BETWEEN 0 AND 31),
+      iv_defense         INTEGER NOT NULL DEFAULT 0 CHECK(iv_defense BETWEEN 0 AND 31),
+      iv_special_attack  INTEGER NOT NULL DEFAULT 0 CHECK(iv_special_attack BETWEEN 0 AND 31),
+      iv_special_defense INTEGER NOT NULL DEFAULT 0 CHECK(iv_special_defense BETWEEN 0 AND 31),
+      iv_speed           INTEGER NOT NULL DEFAULT 0 CHECK(iv_speed BETWEEN 0 AND 31),
+      status             TEXT NOT NULL DEFAULT 'Planning'
+                           CHECK(status IN ('Planning', 'Breeding', 'Done', 'Abandoned')),
+      created_at         TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+      -- Like owned Pokemon, a species with projects can't be deleted.
+      FOREIGN KEY(pokemon_id) REFERENCES pokemon(pokemon_id) ON DELETE RESTRICT ON UPDATE CASCADE
+    ) STRICT;
+
+    CREATE TABLE project_target_move(
+      project_id INTEGER,
+      move_id    INTEGER,
+      PRIMARY KEY(project_id, move_id),
+      FOREIGN KEY(project_id) REFERENCES project(project_id) ON DELETE CASCADE ON UPDATE CASCADE,
+      FOREIGN KEY(move_id)    REFERENCES move(move_id) ON DELETE CASCADE ON UPDATE CASCADE
+    ) STRICT;
+
+    CREATE TABLE project_parent(
+      project_id INTEGER,
+      owned_id   INTEGER,
+      PRIMARY KEY(project_id, owned_id),
+      FOREIGN KEY(project_id) REFERENCES project(project_id) ON DELETE CASCADE ON UPDATE CASCADE,
+      FOREIGN KEY(owned_id)   REFERENCES owned_pokemon(owned_id) ON DELETE CASCADE ON UPDATE CASCADE
+    ) STRICT;
+
+    -- Eggs are copied rather than linked, so releasing one keeps its record.
+    CREATE TABLE project_egg(
+      egg_id             INTEGER PRIMARY KEY AUTOINCREMENT,
+      project_id         INTEGER NOT NULL,
+      gender             TEXT NOT NULL CHECK(gender IN ('Male', 'Female', 'Genderless')),
+      nature             TEXT NOT NULL CHECK(nature IN (
+          'Hardy', 'Lonely', 'Brave', 'Adamant', 'Naughty',
+          'Bold', 'Docile', 'Relaxed', 'Impish', 'Lax',
+          'Timid', 'Hasty', 'Serious', 'Jolly', 'Naive',
+          'Modest', 'Mild', 'Quiet', 'Bashful', 'Rash',
+          'Calm', 'Gentle', 'Sassy', 'Careful', 'Quirky'
+      )),
+      ability            TEXT DEFAULT NULL,
+      ball               TEXT NOT NULL,
+      iv_hp              INTEGER NOT NULL DEFAULT 0 CHECK(iv_hp BETWEEN 0 AND 31),
+      iv_attack          INTEGER NOT NULL DEFAULT 0 CHECK(iv_attack BETWEEN 0 AND 31),
+      iv_defense         INTEGER NOT NULL DEFAULT 0 CHECK(iv_defense BETWEEN 0 AND 31),
+      iv_special_attack  INTEGER NOT NULL DEFAULT 0 CHECK(iv_special_attack BETWEEN 0 AND 31),
+      iv_special_defense INTEGER NOT NULL DEFAULT 0 CHECK(iv_special_defense BETWEEN 0 AND 31),
+      iv_speed           INTEGER NOT NULL DEFAULT 0 CHECK(iv_speed BETWEEN 0 AND 31),
+      hatched_at         TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+      FOREIGN KEY(project_id) REFERENCES project(project_id) ON DELETE CASCADE ON UPDATE CASCADE
+    ) STRICT;
+
+    CREATE TABLE project_egg_move(
+      egg_id  INTEGER,
+      move_id INTEGER,
+      PRIMARY KEY(egg_id, move_id),
+      FOREIGN KEY(egg_id)  REFERENCES project_egg(egg_id) ON DELETE CASCADE ON UPDATE CASCADE,
+      FOREIGN KEY(move_id) REFERENCES move(move_id) ON DELETE CASCADE ON UPDATE CASCADE
+    ) STRICT;
+
+    CREATE INDEX project_egg_project ON project_egg(project_id);
+    ";
+
+// Not audited, since nobody edits it: undoing a search would be odd.
+const ADD_HISTORY: &str = "
+    -- Searches have only `pokemon`, and breeding has `other` as well. What
+    -- came of each is kept as JSON.
+    CREATE TABLE history(
+      history_id INTEGER PRIMARY KEY AUTOINCREMENT,
+      kind       TEXT NOT NULL CHECK(kind IN ('Search', 'Breed')),
+      pokemon    TEXT NOT NULL,
+      other      TEXT DEFAULT NULL CHECK((kind = 'Breed') = (other IS NOT NULL)),
+      result     TEXT NOT NULL CHECK(json_valid(result)),
+      created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+    ) STRICT;
+    ";
+
+const ADD_FAVORITES: &str = "
+    CREATE TABLE favorite_pokemon(
+      pokemon_id INTEGER PRIMARY KEY,
+      created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+      FOREIGN KEY(pokemon_id) REFERENCES pokemon(pokemon_id) ON DELETE CASCADE ON UPDATE CASCADE
+    ) STRICT;
+
+    CREATE TABLE pokemon_tag(
+      pokemon_id INTEGER,
+      tag        TEXT NOT NULL CHECK(tag = trim(tag) AND length(tag) BETWEEN 1 AND 32),
+      created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+      PRIMARY KEY(pokemon_id, tag),
+      FOREIGN KEY(pokemon_id) REFERENCES pokemon(pokemon_id) ON DELETE CASCADE ON UPDATE CASCADE
+    ) STRICT;
+
+    CREATE INDEX pokemon_tag_by_tag ON pokemon_tag(tag);
+    ";
+
+/// Base stats and abilities, for comparing species. Hidden abilities are
+/// left out, like everywhere else.
+const ADD_STATS: &str = "
+    CREATE TABLE pokemon_base_stats(
+      pokemon_id      INTEGER PRIMARY KEY,
+      hp              INTEGER NOT NULL CHECK(hp BETWEEN 1 AND 255),
+      attack          INTEGER NOT NULL CHECK(attack BETWEEN 1 AND 255),
+      defense         INTEGER NOT NULL CHECK(defense BETWEEN 1 AND 255),
+      special_attack  INTEGER NOT NULL CHECK(special_attack BETWEEN 1 AND 255),
+      special_defense INTEGER NOT NULL CHECK(special_defense BETWEEN 1 AND 255),
+      speed           INTEGER NOT NULL CHECK(speed BETWEEN 1 AND 255),
+      FOREIGN KEY(pokemon_id) REFERENCES pokemon(pokemon_id) ON DELETE CASCADE ON UPDATE CASCADE
+    ) STRICT;
+
+    CREATE TABLE pokemon_ability(
+      pokemon_id INTEGER,
+      slot       INTEGER CHECK(slot IN (1, 2)),
+      ability    TEXT NOT NULL,
+      PRIMARY KEY(pokemon_id, slot),
+      FOREIGN KEY(pokemon_id) REFERENCES pokemon(pokemon_id) ON DELETE CASCADE ON UPDATE CASCADE
+    ) STRICT;
+
+    INSERT INTO pokemon_base_stats
+      (pokemon_id, hp, attack, defense, special_attack, special_defense, speed) VALUES
+      (1, 45, 49, 49, 65, 65, 45),
+      (2, 60, 62, 63, 80, 80, 60),
+      (3, 80, 82, 83, 100, 100, 80),
+      (4, 39, 52, 43, 60, 50, 65),
+      (5, 58, 64, 58, 80, 65, 80),
+      (6, 78, 84, 78, 109, 85, 100),
+      (7, 44, 48, 65, 50, 64, 43),
+      (8, 59, 63, 80, 65, 80, 58),
+      (9, 79, 83, 100, 85, 105, 78),
+      (10, 45, 30, 35, 20, 20, 45),
+      (132, 48, 48, 48, 48, 48, 48),
+      (150, 106, 110, 90, 154, 90, 130);
+
+    INSERT INTO pokemon_ability (pokemon_id, slot, ability) VALUES
+      (1, 1, 'Overgrow'),
+      (2, 1, 'Overgrow'),
+      (3, 1, 'Overgrow'),
+      (4, 1, 'Blaze'),
+      (5, 1, 'Blaze'),
+      (6, 1, 'Blaze'),
+      (7, 1, 'Torrent'),
+      (8, 1, 'Torrent'),
+      (9, 1, 'Torrent'),
+      (10, 1, 'Shield Dust'),
+      (132, 1, 'Limber'),
+      (150, 1, 'Pressure');
+    ";
+
+/// Every change to the schema, in order. The database's `user_version`
+/// is how many of these it has had.
+const MIGRATIONS: &[&str] = &[
+    ADD_SCHEMA,
+    ADD_NAMES,
+    ADD_AUDIT,
+    ADD_BOXES,
+    ADD_PROJECTS,
+    ADD_HISTORY,
+    ADD_FAVORITES,
+    ADD_STATS,
+];
+
 pub struct Database {
     pub connection: Connection,
+    /// The file the database is kept in, or `None` if it is in memory.
+    pub path: Option<PathBuf>,
 }
 
 impl Database {
-    pub fn open() -> Result<Self, rusqlite::Error> {
+    /// Opens the database kept at `path`, or one in memory, filling it in
+    /// if it is new.
+    pub fn open(path: Option<&Path>) -> Result<Self, rusqlite::Error> {
+        let connection = match path {
+            Some(path) => Connection::open(path)?,
+            None => Connection::open_in_memory()?,
+        };
         let db = Self {
-            connection: Connection::open_in_memory()?,
+            connection,
+            path: path.map(Path::to_path_buf),
         };
 
-        db.connection.execute_batch(ADD_SCHEMA)?;
+        db.connection.pragma_update(None, "foreign_keys", true)?;
+        db.migrate()?;
+        db.install_audit_triggers()?;
 
         Ok(db)
     }
+
+    /// Brings the schema up to date, running whichever migrations it hasn't had.
+    fn migrate(&self) -> Result<(), rusqlite::Error> {
+        let mut version: usize =
+            self.connection
+                .pragma_query_value(None, "user_version", |row| row.get(0))?;
+
+        // Databases from before migrations were counted only have the schema.
+        if version == 0 {
+            let has_schema = self.connection.query_row(
+                "SELECT EXISTS(SELECT 1 FROM sqlite_schema WHERE type = 'table' AND name = 'pokemon')",
+                (),
+                |row| row.get::<_, bool>(0),
+            )?;
+            if has_schema {
+                version = 1;
+            }
+        }
+
+        // The version is bumped along with the migration, so a failed one
+        // leaves neither behind.
+        for (i, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+            let transaction = self.connection.unchecked_transaction()?;
+            transaction.execute_batch(migration)?;
+            transaction.pragma_update(None, "user_version", i + 1)?;
+            transaction.commit()?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The database being opened in the background, see [`DatabaseTask::spawn`].
+#[derive(Resource)]
+pub struct DatabaseTask(Task<Result<Database, rusqlite::Error>>);
+
+impl DatabaseTask {
+    /// Starts opening the database at `path`, which becomes the [`Database`]
+    /// resource once it is ready, or a [`DatabaseError`] if it fails.
+    pub fn spawn(path: Option<PathBuf>) -> Self {
+        Self(AsyncComputeTaskPool::get().spawn(async move { Database::open(path.as_deref()) }))
+    }
 }
 
+/// Why the database couldn't be opened.
+#[derive(Resource)]
+pub struct DatabaseError(pub String);
+
+/// Set when a database in memory is used for this run instead of the one at
+/// this path in the settings, which couldn't be opened. The settings keep
+/// the path for the next run.
+#[derive(Resource)]
+pub struct MemoryFallback(pub Option<PathBuf>);
+
 pub struct DatabasePlugin;
 
 impl Plugin for DatabasePlugin {
     fn build(&self, app: &mut App) {
-        app.insert_non_send_resource(
-            Database::open()
-                .inspect_err(|e| error!("Failed to open database with: {e}"))
-                .unwrap(),
+        app.add_systems(Startup, start_database).add_systems(
+            Update,
+            finish_database.run_if(resource_exists::<DatabaseTask>),
         );
     }
 }
+
+fn start_database(mut commands: Commands, settings: Res<Settings>) {
+    commands.insert_resource(DatabaseTask::spawn(settings.database_path.clone()));
+}
+
+fn finish_database(mut commands: Commands, mut task: ResMut<DatabaseTask>) {
+    let Some(result) = block_on(future::poll_once(&mut task.0)) else {
+        return;
+    };
+    commands.remove_resource::<DatabaseTask>();
+
+    match result {
+        Ok(db) => {
+            commands.remove_resource::<DatabaseError>();
+            commands.queue(move |world: &mut World| world.insert_non_send_resource(db));
+        }
+        Err(e) => {
+            error!("Failed to open database with: {e}");
+            commands.insert_resource(DatabaseError(e.to_string()));
+        }
+    }
+}
@@ -0,0 +1,421 @@
+//! The history of changes to the data, for undo and redo.
+//!
+//! Writes made through [`Database::record`] are logged row by row into
+//! `audit_row` by triggers, grouped into one `audit_change` per call, so
+//! rows changed by a cascade are logged too. Undoing a change replays the
+//! inverse of each row backwards, and redoing it replays them forwards.
+//!
+//! The triggers are `TEMP`, so they only exist on connections opened by
+//! [`Database::open`], and only log while a change is being recorded.
+
+use rusqlite::OptionalExtension;
+
+use super::Database;
+
+/// Every table whose rows are logged.
+const AUDITED_TABLES: &[&str] = &[
+    "pokemon",
+    "egg_group",
+    "move",
+    "pokemon_egg_group",
+    "pokemon_move",
+    "pokemon_name",
+    "move_name",
+    "owned_pokemon",
+    "owned_pokemon_move",
+    "project",
+    "project_target_move",
+    "project_parent",
+    "project_egg",
+    "project_egg_move",
+    "favorite_pokemon",
+    "pokemon_tag",
+    "pokemon_base_stats",
+    "pokemon_ability",
+];
+
+/// The columns of a table, and which of them are its primary key.
+struct Columns {
+    all: Vec<String>,
+    key: Vec<String>,
+}
+
+impl Columns {
+    fn of(db: &Database, table: &str) -> Result<Self, rusqlite::Error> {
+        let mut query = db
+            .connection
+            .prepare("SELECT name, pk FROM pragma_table_info(?1) ORDER BY cid")?;
+        let columns = query
+            .query_map((table,), |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut key = columns.iter().filter(|(_, pk)| *pk > 0).collect::<Vec<_>>();
+        key.sort_by_key(|(_, pk)| *pk);
+
+        Ok(Self {
+            key: key.into_iter().map(|(name, _)| name.clone()).collect(),
+            all: columns.into_iter().map(|(name, _)| name).collect(),
+        })
+    }
+
+    /// `json_object('column', ROW.column, ...)` over every column.
+    fn json(&self, row: &str) -> String {
+        let pairs = self
+            .all
+            .iter()
+            .map(|column| format!("'{column}', {row}.{column}"))
+            .collect::<Vec<_>>();
+        format!("json_object({})", pairs.join(", "))
+    }
+
+    /// A `SELECT` of `columns` out of the JSON in `side` of the logged row `?1`.
+    fn extract(columns: &[String], side: &str) -> String {
+        let values = columns
+            .iter()
+            .map(|column| format!("json_extract({side}, '$.{column}')"))
+            .collect::<Vec<_>>();
+        format!(
+            "SELECT {} FROM audit_row WHERE row_id = ?1",
+            values.join(", ")
+        )
+    }
+}
+
+/// Which way a change is being replayed.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Direction {
+    Undo,
+    Redo,
+}
+
+impl Database {
+    /// Creates the triggers that log changes, on this connection only.
+    pub(super) fn install_audit_triggers(&self) -> Result<(), rusqlite::Error> {
+        self.connection
+            .execute_batch("CREATE TEMP TABLE IF NOT EXISTS audit_current(change_id INTEGER);")?;
+
+        for table in AUDITED_TABLES {
+            let columns = Columns::of(self, table)?;
+            let (new, old) = (columns.json("NEW"), columns.json("OLD"));
+
+            for (event, before, after) in [
+                ("INSERT", "NULL", new.as_str()),
+                ("UPDATE", old.as_str(), new.as_str()),
+                ("DELETE", old.as_str(), "NULL"),
+            ] {
+                self.connection.execute_batch(&format!(
+                    "
+                    CREATE TEMP TRIGGER IF NOT EXISTS audit_{table}_{event}
+                        AFTER {event} ON main.{table}
+                        WHEN EXISTS (SELECT 1 FROM audit_current)
+                    BEGIN
+                        INSERT INTO audit_row (change_id, table_name, old_row, new_row)
+                            SELECT change_id, '{table}', {before}, {after} FROM audit_current;
+                    END;
+                    "
+                ))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Runs `change` in a transaction, logging everything it writes as one
+    /// change that can be undone. Nothing is kept if it fails.
+    ///
+    /// Recording a change forgets whatever could have been redone.
+    pub fn record<T, E: From<rusqlite::Error>>(
+        &self,
+        description: &str,
+        change: impl FnOnce(&Database) -> Result<T, E>,
+    ) -> Result<T, E> {
+        let transaction = self.connection.unchecked_transaction()?;
+
+        transaction.execute("DELETE FROM audit_change WHERE undone = 1", ())?;
+        transaction.execute(
+            "INSERT INTO audit_change (description) VALUES (?1)",
+            (description,),
+        )?;
+        transaction.execute(
+            "INSERT INTO audit_current (change_id) VALUES (last_insert_rowid())",
+            (),
+        )?;
+
+        let result = change(self)?;
+
+        transaction.execute("DELETE FROM audit_current", ())?;
+        transaction.commit()?;
+
+        Ok(result)
+    }
+
+    /// Undoes the last change, returning its description, or `None` if
+    /// there is nothing to undo.
+    pub fn undo(&self) -> Result<Option<String>, rusqlite::Error> {
+        self.replay(Direction::Undo)
+    }
+
+    /// Redoes the last undone change, returning its description, or `None`
+    /// if there is nothing to redo.
+    pub fn redo(&self) -> Result<Option<String>, rusqlite::Error> {
+        self.replay(Direction::Redo)
+    }
+
+    fn replay(&self, direction: Direction) -> Result<Option<String>, rusqlite::Error> {
+        let transaction = self.connection.unchecked_transaction()?;
+
+        let next = match direction {
+            Direction::Undo => {
+                "SELECT change_id, description FROM audit_change WHERE undone = 0
+                    ORDER BY change_id DESC LIMIT 1"
+            }
+            Direction::Redo => {
+                "SELECT change_id, description FROM audit_change WHERE undone = 1
+                    ORDER BY change_id ASC LIMIT 1"
+            }
+        };
+        let Some((change_id, description)) = transaction
+            .query_row(next, (), |row| {
+                Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+            })
+            .optional()?
+        else {
+            return Ok(None);
+        };
+
+        // Rows are put back one at a time, so a child may come back before
+        // its parent. The foreign keys are checked once it's all done.
+        transaction.pragma_update(None, "defer_foreign_keys", true)?;
+
+        let order = match direction {
+            Direction::Undo => "DESC",
+            Direction::Redo => "ASC",
+        };
+        let rows = transaction
+            .prepare(&format!(
+                "SELECT row_id, table_name, old_row IS NULL, new_row IS NULL FROM audit_row
+                    WHERE change_id = ?1 ORDER BY row_id {order}"
+            ))?
+            .query_map((change_id,), |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, bool>(2)?,
+                    row.get::<_, bool>(3)?,
+                ))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for (row_id, table, no_old, no_new) in rows {
+            // Undoing goes from the row after the change to the row before.
+            let (from, to, from_missing, to_missing) = match direction {
+                Direction::Undo => ("new_row", "old_row", no_new, no_old),
+                Direction::Redo => ("old_row", "new_row", no_old, no_new),
+            };
+            let columns = Columns::of(self, &table)?;
+            let all = columns.all.join(", ");
+            let key = columns.key.join(", ");
+            let key_of_from = Columns::extract(&columns.key, from);
+            // The row is only replayed if it is still exactly as it was logged.
+            let is_from = format!("({all}) IS ({})", Columns::extract(&columns.all, from));
+
+            let query = match (from_missing, to_missing) {
+                (true, _) => format!(
+                    "INSERT INTO {table} ({all}) {}",
+                    Columns::extract(&columns.all, to)
+                ),
+                (false, true) => format!("DELETE FROM {table} WHERE {is_from}"),
+                (false, false) => format!(
+                    "UPDATE {table} SET ({all}) = ({}) WHERE {is_from}",
+                    Columns::extract(&columns.all, to)
+                ),
+            };
+            let changed = transaction.execute(&query, (row_id,))?;
+            if changed == 1 {
+                continue;
+            }
+
+            // Nothing changed when a cascade from an earlier row already put
+            // this one in place. Otherwise the row was changed outside of
+            // `record`, and replaying the rest would leave the log out of
+            // step with the data, so the whole change is refused.
+            let in_place = if to_missing {
+                format!("SELECT NOT EXISTS (SELECT 1 FROM {table} WHERE ({key}) = ({key_of_from}))")
+            } else {
+                format!(
+                    "SELECT EXISTS (SELECT 1 FROM {table} WHERE ({all}) IS ({}))",
+                    Columns::extract(&columns.all, to)
+                )
+            };
+            if !transaction.query_row(&in_place, (row_id,), |row| row.get::<_, bool>(0))? {
+                return Err(rusqlite::Error::StatementChangedRows(changed));
+            }
+        }
+
+        transaction.execute(
+            "UPDATE audit_change SET undone = ?2 WHERE change_id = ?1",
+            (change_id, direction == Direction::Undo),
+        )?;
+        transaction.commit()?;
+
+        Ok(Some(description))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every row of `table`, sorted, each as its values joined up.
+    fn dump(db: &Database, table: &str) -> Vec<String> {
+        let columns = Columns::of(db, table).unwrap().all;
+        let mut query = db
+            .connection
+            .prepare(&format!(
+                "SELECT * FROM {table} ORDER BY {}",
+                columns.join(", ")
+            ))
+            .unwrap();
+        let columns = columns.len();
+        query
+            .query_map((), |row| {
+                let values = (0..columns)
+                    .map(|i| row.get_ref(i).map(|value| format!("{value:?}")))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(values.join("|"))
+            })
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap()
+    }
+
+    fn dump_all(db: &Database) -> Vec<Vec<String>> {
+        ["pokemon", "egg_group", "pokemon_egg_group", "pokemon_move"]
+            .into_iter()
+            .map(|table| dump(db, table))
+            .collect()
+    }
+
+    /// Whether each change is undone, oldest first.
+    fn undone(db: &Database) -> Vec<bool> {
+        let mut query = db
+            .connection
+            .prepare("SELECT undone FROM audit_change ORDER BY change_id")
+            .unwrap();
+        query
+            .query_map((), |row| row.get(0))
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap()
+    }
+
+    fn write(db: &Database, description: &str, sql: &str) {
+        db.record(description, |db| db.connection.execute_batch(sql))
+            .unwrap();
+    }
+
+    #[test]
+    fn undo_and_redo_put_rows_back() {
+        let db = Database::open(None).unwrap();
+        let before = dump_all(&db);
+
+        write(
+            &db,
+            "Dragons",
+            "
+            INSERT INTO egg_group (name) VALUES ('Dragon');
+            UPDATE pokemon SET primary_type = 'Dragon' WHERE pokemon_id = 4;
+            DELETE FROM pokemon_egg_group WHERE pokemon_id = 10;
+            ",
+        );
+        let after = dump_all(&db);
+        assert_ne!(before, after);
+        assert_eq!(undone(&db), [false]);
+
+        assert_eq!(db.undo().unwrap().as_deref(), Some("Dragons"));
+        assert_eq!(dump_all(&db), before);
+        assert_eq!(undone(&db), [true]);
+        assert_eq!(db.undo().unwrap(), None);
+
+        assert_eq!(db.redo().unwrap().as_deref(), Some("Dragons"));
+        assert_eq!(dump_all(&db), after);
+        assert_eq!(undone(&db), [false]);
+        assert_eq!(db.redo().unwrap(), None);
+    }
+
+    #[test]
+    fn cascades_are_put_back() {
+        let db = Database::open(None).unwrap();
+        let before = dump_all(&db);
+
+        // Renumbering Charmander and deleting an egg group both reach into
+        // other tables.
+        write(
+            &db,
+            "Cascades",
+            "
+            UPDATE pokemon SET pokemon_id = 200 WHERE pokemon_id = 4;
+            DELETE FROM egg_group WHERE name = 'Monster';
+            ",
+        );
+        let after = dump_all(&db);
+        assert_ne!(before, after);
+
+        db.undo().unwrap();
+        assert_eq!(dump_all(&db), before);
+
+        db.redo().unwrap();
+        assert_eq!(dump_all(&db), after);
+    }
+
+    #[test]
+    fn recording_forgets_redo() {
+        let db = Database::open(None).unwrap();
+
+        write(
+            &db,
+            "First",
+            "UPDATE pokemon SET primary_type = 'Dragon' WHERE pokemon_id = 4",
+        );
+        db.undo().unwrap();
+        write(
+            &db,
+            "Second",
+            "UPDATE pokemon SET primary_type = 'Ice' WHERE pokemon_id = 7",
+        );
+
+        assert_eq!(undone(&db), [false]);
+        assert_eq!(db.redo().unwrap(), None);
+        assert_eq!(db.undo().unwrap().as_deref(), Some("Second"));
+    }
+
+    #[test]
+    fn changes_made_elsewhere_refuse_replay() {
+        let db = Database::open(None).unwrap();
+
+        write(
+            &db,
+            "Dragons",
+            "
+            INSERT INTO egg_group (name) VALUES ('Dragon');
+            UPDATE pokemon SET primary_type = 'Dragon' WHERE pokemon_id = 4;
+            ",
+        );
+        db.connection
+            .execute(
+                "UPDATE pokemon SET primary_type = 'Ice' WHERE pokemon_id = 4",
+                (),
+            )
+            .unwrap();
+        let edited = dump_all(&db);
+
+        assert!(matches!(
+            db.undo(),
+            Err(rusqlite::Error::StatementChangedRows(0))
+        ));
+        assert_eq!(dump_all(&db), edited);
+        assert_eq!(undone(&db), [false]);
+    }
+}
@@ -0,0 +1,136 @@
+//! Species starred as favorites and the free-form tags attached to them,
+//! like "ladder team" or "needs HA", for narrowing down lists of Pokemon.
+
+use std::collections::HashMap;
+
+use rusqlite::named_params;
+use serde::{Deserialize, Serialize};
+
+use super::Database;
+
+/// How a species is marked, if it is starred or has tags.
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq, Debug)]
+pub struct SpeciesMarks {
+    pub pokemon_id: i64,
+    /// The name of the species, in English.
+    pub species: String,
+    pub favorite: bool,
+    pub tags: Vec<String>,
+}
+
+/// Which species a list shows, by how they are marked.
+#[derive(Clone, Default, PartialEq, Debug)]
+pub struct MarkFilter {
+    pub favorites_only: bool,
+    /// `None` for any tags or none.
+    pub tag: Option<String>,
+}
+
+impl MarkFilter {
+    pub fn allows(&self, marks: Option<&SpeciesMarks>) -> bool {
+        if self.favorites_only && !marks.is_some_and(|marks| marks.favorite) {
+            return false;
+        }
+        match &self.tag {
+            None => true,
+            Some(tag) => marks.is_some_and(|marks| marks.tags.contains(tag)),
+        }
+    }
+}
+
+/// Every species that is starred or has tags, by id.
+pub fn get_all_marks(db: &Database) -> HashMap<i64, SpeciesMarks> {
+    let query = r#"
+        SELECT pokemon.pokemon_id, pokemon.name, favorite_pokemon.pokemon_id IS NOT NULL, pokemon_tag.tag
+            FROM pokemon
+                LEFT JOIN favorite_pokemon ON pokemon.pokemon_id = favorite_pokemon.pokemon_id
+                LEFT JOIN pokemon_tag ON pokemon.pokemon_id = pokemon_tag.pokemon_id
+            WHERE favorite_pokemon.pokemon_id IS NOT NULL OR pokemon_tag.tag IS NOT NULL
+            ORDER BY pokemon.pokemon_id, pokemon_tag.tag COLLATE NOCASE
+    "#;
+    let mut query = db.connection.prepare_cached(query).unwrap();
+
+    let rows = query
+        .query_map((), |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, bool>(2)?,
+                row.get::<_, Option<String>>(3)?,
+            ))
+        })
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    let mut all = HashMap::<i64, SpeciesMarks>::new();
+    for (pokemon_id, species, favorite, tag) in rows {
+        let marks = all.entry(pokemon_id).or_insert_with(|| SpeciesMarks {
+            pokemon_id,
+            species,
+            favorite,
+            tags: Vec::new(),
+        });
+        marks.tags.extend(tag);
+    }
+    all
+}
+
+/// Every tag on any species, in alphabetical order.
+pub fn get_tags(db: &Database) -> Vec<String> {
+    let query = "SELECT DISTINCT tag FROM pokemon_tag ORDER BY tag COLLATE NOCASE";
+    let mut query = db.connection.prepare_cached(query).unwrap();
+
+    query
+        .query_map((), |row| row.get::<_, String>(0))
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap()
+}
+
+pub fn set_favorite(db: &Database, pokemon_id: i64, favorite: bool) -> Result<(), rusqlite::Error> {
+    let description = if favorite {
+        "star pokemon"
+    } else {
+        "unstar pokemon"
+    };
+    db.record(description, |db| {
+        let query = if favorite {
+            "INSERT OR IGNORE INTO favorite_pokemon (pokemon_id) VALUES (?1)"
+        } else {
+            "DELETE FROM favorite_pokemon WHERE pokemon_id = ?1"
+        };
+        db.connection.execute(query, (pokemon_id,)).map(|_| ())
+    })
+}
+
+/// Tags a species, unless it already has the tag.
+pub fn add_tag(db: &Database, pokemon_id: i64, tag: &str) -> Result<(), rusqlite::Error> {
+    let tag = tag.trim();
+    db.record(&format!("tag pokemon {tag}"), |db| {
+        db.connection
+            .execute(
+                "INSERT OR IGNORE INTO pokemon_tag (pokemon_id, tag) VALUES (:pokemon_id, :tag)",
+                named_params! {
+                    ":pokemon_id": pokemon_id,
+                    ":tag": tag,
+                },
+            )
+            .map(|_| ())
+    })
+}
+
+pub fn remove_tag(db: &Database, pokemon_id: i64, tag: &str) -> Result<(), rusqlite::Error> {
+    let tag = tag.trim();
+    db.record(&format!("untag pokemon {tag}"), |db| {
+        db.connection
+            .execute(
+                "DELETE FROM pokemon_tag WHERE pokemon_id = :pokemon_id AND tag = :tag",
+                named_params! {
+                    ":pokemon_id": pokemon_id,
+                    ":tag": tag,
+                },
+            )
+            .map(|_| ())
+    })
+}
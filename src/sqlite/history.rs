@@ -0,0 +1,145 @@
+//! Every search and breeding submitted, with when and what came of it, so
+//! they can be looked back on and run again.
+
+use rusqlite::named_params;
+use serde::{Deserialize, Serialize};
+
+use super::Database;
+
+/// How many submissions are kept, the oldest are forgotten first.
+const HISTORY_LIMIT: i64 = 500;
+
+/// What was submitted.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub enum Submission {
+    Search { pokemon: String },
+    Breed { mother: String, other: String },
+}
+
+/// A submission from the history.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct HistoryEntry {
+    pub history_id: i64,
+    pub submission: Submission,
+    /// What came of it, as JSON.
+    pub result: String,
+    /// When it was submitted, in local time.
+    pub created_at: String,
+}
+
+/// An input whose past values are offered again.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum HistoryField {
+    Search,
+    Mother,
+    Other,
+}
+
+/// Adds a submission to the history, forgetting the oldest past
+/// [`HISTORY_LIMIT`].
+pub fn add_history(
+    db: &Database,
+    submission: &Submission,
+    result: &str,
+) -> Result<i64, rusqlite::Error> {
+    let (kind, pokemon, other) = match submission {
+        Submission::Search { pokemon } => ("Search", pokemon, None),
+        Submission::Breed { mother, other } => ("Breed", mother, Some(other)),
+    };
+
+    db.connection.execute(
+        r#"
+        INSERT INTO history (kind, pokemon, other, result)
+            VALUES (:kind, :pokemon, :other, :result)
+        "#,
+        named_params! {
+            ":kind": kind,
+            ":pokemon": pokemon,
+            ":other": other,
+            ":result": result,
+        },
+    )?;
+    let history_id = db.connection.last_insert_rowid();
+
+    db.connection.execute(
+        "DELETE FROM history WHERE history_id <= ?1",
+        (history_id - HISTORY_LIMIT,),
+    )?;
+    Ok(history_id)
+}
+
+/// The whole history, newest first.
+pub fn get_history(db: &Database) -> Vec<HistoryEntry> {
+    let query = r#"
+        SELECT history_id, kind, pokemon, other, result, datetime(created_at, 'localtime')
+            FROM history
+            ORDER BY history_id DESC
+    "#;
+    let mut query = db.connection.prepare_cached(query).unwrap();
+
+    query
+        .query_map((), |row| {
+            let pokemon = row.get(2)?;
+            let submission = match row.get::<_, Option<String>>(3)? {
+                Some(other) => Submission::Breed {
+                    mother: pokemon,
+                    other,
+                },
+                None => Submission::Search { pokemon },
+            };
+            Ok(HistoryEntry {
+                history_id: row.get(0)?,
+                submission,
+                result: row.get(4)?,
+                created_at: row.get(5)?,
+            })
+        })
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap()
+}
+
+/// The last `limit` different names submitted in a field, most recent
+/// first.
+pub fn get_recent(db: &Database, field: HistoryField, limit: usize) -> Vec<String> {
+    let query = match field {
+        HistoryField::Search => {
+            r#"
+            SELECT pokemon FROM history
+                WHERE kind = 'Search'
+                GROUP BY pokemon COLLATE NOCASE
+                ORDER BY max(history_id) DESC
+                LIMIT ?1
+            "#
+        }
+        HistoryField::Mother => {
+            r#"
+            SELECT pokemon FROM history
+                WHERE kind = 'Breed'
+                GROUP BY pokemon COLLATE NOCASE
+                ORDER BY max(history_id) DESC
+                LIMIT ?1
+            "#
+        }
+        HistoryField::Other => {
+            r#"
+            SELECT other FROM history
+                WHERE kind = 'Breed'
+                GROUP BY other COLLATE NOCASE
+                ORDER BY max(history_id) DESC
+                LIMIT ?1
+            "#
+        }
+    };
+    let mut query = db.connection.prepare_cached(query).unwrap();
+
+    query
+        .query_map((limit as i64,), |row| row.get::<_, String>(0))
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap()
+}
+
+pub fn clear_history(db: &Database) -> Result<(), rusqlite::Error> {
+    db.connection.execute("DELETE FROM history", ()).map(|_| ())
+}
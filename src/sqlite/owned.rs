@@ -0,0 +1,315 @@
+//! The Pokemon we own, kept in boxes of [`BOX_SIZE`] slots like in the games.
+//!
+
+use std::str::FromStr;
+
+use rusqlite::types::{ToSql, Type};
+use rusqlite::{OptionalExtension, named_params};
+use serde::{Deserialize, Serialize};
+
+use super::Database;
+use crate::breeding::{Gender, Ivs, Nature};
+
+// The `owned_pokemon` table checks boxes and slots against these too, so
+// they have to change along with it.
+pub const BOX_SIZE: u32 = 30;
+pub const BOX_COUNT: u32 = 16;
+
+/// The balls a Pokemon can be caught in, the usual one first.
+pub const BALLS: &[&str] = &[
+    "Poke Ball",
+    "Great Ball",
+    "Ultra Ball",
+    "Master Ball",
+    "Premier Ball",
+    "Luxury Ball",
+    "Heal Ball",
+    "Dive Ball",
+    "Net Ball",
+    "Nest Ball",
+    "Repeat Ball",
+    "Timer Ball",
+    "Dusk Ball",
+    "Quick Ball",
+    "Fast Ball",
+    "Level Ball",
+    "Lure Ball",
+    "Heavy Ball",
+    "Love Ball",
+    "Friend Ball",
+    "Moon Ball",
+    "Sport Ball",
+    "Safari Ball",
+    "Dream Ball",
+    "Beast Ball",
+    "Cherish Ball",
+];
+
+/// The most moves a Pokemon can know.
+pub const MAX_MOVES: usize = 4;
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct OwnedPokemon {
+    /// `None` until it is saved.
+    pub owned_id: Option<i64>,
+    pub pokemon_id: i64,
+    /// The name of its species, in English.
+    pub species: String,
+    pub nickname: Option<String>,
+    pub gender: Gender,
+    pub nature: Nature,
+    pub ability: Option<String>,
+    pub ball: String,
+    pub ivs: Ivs,
+    /// The names of the moves it knows, in English.
+    pub moves: Vec<String>,
+    /// Which box it is in, from 1.
+    pub box_number: u32,
+    pub slot: u32,
+}
+
+impl OwnedPokemon {
+    /// Its nickname, or its species if it has none.
+    pub fn name(&self) -> &str {
+        self.nickname.as_deref().unwrap_or(&self.species)
+    }
+
+    /// What matters for breeding, like `Adamant, 31/31/31/0/31/31, Poke Ball`.
+    pub fn summary(&self) -> String {
+        format!("{}, {}, {}", <&str>::from(self.nature), self.ivs, self.ball)
+    }
+}
+
+const OWNED_QUERY: &str = r#"
+    SELECT owned_pokemon.owned_id, owned_pokemon.pokemon_id, pokemon.name,
+           nickname, gender, nature, ability, ball,
+           iv_hp, iv_attack, iv_defense, iv_special_attack, iv_special_defense, iv_speed,
+           box, slot
+        FROM owned_pokemon
+            JOIN pokemon ON owned_pokemon.pokemon_id = pokemon.pokemon_id
+"#;
+
+/// Reads a row of [`OWNED_QUERY`], without its moves.
+fn owned_from_row(row: &rusqlite::Row) -> Result<OwnedPokemon, rusqlite::Error> {
+    Ok(OwnedPokemon {
+        owned_id: row.get(0)?,
+        pokemon_id: row.get(1)?,
+        species: row.get(2)?,
+        nickname: row.get(3)?,
+        gender: parse_column(row, 4)?,
+        nature: parse_column(row, 5)?,
+        ability: row.get(6)?,
+        ball: row.get(7)?,
+        ivs: ivs_from_row(row, 8)?,
+        moves: Vec::new(),
+        box_number: row.get(14)?,
+        slot: row.get(15)?,
+    })
+}
+
+/// Reads six IV columns in [`Stat`](crate::breeding::Stat) order, starting at `first`.
+pub(super) fn ivs_from_row(row: &rusqlite::Row, first: usize) -> Result<Ivs, rusqlite::Error> {
+    let mut ivs = Ivs::default();
+    for (i, iv) in ivs.0.iter_mut().enumerate() {
+        *iv = row.get(first + i)?;
+    }
+    Ok(ivs)
+}
+
+/// Reads a text column into anything that parses from it, like a [`Nature`].
+pub(super) fn parse_column<T>(row: &rusqlite::Row, i: usize) -> Result<T, rusqlite::Error>
+where
+    T: FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    row.get::<_, String>(i)?
+        .parse()
+        .map_err(|e| rusqlite::Error::FromSqlConversionFailure(i, Type::Text, Box::new(e)))
+}
+
+fn with_moves(db: &Database, mut owned: OwnedPokemon) -> OwnedPokemon {
+    let query = r#"
+        SELECT move.name
+            FROM owned_pokemon_move
+                JOIN move ON owned_pokemon_move.move_id = move.move_id
+            WHERE owned_pokemon_move.owned_id = :owned_id
+            ORDER BY owned_pokemon_move.slot
+    "#;
+    let mut query = db.connection.prepare_cached(query).unwrap();
+
+    owned.moves = query
+        .query_map((owned.owned_id,), |row| row.get::<_, String>(0))
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    owned
+}
+
+/// The Pokemon in box `box_number`, by slot.
+pub fn get_box(db: &Database, box_number: u32) -> Vec<OwnedPokemon> {
+    let query = format!("{OWNED_QUERY} WHERE box = :box ORDER BY slot");
+    let mut query = db.connection.prepare_cached(&query).unwrap();
+
+    query
+        .query_map((box_number,), owned_from_row)
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap()
+        .into_iter()
+        .map(|owned| with_moves(db, owned))
+        .collect()
+}
+
+/// Every Pokemon in every box.
+pub fn get_all_owned(db: &Database) -> Vec<OwnedPokemon> {
+    let query = format!("{OWNED_QUERY} ORDER BY box, slot");
+    let mut query = db.connection.prepare_cached(&query).unwrap();
+
+    query
+        .query_map((), owned_from_row)
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap()
+        .into_iter()
+        .map(|owned| with_moves(db, owned))
+        .collect()
+}
+
+/// The first Pokemon, by box and slot, whose [`OwnedPokemon::name`] is `name`.
+pub fn find_owned(db: &Database, name: &str) -> Option<OwnedPokemon> {
+    let query = format!(
+        "{OWNED_QUERY}
+            WHERE nickname = :name OR (nickname IS NULL AND pokemon.name = :name)
+            ORDER BY box, slot
+            LIMIT 1"
+    );
+    let mut query = db.connection.prepare_cached(&query).unwrap();
+
+    query
+        .query_one((name,), owned_from_row)
+        .optional()
+        .unwrap()
+        .map(|owned| with_moves(db, owned))
+}
+
+pub fn get_owned(db: &Database, owned_id: i64) -> Option<OwnedPokemon> {
+    let query = format!("{OWNED_QUERY} WHERE owned_pokemon.owned_id = :owned_id");
+    let mut query = db.connection.prepare_cached(&query).unwrap();
+
+    query
+        .query_one((owned_id,), owned_from_row)
+        .optional()
+        .unwrap()
+        .map(|owned| with_moves(db, owned))
+}
+
+/// The English name of a move, found by its name in any language.
+pub fn find_move(db: &Database, name: &str) -> Option<String> {
+    let query = r#"
+            SELECT move.name
+                FROM move
+                WHERE move.name = :name
+            UNION
+            SELECT move.name
+                FROM move_name
+                    JOIN move ON move_name.move_id = move.move_id
+                WHERE move_name.name = :name
+            LIMIT 1
+        "#;
+    let mut query = db.connection.prepare_cached(query).unwrap();
+
+    query
+        .query_one((name,), |row| row.get::<_, String>(0))
+        .optional()
+        .unwrap()
+}
+
+/// Saves a Pokemon into its slot, returning its id.
+///
+/// Its moves have to be English names, like [`find_move`] gives.
+pub fn save_owned(db: &Database, owned: &OwnedPokemon) -> Result<i64, rusqlite::Error> {
+    db.record(&format!("save {}", owned.name()), |db| {
+        let gender: &str = owned.gender.into();
+        let nature: &str = owned.nature.into();
+        let values: &[(&str, &dyn ToSql)] = named_params! {
+            ":pokemon_id": owned.pokemon_id,
+            ":nickname": owned.nickname,
+            ":gender": gender,
+            ":nature": nature,
+            ":ability": owned.ability,
+            ":ball": owned.ball,
+            ":iv_hp": owned.ivs.0[0],
+            ":iv_attack": owned.ivs.0[1],
+            ":iv_defense": owned.ivs.0[2],
+            ":iv_special_attack": owned.ivs.0[3],
+            ":iv_special_defense": owned.ivs.0[4],
+            ":iv_speed": owned.ivs.0[5],
+            ":box": owned.box_number,
+            ":slot": owned.slot,
+        };
+
+        let owned_id = match owned.owned_id {
+            None => {
+                db.connection.execute(
+                    r#"
+                    INSERT INTO owned_pokemon (
+                        pokemon_id, nickname, gender, nature, ability, ball,
+                        iv_hp, iv_attack, iv_defense, iv_special_attack, iv_special_defense, iv_speed,
+                        box, slot
+                    ) VALUES (
+                        :pokemon_id, :nickname, :gender, :nature, :ability, :ball,
+                        :iv_hp, :iv_attack, :iv_defense, :iv_special_attack, :iv_special_defense, :iv_speed,
+                        :box, :slot
+                    )
+                    "#,
+                    values,
+                )?;
+                db.connection.last_insert_rowid()
+            }
+            Some(owned_id) => {
+                db.connection.execute(
+                    r#"
+                    UPDATE owned_pokemon SET
+                        pokemon_id = :pokemon_id, nickname = :nickname, gender = :gender,
+                        nature = :nature, ability = :ability, ball = :ball,
+                        iv_hp = :iv_hp, iv_attack = :iv_attack, iv_defense = :iv_defense,
+                        iv_special_attack = :iv_special_attack,
+                        iv_special_defense = :iv_special_defense, iv_speed = :iv_speed,
+                        box = :box, slot = :slot
+                    WHERE owned_id = :owned_id
+                    "#,
+                    [values, named_params! { ":owned_id": owned_id }].concat().as_slice(),
+                )?;
+                owned_id
+            }
+        };
+
+        db.connection.execute(
+            "DELETE FROM owned_pokemon_move WHERE owned_id = ?1",
+            (owned_id,),
+        )?;
+        for (slot, name) in owned.moves.iter().take(MAX_MOVES).enumerate() {
+            db.connection.execute(
+                r#"
+                INSERT INTO owned_pokemon_move (owned_id, slot, move_id)
+                    SELECT ?1, ?2, move_id FROM move WHERE name = ?3
+                "#,
+                (owned_id, slot, name),
+            )?;
+        }
+
+        Ok(owned_id)
+    })
+}
+
+/// Lets a Pokemon go, emptying its slot.
+pub fn release_owned(db: &Database, owned: &OwnedPokemon) -> Result<(), rusqlite::Error> {
+    db.record(&format!("release {}", owned.name()), |db| {
+        db.connection
+            .execute(
+                "DELETE FROM owned_pokemon WHERE owned_id = ?1",
+                (owned.owned_id,),
+            )
+            .map(|_| ())
+    })
+}
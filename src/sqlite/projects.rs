@@ -0,0 +1,438 @@
+//! Breeding projects: a Pokemon we are trying to breed, the parents we are
+//! breeding it from, and the eggs that have hatched so far.
+//!
+
+use rusqlite::named_params;
+use rusqlite::types::ToSql;
+use serde::{Deserialize, Serialize};
+use strum::{EnumIter, EnumString, IntoEnumIterator, IntoStaticStr};
+
+use super::Database;
+use super::owned::{OwnedPokemon, ivs_from_row, parse_column};
+use crate::breeding::{Gender, Ivs, Nature, Stat};
+
+#[derive(
+    Serialize,
+    Deserialize,
+    EnumIter,
+    EnumString,
+    IntoStaticStr,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Hash,
+    Debug,
+)]
+pub enum ProjectStatus {
+    #[default]
+    Planning,
+    Breeding,
+    Done,
+    Abandoned,
+}
+
+/// The Pokemon a project is trying to breed.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Project {
+    /// `None` until it is saved.
+    pub project_id: Option<i64>,
+    pub name: String,
+    pub pokemon_id: i64,
+    /// The name of its species, in English.
+    pub species: String,
+    /// `None` for any nature.
+    pub nature: Option<Nature>,
+    pub ability: Option<String>,
+    pub ball: Option<String>,
+    /// The least each stat should have, 0 for any.
+    pub ivs: Ivs,
+    /// The names of the egg moves it should know, in English.
+    pub egg_moves: Vec<String>,
+    pub status: ProjectStatus,
+}
+
+/// An egg hatched for a project.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Egg {
+    /// `None` until it is recorded.
+    pub egg_id: Option<i64>,
+    pub gender: Gender,
+    pub nature: Nature,
+    pub ability: Option<String>,
+    pub ball: String,
+    pub ivs: Ivs,
+    /// The names of the moves it knows, in English.
+    pub moves: Vec<String>,
+}
+
+impl From<&OwnedPokemon> for Egg {
+    fn from(owned: &OwnedPokemon) -> Self {
+        Self {
+            egg_id: None,
+            gender: owned.gender,
+            nature: owned.nature,
+            ability: owned.ability.clone(),
+            ball: owned.ball.clone(),
+            ivs: owned.ivs,
+            moves: owned.moves.clone(),
+        }
+    }
+}
+
+/// One thing a project wants, which a Pokemon either has or doesn't.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub enum Goal {
+    Nature(Nature),
+    Ability(String),
+    Ball(String),
+    /// At least this IV in a stat.
+    Iv(Stat, u8),
+    EggMove(String),
+}
+
+impl Goal {
+    pub fn met_by(
+        &self,
+        nature: Nature,
+        ability: Option<&str>,
+        ball: &str,
+        ivs: &Ivs,
+        moves: &[String],
+    ) -> bool {
+        match self {
+            Goal::Nature(wanted) => nature == *wanted,
+            Goal::Ability(wanted) => ability.is_some_and(|a| a.eq_ignore_ascii_case(wanted)),
+            Goal::Ball(wanted) => ball == wanted,
+            Goal::Iv(stat, least) => ivs.get(*stat) >= *least,
+            Goal::EggMove(wanted) => moves.contains(wanted),
+        }
+    }
+}
+
+impl Egg {
+    pub fn meets(&self, goal: &Goal) -> bool {
+        goal.met_by(
+            self.nature,
+            self.ability.as_deref(),
+            &self.ball,
+            &self.ivs,
+            &self.moves,
+        )
+    }
+}
+
+impl Project {
+    /// Everything the project wants, one goal each.
+    pub fn goals(&self) -> Vec<Goal> {
+        let mut goals = Vec::new();
+        goals.extend(self.nature.map(Goal::Nature));
+        goals.extend(self.ability.clone().map(Goal::Ability));
+        goals.extend(self.ball.clone().map(Goal::Ball));
+        goals.extend(
+            Stat::iter()
+                .filter(|stat| self.ivs.get(*stat) > 0)
+                .map(|stat| Goal::Iv(stat, self.ivs.get(stat))),
+        );
+        goals.extend(self.egg_moves.iter().cloned().map(Goal::EggMove));
+        goals
+    }
+
+    /// The egg that meets the most goals, and the goals it misses.
+    pub fn best_egg<'a>(&self, eggs: &'a [Egg]) -> Option<(&'a Egg, Vec<Goal>)> {
+        let goals = self.goals();
+        eggs.iter()
+            .map(|egg| {
+                let missed = goals
+                    .iter()
+                    .filter(|goal| !egg.meets(goal))
+                    .cloned()
+                    .collect::<Vec<_>>();
+                (egg, missed)
+            })
+            .min_by_key(|(_, missed)| missed.len())
+    }
+}
+
+const PROJECT_QUERY: &str = r#"
+    SELECT project.project_id, project.name, project.pokemon_id, pokemon.name,
+           nature, ability, ball,
+           iv_hp, iv_attack, iv_defense, iv_special_attack, iv_special_defense, iv_speed,
+           status
+        FROM project
+            JOIN pokemon ON project.pokemon_id = pokemon.pokemon_id
+"#;
+
+/// Names of the moves linked to `id` through `query`, which selects them by `?1`.
+fn move_names(db: &Database, query: &str, id: Option<i64>) -> Vec<String> {
+    let mut query = db.connection.prepare_cached(query).unwrap();
+
+    query
+        .query_map((id,), |row| row.get::<_, String>(0))
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap()
+}
+
+/// Every project, oldest first.
+pub fn get_projects(db: &Database) -> Vec<Project> {
+    let query = format!("{PROJECT_QUERY} ORDER BY project.project_id");
+    let mut query = db.connection.prepare_cached(&query).unwrap();
+
+    let projects = query
+        .query_map((), |row| {
+            Ok(Project {
+                project_id: row.get(0)?,
+                name: row.get(1)?,
+                pokemon_id: row.get(2)?,
+                species: row.get(3)?,
+                nature: row
+                    .get::<_, Option<String>>(4)?
+                    .map(|_| parse_column(row, 4))
+                    .transpose()?,
+                ability: row.get(5)?,
+                ball: row.get(6)?,
+                ivs: ivs_from_row(row, 7)?,
+                egg_moves: Vec::new(),
+                status: parse_column(row, 13)?,
+            })
+        })
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    projects
+        .into_iter()
+        .map(|mut project| {
+            project.egg_moves = move_names(
+                db,
+                r#"
+                SELECT move.name
+                    FROM project_target_move
+                        JOIN move ON project_target_move.move_id = move.move_id
+                    WHERE project_target_move.project_id = ?1
+                    ORDER BY move.name
+                "#,
+                project.project_id,
+            );
+            project
+        })
+        .collect()
+}
+
+/// Saves a project, returning its id.
+///
+/// Its egg moves have to be English names, like [`find_move`](super::find_move) gives.
+pub fn save_project(db: &Database, project: &Project) -> Result<i64, rusqlite::Error> {
+    db.record(&format!("save project {}", project.name), |db| {
+        let nature = project.nature.map(<&str>::from);
+        let status: &str = project.status.into();
+        let values: &[(&str, &dyn ToSql)] = named_params! {
+            ":name": project.name,
+            ":pokemon_id": project.pokemon_id,
+            ":nature": nature,
+            ":ability": project.ability,
+            ":ball": project.ball,
+            ":iv_hp": project.ivs.0[0],
+            ":iv_attack": project.ivs.0[1],
+            ":iv_defense": project.ivs.0[2],
+            ":iv_special_attack": project.ivs.0[3],
+            ":iv_special_defense": project.ivs.0[4],
+            ":iv_speed": project.ivs.0[5],
+            ":status": status,
+        };
+
+        let project_id = match project.project_id {
+            None => {
+                db.connection.execute(
+                    r#"
+                    INSERT INTO project (
+                        name, pokemon_id, nature, ability, ball,
+                        iv_hp, iv_attack, iv_defense, iv_special_attack, iv_special_defense, iv_speed,
+                        status
+                    ) VALUES (
+                        :name, :pokemon_id, :nature, :ability, :ball,
+                        :iv_hp, :iv_attack, :iv_defense, :iv_special_attack, :iv_special_defense, :iv_speed,
+                        :status
+                    )
+                    "#,
+                    values,
+                )?;
+                db.connection.last_insert_rowid()
+            }
+            Some(project_id) => {
+                db.connection.execute(
+                    r#"
+                    UPDATE project SET
+                        name = :name, pokemon_id = :pokemon_id, nature = :nature,
+                        ability = :ability, ball = :ball,
+                        iv_hp = :iv_hp, iv_attack = :iv_attack, iv_defense = :iv_defense,
+                        iv_special_attack = :iv_special_attack,
+                        iv_special_defense = :iv_special_defense, iv_speed = :iv_speed,
+                        status = :status
+                    WHERE project_id = :project_id
+                    "#,
+                    [values, named_params! { ":project_id": project_id }].concat().as_slice(),
+                )?;
+                project_id
+            }
+        };
+
+        db.connection.execute(
+            "DELETE FROM project_target_move WHERE project_id = ?1",
+            (project_id,),
+        )?;
+        for name in &project.egg_moves {
+            db.connection.execute(
+                r#"
+                INSERT OR IGNORE INTO project_target_move (project_id, move_id)
+                    SELECT ?1, move_id FROM move WHERE name = ?2
+                "#,
+                (project_id, name),
+            )?;
+        }
+
+        Ok(project_id)
+    })
+}
+
+/// Deletes a project, with its eggs. Its parents stay in their boxes.
+pub fn delete_project(db: &Database, project: &Project) -> Result<(), rusqlite::Error> {
+    db.record(&format!("delete project {}", project.name), |db| {
+        db.connection
+            .execute(
+                "DELETE FROM project WHERE project_id = ?1",
+                (project.project_id,),
+            )
+            .map(|_| ())
+    })
+}
+
+/// The ids of the Pokemon in the boxes that a project is breeding from.
+pub fn get_project_parents(db: &Database, project_id: i64) -> Vec<i64> {
+    let query = "SELECT owned_id FROM project_parent WHERE project_id = ?1 ORDER BY owned_id";
+    let mut query = db.connection.prepare_cached(query).unwrap();
+
+    query
+        .query_map((project_id,), |row| row.get::<_, i64>(0))
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap()
+}
+
+/// Adds a Pokemon from the boxes to a project's parents, or takes it away.
+pub fn link_parent(
+    db: &Database,
+    project_id: i64,
+    owned: &OwnedPokemon,
+    linked: bool,
+) -> Result<(), rusqlite::Error> {
+    let (description, query) = if linked {
+        (
+            "link",
+            "INSERT OR IGNORE INTO project_parent (project_id, owned_id) VALUES (?1, ?2)",
+        )
+    } else {
+        (
+            "unlink",
+            "DELETE FROM project_parent WHERE project_id = ?1 AND owned_id = ?2",
+        )
+    };
+
+    db.record(&format!("{description} {}", owned.name()), |db| {
+        db.connection
+            .execute(query, (project_id, owned.owned_id))
+            .map(|_| ())
+    })
+}
+
+/// The eggs hatched for a project, oldest first.
+pub fn get_eggs(db: &Database, project_id: i64) -> Vec<Egg> {
+    let query = r#"
+        SELECT egg_id, gender, nature, ability, ball,
+               iv_hp, iv_attack, iv_defense, iv_special_attack, iv_special_defense, iv_speed
+            FROM project_egg
+            WHERE project_id = ?1
+            ORDER BY egg_id
+    "#;
+    let mut query = db.connection.prepare_cached(query).unwrap();
+
+    let eggs = query
+        .query_map((project_id,), |row| {
+            Ok(Egg {
+                egg_id: row.get(0)?,
+                gender: parse_column(row, 1)?,
+                nature: parse_column(row, 2)?,
+                ability: row.get(3)?,
+                ball: row.get(4)?,
+                ivs: ivs_from_row(row, 5)?,
+                moves: Vec::new(),
+            })
+        })
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    eggs.into_iter()
+        .map(|mut egg| {
+            egg.moves = move_names(
+                db,
+                r#"
+                SELECT move.name
+                    FROM project_egg_move
+                        JOIN move ON project_egg_move.move_id = move.move_id
+                    WHERE project_egg_move.egg_id = ?1
+                    ORDER BY move.name
+                "#,
+                egg.egg_id,
+            );
+            egg
+        })
+        .collect()
+}
+
+/// Records an egg hatched for a project, returning its id.
+pub fn record_egg(db: &Database, project_id: i64, egg: &Egg) -> Result<i64, rusqlite::Error> {
+    db.record("record egg", |db| {
+        let gender: &str = egg.gender.into();
+        let nature: &str = egg.nature.into();
+        db.connection.execute(
+            r#"
+            INSERT INTO project_egg (
+                project_id, gender, nature, ability, ball,
+                iv_hp, iv_attack, iv_defense, iv_special_attack, iv_special_defense, iv_speed
+            ) VALUES (
+                :project_id, :gender, :nature, :ability, :ball,
+                :iv_hp, :iv_attack, :iv_defense, :iv_special_attack, :iv_special_defense, :iv_speed
+            )
+            "#,
+            named_params! {
+                ":project_id": project_id,
+                ":gender": gender,
+                ":nature": nature,
+                ":ability": egg.ability,
+                ":ball": egg.ball,
+                ":iv_hp": egg.ivs.0[0],
+                ":iv_attack": egg.ivs.0[1],
+                ":iv_defense": egg.ivs.0[2],
+                ":iv_special_attack": egg.ivs.0[3],
+                ":iv_special_defense": egg.ivs.0[4],
+                ":iv_speed": egg.ivs.0[5],
+            },
+        )?;
+        let egg_id = db.connection.last_insert_rowid();
+
+        for name in &egg.moves {
+            db.connection.execute(
+                r#"
+                INSERT OR IGNORE INTO project_egg_move (egg_id, move_id)
+                    SELECT ?1, move_id FROM move WHERE name = ?2
+                "#,
+                (egg_id, name),
+            )?;
+        }
+
+        Ok(egg_id)
+    })
+}
@@ -0,0 +1,234 @@
+//! Queries about Pokemon shared by the menus and the command line.
+//!
+
+use rusqlite::OptionalExtension;
+use serde::{Deserialize, Serialize};
+
+use super::Database;
+
+pub fn exists(db: &Database, name: &str) -> bool {
+    get_pokemon_id(db, name).is_some()
+}
+
+/// Finds a Pokemon by its name in any language.
+pub fn get_pokemon_id(db: &Database, name: &str) -> Option<i64> {
+    let query = r#"
+            SELECT pokemon.pokemon_id
+                FROM pokemon
+                WHERE pokemon.name = :name
+            UNION
+            SELECT pokemon_name.pokemon_id
+                FROM pokemon_name
+                WHERE pokemon_name.name = :name
+            LIMIT 1
+        "#;
+    let mut query = db.connection.prepare_cached(query).unwrap();
+
+    query
+        .query_one((name,), |row| row.get::<_, i64>(0))
+        .optional()
+        .unwrap()
+}
+
+pub fn get_groups(db: &Database, name: &str) -> Vec<String> {
+    let query = r#"
+            SELECT egg_group.name
+                FROM pokemon_egg_group
+                    JOIN egg_group ON pokemon_egg_group.egg_group_id = egg_group.egg_group_id
+                WHERE pokemon_egg_group.pokemon_id = :pokemon_id
+        "#;
+    let mut query = db.connection.prepare_cached(query).unwrap();
+
+    query
+        .query_map((get_pokemon_id(db, name),), |row| row.get::<_, String>(0))
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap()
+}
+
+/// The egg moves of a Pokemon, named in `language`.
+pub fn get_egg_moves(db: &Database, name: &str, language: &str) -> Vec<String> {
+    let query = r#"
+        SELECT COALESCE(move_name.name, move.name)
+            FROM pokemon_move
+                JOIN move ON pokemon_move.move_id = move.move_id
+                LEFT JOIN move_name ON move.move_id = move_name.move_id
+                    AND move_name.language = :language
+            WHERE pokemon_move.pokemon_id = :pokemon_id
+              AND pokemon_move.method = 'egg'
+    "#;
+    let mut query = db.connection.prepare_cached(query).unwrap();
+
+    query
+        .query_map(
+            rusqlite::named_params! {
+                ":pokemon_id": get_pokemon_id(db, name),
+                ":language": language,
+            },
+            |row| row.get::<_, String>(0),
+        )
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap()
+}
+
+/// A Pokemon's base stats, by [`Stat`](crate::breeding::Stat), if they are
+/// known.
+pub fn get_base_stats(db: &Database, name: &str) -> Option<[u8; 6]> {
+    let query = r#"
+        SELECT hp, attack, defense, special_attack, special_defense, speed
+            FROM pokemon_base_stats
+            WHERE pokemon_id = :pokemon_id
+    "#;
+    let mut query = db.connection.prepare_cached(query).unwrap();
+
+    query
+        .query_one((get_pokemon_id(db, name),), |row| {
+            Ok([
+                row.get(0)?,
+                row.get(1)?,
+                row.get(2)?,
+                row.get(3)?,
+                row.get(4)?,
+                row.get(5)?,
+            ])
+        })
+        .optional()
+        .unwrap()
+}
+
+/// A Pokemon's abilities, other than its hidden ability.
+pub fn get_abilities(db: &Database, name: &str) -> Vec<String> {
+    let query = r#"
+        SELECT ability
+            FROM pokemon_ability
+            WHERE pokemon_id = :pokemon_id
+            ORDER BY slot
+    "#;
+    let mut query = db.connection.prepare_cached(query).unwrap();
+
+    query
+        .query_map((get_pokemon_id(db, name),), |row| row.get::<_, String>(0))
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap()
+}
+
+/// A Pokemon's name and typing, as shown in lists of results.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct PokemonEntry {
+    pub pokemon_id: i64,
+    pub name: String,
+    pub primary_type: String,
+    pub secondary_type: Option<String>,
+}
+
+/// A Pokemon, named in any language, with its name in `language`.
+pub fn get_pokemon_entry(db: &Database, name: &str, language: &str) -> Option<PokemonEntry> {
+    let query = r#"
+        SELECT
+            pokemon.pokemon_id,
+            COALESCE(pokemon_name.name, pokemon.name),
+            pokemon.primary_type,
+            pokemon.secondary_type
+            FROM pokemon
+                LEFT JOIN pokemon_name ON pokemon.pokemon_id = pokemon_name.pokemon_id
+                    AND pokemon_name.language = :language
+            WHERE pokemon.pokemon_id = :pokemon_id
+    "#;
+    let mut query = db.connection.prepare_cached(query).unwrap();
+
+    query
+        .query_one(
+            rusqlite::named_params! {
+                ":pokemon_id": get_pokemon_id(db, name),
+                ":language": language,
+            },
+            |row| {
+                Ok(PokemonEntry {
+                    pokemon_id: row.get(0)?,
+                    name: row.get(1)?,
+                    primary_type: row.get(2)?,
+                    secondary_type: row.get(3)?,
+                })
+            },
+        )
+        .optional()
+        .unwrap()
+}
+
+/// Every Pokemon, named in `language`.
+pub fn get_all_pokemon(db: &Database, language: &str) -> Vec<PokemonEntry> {
+    let query = r#"
+        SELECT
+            pokemon.pokemon_id,
+            COALESCE(pokemon_name.name, pokemon.name),
+            pokemon.primary_type,
+            pokemon.secondary_type
+            FROM pokemon
+                LEFT JOIN pokemon_name ON pokemon.pokemon_id = pokemon_name.pokemon_id
+                    AND pokemon_name.language = :language
+            ORDER BY pokemon.pokemon_id
+    "#;
+    let mut query = db.connection.prepare_cached(query).unwrap();
+
+    query
+        .query_map(
+            rusqlite::named_params! {
+                ":language": language,
+            },
+            |row| {
+                Ok(PokemonEntry {
+                    pokemon_id: row.get(0)?,
+                    name: row.get(1)?,
+                    primary_type: row.get(2)?,
+                    secondary_type: row.get(3)?,
+                })
+            },
+        )
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap()
+}
+
+/// Every Pokemon sharing an egg group with this one, named in `language`.
+pub fn get_pokemon_compatible(db: &Database, name: &str, language: &str) -> Vec<PokemonEntry> {
+    let query = r#"
+        SELECT
+            pokemon.pokemon_id,
+            COALESCE(pokemon_name.name, pokemon.name),
+            pokemon.primary_type,
+            pokemon.secondary_type
+            FROM pokemon
+                JOIN pokemon_egg_group ON pokemon.pokemon_id = pokemon_egg_group.pokemon_id
+                JOIN (
+                    SELECT peg.egg_group_id AS id
+                        FROM pokemon_egg_group AS peg
+                        WHERE peg.pokemon_id = :pokemon_id
+                ) AS breedable ON pokemon_egg_group.egg_group_id = breedable.id
+                LEFT JOIN pokemon_name ON pokemon.pokemon_id = pokemon_name.pokemon_id
+                    AND pokemon_name.language = :language
+            GROUP BY pokemon.pokemon_id
+            ORDER BY pokemon.pokemon_id
+    "#;
+    let mut query = db.connection.prepare_cached(query).unwrap();
+
+    query
+        .query_map(
+            rusqlite::named_params! {
+                ":pokemon_id": get_pokemon_id(db, name),
+                ":language": language,
+            },
+            |row| {
+                Ok(PokemonEntry {
+                    pokemon_id: row.get(0)?,
+                    name: row.get(1)?,
+                    primary_type: row.get(2)?,
+                    secondary_type: row.get(3)?,
+                })
+            },
+        )
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap()
+}
@@ -0,0 +1,543 @@
+//! Creating, editing and deleting rows of the data tables.
+//!
+//! Values are given as text, like they are typed in, and checked against the
+//! same rules as the schema first, so a mistake can be pinned to its field.
+//! Anything the checks miss is caught by SQLite and pinned the same way.
+
+use rusqlite::types::{Value, ValueRef};
+use rusqlite::{ErrorCode, OptionalExtension, ffi, params_from_iter};
+use strum::EnumIter;
+
+use super::Database;
+
+/// A row, with a value for every field of its table.
+pub type Row = Vec<Value>;
+
+/// The tables that can be edited.
+#[derive(EnumIter, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Table {
+    Pokemon,
+    EggGroup,
+    Move,
+    PokemonEggGroup,
+    PokemonMove,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Field {
+    pub column: &'static str,
+    pub kind: FieldKind,
+    /// Whether it is part of the primary key.
+    pub key: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FieldKind {
+    /// A positive id, which is picked by SQLite when left empty if `generated`.
+    Id {
+        generated: bool,
+    },
+    Text {
+        required: bool,
+    },
+    /// One of a fixed set of values.
+    Choice(&'static [&'static str]),
+    /// A positive number, or empty for `NULL`.
+    OptionalPositive,
+    /// A row of another table, given by its id or name.
+    Reference(Table),
+}
+
+impl Field {
+    const fn primary(column: &'static str, kind: FieldKind) -> Self {
+        Self {
+            column,
+            kind,
+            key: true,
+        }
+    }
+
+    const fn new(column: &'static str, kind: FieldKind) -> Self {
+        Self {
+            column,
+            kind,
+            key: false,
+        }
+    }
+
+    fn parse(&self, db: &Database, text: &str, inserting: bool) -> Result<Value, Problem> {
+        if text.is_empty() {
+            return match self.kind {
+                FieldKind::Id { generated: true } if inserting => Ok(Value::Null),
+                FieldKind::Text { required: false } | FieldKind::OptionalPositive => {
+                    Ok(Value::Null)
+                }
+                _ => Err(Problem::Required),
+            };
+        }
+
+        match self.kind {
+            FieldKind::Id { .. } | FieldKind::OptionalPositive => positive(text),
+            FieldKind::Text { .. } => Ok(Value::Text(text.into())),
+            FieldKind::Choice(choices) => choices
+                .iter()
+                .find(|choice| choice.eq_ignore_ascii_case(text))
+                .map(|choice| Value::Text(choice.to_string()))
+                .ok_or(Problem::NotAChoice(choices)),
+            FieldKind::Reference(table) => table
+                .find(db, text)
+                .map(Value::Integer)
+                .ok_or(Problem::Missing(table)),
+        }
+    }
+}
+
+pub const MOVE_CATEGORIES: &[&str] = &["Status", "Physical", "Special"];
+
+const POKEMON_FIELDS: &[Field] = &[
+    Field::primary("pokemon_id", FieldKind::Id { generated: false }),
+    Field::new("name", FieldKind::Text { required: true }),
+    Field::new("primary_type", FieldKind::Text { required: true }),
+    Field::new("secondary_type", FieldKind::Text { required: false }),
+];
+
+const EGG_GROUP_FIELDS: &[Field] = &[
+    Field::primary("egg_group_id", FieldKind::Id { generated: true }),
+    Field::new("name", FieldKind::Text { required: true }),
+];
+
+const MOVE_FIELDS: &[Field] = &[
+    Field::primary("move_id", FieldKind::Id { generated: false }),
+    Field::new("name", FieldKind::Text { required: true }),
+    Field::new("type", FieldKind::Text { required: true }),
+    Field::new("category", FieldKind::Choice(MOVE_CATEGORIES)),
+    Field::new("power", FieldKind::OptionalPositive),
+    Field::new("accuracy", FieldKind::OptionalPositive),
+];
+
+const POKEMON_EGG_GROUP_FIELDS: &[Field] = &[
+    Field::primary("pokemon_id", FieldKind::Reference(Table::Pokemon)),
+    Field::primary("egg_group_id", FieldKind::Reference(Table::EggGroup)),
+];
+
+const POKEMON_MOVE_FIELDS: &[Field] = &[
+    Field::primary("pokemon_id", FieldKind::Reference(Table::Pokemon)),
+    Field::primary("move_id", FieldKind::Reference(Table::Move)),
+    Field::new("method", FieldKind::Text { required: true }),
+];
+
+impl Table {
+    pub fn name(self) -> &'static str {
+        match self {
+            Table::Pokemon => "pokemon",
+            Table::EggGroup => "egg_group",
+            Table::Move => "move",
+            Table::PokemonEggGroup => "pokemon_egg_group",
+            Table::PokemonMove => "pokemon_move",
+        }
+    }
+
+    pub fn fields(self) -> &'static [Field] {
+        match self {
+            Table::Pokemon => POKEMON_FIELDS,
+            Table::EggGroup => EGG_GROUP_FIELDS,
+            Table::Move => MOVE_FIELDS,
+            Table::PokemonEggGroup => POKEMON_EGG_GROUP_FIELDS,
+            Table::PokemonMove => POKEMON_MOVE_FIELDS,
+        }
+    }
+
+    /// The values of `row` that make up its primary key.
+    pub fn key_of(self, row: &[Value]) -> Vec<Value> {
+        self.fields()
+            .iter()
+            .zip(row)
+            .filter(|(field, _)| field.key)
+            .map(|(_, value)| value.clone())
+            .collect()
+    }
+
+    fn columns(self, key: bool) -> impl Iterator<Item = &'static str> {
+        self.fields()
+            .iter()
+            .filter(move |field| !key || field.key)
+            .map(|field| field.column)
+    }
+
+    /// `column = ?n AND ...` over the primary key, numbered from `first`.
+    fn key_condition(self, first: usize) -> String {
+        self.columns(true)
+            .enumerate()
+            .map(|(i, column)| format!("{column} = ?{}", first + i))
+            .collect::<Vec<_>>()
+            .join(" AND ")
+    }
+
+    /// Finds a row of a table with names by its id or name.
+    fn find(self, db: &Database, id_or_name: &str) -> Option<i64> {
+        let id = self.fields()[0].column;
+        let query = format!(
+            "SELECT {id} FROM {} WHERE {id} = ?1 OR name = ?1 LIMIT 1",
+            self.name()
+        );
+        let mut query = db.connection.prepare_cached(&query).unwrap();
+
+        query
+            .query_one((id_or_name,), |row| row.get::<_, i64>(0))
+            .optional()
+            .unwrap()
+    }
+
+    /// Checks typed in values, returning them as they are stored.
+    fn parse(self, db: &Database, texts: &[String], inserting: bool) -> Result<Row, EditError> {
+        let mut values = Vec::new();
+        let mut problems = Vec::new();
+
+        for (i, (field, text)) in self.fields().iter().zip(texts).enumerate() {
+            match field.parse(db, text.trim(), inserting) {
+                Ok(value) => values.push(value),
+                Err(problem) => problems.push((i, problem)),
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(values)
+        } else {
+            Err(EditError::Fields(problems))
+        }
+    }
+}
+
+fn positive(text: &str) -> Result<Value, Problem> {
+    match text.parse::<i64>() {
+        Ok(number) if number > 0 => Ok(Value::Integer(number)),
+        Ok(_) => Err(Problem::NotPositive),
+        Err(_) => Err(Problem::NotANumber),
+    }
+}
+
+/// What is wrong with the value of a field.
+#[derive(Clone, PartialEq, Debug)]
+pub enum Problem {
+    Required,
+    NotANumber,
+    NotPositive,
+    NotAChoice(&'static [&'static str]),
+    /// There is no row of the table by that id or name.
+    Missing(Table),
+    /// Another row already has it.
+    Taken,
+    /// A `CHECK` constraint failed, with SQLite's message.
+    Check(String),
+    /// Other rows still refer to it, like owned Pokemon of a species.
+    InUse,
+}
+
+#[derive(Debug)]
+pub enum EditError {
+    /// Problems with fields, by their index into [`Table::fields`].
+    Fields(Vec<(usize, Problem)>),
+    /// Anything that isn't down to one field, like a foreign key.
+    Sqlite(rusqlite::Error),
+}
+
+impl From<rusqlite::Error> for EditError {
+    fn from(e: rusqlite::Error) -> Self {
+        EditError::Sqlite(e)
+    }
+}
+
+impl EditError {
+    /// Pins a constraint that failed in `table` on the field it names.
+    fn from_sqlite(table: Table, e: rusqlite::Error) -> Self {
+        let rusqlite::Error::SqliteFailure(failure, Some(message)) = &e else {
+            return EditError::Sqlite(e);
+        };
+        if failure.code != ErrorCode::ConstraintViolation {
+            return EditError::Sqlite(e);
+        }
+
+        let problem = match failure.extended_code {
+            ffi::SQLITE_CONSTRAINT_UNIQUE | ffi::SQLITE_CONSTRAINT_PRIMARYKEY => Problem::Taken,
+            ffi::SQLITE_CONSTRAINT_NOTNULL => Problem::Required,
+            ffi::SQLITE_CONSTRAINT_CHECK => Problem::Check(message.clone()),
+            // Foreign keys that refuse deletes don't name a column, so it is
+            // down to the row, which is pinned on its key.
+            ffi::SQLITE_CONSTRAINT_FOREIGNKEY => {
+                return match table.fields().iter().position(|field| field.key) {
+                    Some(field) => EditError::Fields(vec![(field, Problem::InUse)]),
+                    None => EditError::Sqlite(e),
+                };
+            }
+            _ => return EditError::Sqlite(e),
+        };
+
+        // Like "UNIQUE constraint failed: pokemon.name" or
+        // "CHECK constraint failed: power > 0 OR power IS NULL".
+        let detail = message.split_once(':').map_or("", |(_, detail)| detail);
+        let words = detail
+            .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .collect::<Vec<_>>();
+
+        match table
+            .fields()
+            .iter()
+            .position(|field| words.contains(&field.column))
+        {
+            Some(field) => EditError::Fields(vec![(field, problem)]),
+            None => EditError::Sqlite(e),
+        }
+    }
+}
+
+/// How a value is shown, `NULL` included.
+pub fn value_text(value: ValueRef) -> String {
+    match value {
+        ValueRef::Null => "NULL".into(),
+        ValueRef::Integer(i) => i.to_string(),
+        ValueRef::Real(f) => f.to_string(),
+        ValueRef::Text(text) => String::from_utf8_lossy(text).into(),
+        ValueRef::Blob(blob) => format!("<{} bytes>", blob.len()),
+    }
+}
+
+/// How a value is typed in, which leaves `NULL` empty.
+pub fn value_input(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        value => value_text(value.into()),
+    }
+}
+
+/// Every row of `table`, in primary key order.
+pub fn get_rows(db: &Database, table: Table) -> Vec<Row> {
+    let query = format!(
+        "SELECT {} FROM {} ORDER BY {}",
+        table.columns(false).collect::<Vec<_>>().join(", "),
+        table.name(),
+        table.columns(true).collect::<Vec<_>>().join(", "),
+    );
+    let mut query = db.connection.prepare_cached(&query).unwrap();
+    let count = table.fields().len();
+
+    query
+        .query_map((), |row| {
+            (0..count).map(|i| row.get::<_, Value>(i)).collect()
+        })
+        .unwrap()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap()
+}
+
+/// Adds a row from typed in values, returning its primary key.
+pub fn insert_row(db: &Database, table: Table, texts: &[String]) -> Result<Vec<Value>, EditError> {
+    let values = table.parse(db, texts, true)?;
+
+    // Generated ids are left out for SQLite to pick.
+    let (columns, given): (Vec<_>, Vec<_>) = table
+        .fields()
+        .iter()
+        .zip(&values)
+        .filter(|(field, value)| {
+            !(matches!(field.kind, FieldKind::Id { generated: true }) && **value == Value::Null)
+        })
+        .map(|(field, value)| (field.column, value))
+        .unzip();
+
+    let query = format!(
+        "INSERT INTO {} ({}) VALUES ({})",
+        table.name(),
+        columns.join(", "),
+        (1..=columns.len())
+            .map(|i| format!("?{i}"))
+            .collect::<Vec<_>>()
+            .join(", "),
+    );
+
+    db.record(&format!("insert into {}", table.name()), |db| {
+        db.connection
+            .execute(&query, params_from_iter(given))
+            .map_err(|e| EditError::from_sqlite(table, e))?;
+
+        Ok(table
+            .key_of(&values)
+            .into_iter()
+            .map(|value| match value {
+                Value::Null => Value::Integer(db.connection.last_insert_rowid()),
+                value => value,
+            })
+            .collect())
+    })
+}
+
+/// Replaces the row with primary key `key` with typed in values, returning
+/// its new primary key.
+pub fn update_row(
+    db: &Database,
+    table: Table,
+    key: &[Value],
+    texts: &[String],
+) -> Result<Vec<Value>, EditError> {
+    let values = table.parse(db, texts, false)?;
+
+    let query = format!(
+        "UPDATE {} SET {} WHERE {}",
+        table.name(),
+        table
+            .columns(false)
+            .enumerate()
+            .map(|(i, column)| format!("{column} = ?{}", i + 1))
+            .collect::<Vec<_>>()
+            .join(", "),
+        table.key_condition(values.len() + 1),
+    );
+
+    db.record(&format!("update {}", table.name()), |db| {
+        db.connection
+            .execute(&query, params_from_iter(values.iter().chain(key)))
+            .map_err(|e| EditError::from_sqlite(table, e))
+    })?;
+
+    Ok(table.key_of(&values))
+}
+
+/// Deletes the row with primary key `key`, and through the foreign keys,
+/// everything that refers to it, unless something that refers to it
+/// refuses, like owned Pokemon of a species.
+pub fn delete_row(db: &Database, table: Table, key: &[Value]) -> Result<(), EditError> {
+    let query = format!(
+        "DELETE FROM {} WHERE {}",
+        table.name(),
+        table.key_condition(1)
+    );
+
+    db.record(&format!("delete from {}", table.name()), |db| {
+        db.connection
+            .execute(&query, params_from_iter(key))
+            .map(|_| ())
+            .map_err(|e| EditError::from_sqlite(table, e))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::breeding::{Gender, Ivs, Nature};
+    use crate::sqlite::{OwnedPokemon, get_all_owned, save_owned};
+
+    fn texts(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    /// The problems of a failed edit, by field.
+    fn problems<T: std::fmt::Debug>(result: Result<T, EditError>) -> Vec<(usize, Problem)> {
+        match result {
+            Err(EditError::Fields(problems)) => problems,
+            result => panic!("expected problems with fields, got {result:?}"),
+        }
+    }
+
+    #[test]
+    fn out_of_range_values() {
+        let db = Database::open(None).unwrap();
+
+        let result = insert_row(
+            &db,
+            Table::Move,
+            &texts(&["0", "Splash", "Water", "Magic", "-5", "100"]),
+        );
+        assert_eq!(
+            problems(result),
+            [
+                (0, Problem::NotPositive),
+                (3, Problem::NotAChoice(MOVE_CATEGORIES)),
+                (4, Problem::NotPositive),
+            ]
+        );
+
+        let result = update_row(
+            &db,
+            Table::Pokemon,
+            &[Value::Integer(1)],
+            &texts(&["1", "", "Grass", "many"]),
+        );
+        assert_eq!(problems(result), [(1, Problem::Required)]);
+    }
+
+    #[test]
+    fn unknown_references() {
+        let db = Database::open(None).unwrap();
+
+        let result = insert_row(
+            &db,
+            Table::PokemonEggGroup,
+            &texts(&["Bulbasaur", "Dragon"]),
+        );
+        assert_eq!(problems(result), [(1, Problem::Missing(Table::EggGroup))]);
+
+        let result = insert_row(&db, Table::PokemonMove, &texts(&["999", "Amnesia", "egg"]));
+        assert_eq!(problems(result), [(0, Problem::Missing(Table::Pokemon))]);
+    }
+
+    #[test]
+    fn taken_keys_and_names() {
+        let db = Database::open(None).unwrap();
+
+        // Caught by SQLite, and pinned on the field its message names.
+        let result = insert_row(
+            &db,
+            Table::Pokemon,
+            &texts(&["1", "Bulbasaur 2", "Grass", ""]),
+        );
+        assert_eq!(problems(result), [(0, Problem::Taken)]);
+
+        let result = update_row(
+            &db,
+            Table::Pokemon,
+            &[Value::Integer(4)],
+            &texts(&["4", "bulbasaur", "Fire", ""]),
+        );
+        assert_eq!(problems(result), [(1, Problem::Taken)]);
+
+        let result = insert_row(&db, Table::PokemonEggGroup, &texts(&["Bulbasaur", "Grass"]));
+        assert_eq!(problems(result), [(0, Problem::Taken)]);
+    }
+
+    #[test]
+    fn owned_species_are_not_deleted() {
+        let db = Database::open(None).unwrap();
+        save_owned(
+            &db,
+            &OwnedPokemon {
+                owned_id: None,
+                pokemon_id: 1,
+                species: "Bulbasaur".into(),
+                nickname: None,
+                gender: Gender::Female,
+                nature: Nature::Modest,
+                ability: None,
+                ball: "Poke Ball".into(),
+                ivs: Ivs::default(),
+                moves: Vec::new(),
+                box_number: 1,
+                slot: 0,
+            },
+        )
+        .unwrap();
+
+        let result = delete_row(&db, Table::Pokemon, &[Value::Integer(1)]);
+        assert_eq!(problems(result), [(0, Problem::InUse)]);
+        assert!(Table::Pokemon.find(&db, "Bulbasaur").is_some());
+        assert_eq!(get_all_owned(&db).len(), 1);
+
+        // Species nobody owns still go, with their egg groups.
+        delete_row(&db, Table::Pokemon, &[Value::Integer(4)]).unwrap();
+        assert!(Table::Pokemon.find(&db, "Charmander").is_none());
+        assert!(
+            get_rows(&db, Table::PokemonEggGroup)
+                .iter()
+                .all(|row| row[0] != Value::Integer(4))
+        );
+    }
+}